@@ -0,0 +1,107 @@
+use super::Color;
+use super::linear::{
+    linear_to_srgb,
+    srgb_to_linear
+};
+
+/// The types of color vision deficiency that can be simulated with Color::simulate.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum ColorDeficiency {
+    /// No functional red cones (red-blind)
+    Protanopia,
+    /// No functional green cones (green-blind), the most common type
+    Deuteranopia,
+    /// No functional blue cones (blue-blind)
+    Tritanopia,
+    /// No color vision at all, only lightness can be perceived
+    Achromatopsia
+}
+
+impl ColorDeficiency {
+
+    /// The simulation matrices (in linear RGB) from Machado, Oliveira and Fernandes (2009), at full severity.
+    fn get_matrix(&self) -> [[f32; 3]; 3] {
+        match self {
+            ColorDeficiency::Protanopia => [
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998]
+            ], ColorDeficiency::Deuteranopia => [
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881]
+            ], ColorDeficiency::Tritanopia => [
+                [1.255528, -0.076749, -0.178779],
+                [-0.078411, 0.930809, 0.147602],
+                [0.004733, 0.691367, 0.303900]
+            ], ColorDeficiency::Achromatopsia => [
+                [0.2126, 0.7152, 0.0722],
+                [0.2126, 0.7152, 0.0722],
+                [0.2126, 0.7152, 0.0722]
+            ]
+        }
+    }
+}
+
+impl Color {
+
+    /// Approximates how this color would be perceived by someone with the given color vision deficiency. This can be
+    /// used to preview and validate palettes: colors that should be distinguishable should still be distinguishable
+    /// after simulating each kind of deficiency. The alpha component is preserved.
+    pub fn simulate(&self, kind: ColorDeficiency) -> Color {
+        let linear = [
+            srgb_to_linear(self.get_red_float()),
+            srgb_to_linear(self.get_green_float()),
+            srgb_to_linear(self.get_blue_float())
+        ];
+        let matrix = kind.get_matrix();
+
+        let transform = |row: [f32; 3]| {
+            let value = row[0] * linear[0] + row[1] * linear[1] + row[2] * linear[2];
+            (linear_to_srgb(value.clamp(0.0, 1.0)) * 255.0).round() as u8
+        };
+
+        Color::from_rgba(transform(matrix[0]), transform(matrix[1]), transform(matrix[2]), self.get_alpha())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const ALL_KINDS: [ColorDeficiency; 4] = [
+        ColorDeficiency::Protanopia, ColorDeficiency::Deuteranopia, ColorDeficiency::Tritanopia, ColorDeficiency::Achromatopsia
+    ];
+
+    #[test]
+    fn test_gray_is_preserved(){
+        for kind in &ALL_KINDS {
+            for gray in &[Color::BLACK, Color::WHITE, Color::from_rgba(128, 128, 128, 77)] {
+                let simulated = gray.simulate(*kind);
+                assert!((simulated.get_red() as i32 - gray.get_red() as i32).abs() <= 1);
+                assert!((simulated.get_green() as i32 - gray.get_green() as i32).abs() <= 1);
+                assert!((simulated.get_blue() as i32 - gray.get_blue() as i32).abs() <= 1);
+                assert_eq!(gray.get_alpha(), simulated.get_alpha());
+            }
+        }
+    }
+
+    #[test]
+    fn test_red_green_confusion(){
+        // Red and green are hard to distinguish for protanopes and deuteranopes, so their hues should get close
+        for kind in &[ColorDeficiency::Protanopia, ColorDeficiency::Deuteranopia] {
+            let red = Color::from_rgb(200, 60, 40).simulate(*kind);
+            let green = Color::from_rgb(110, 120, 40).simulate(*kind);
+            assert!((red.get_red() as i32 - green.get_red() as i32).abs() < 40);
+            assert!((red.get_green() as i32 - green.get_green() as i32).abs() < 40);
+        }
+    }
+
+    #[test]
+    fn test_achromatopsia(){
+        let simulated = Color::RED.simulate(ColorDeficiency::Achromatopsia);
+        assert_eq!(simulated.get_red(), simulated.get_green());
+        assert_eq!(simulated.get_green(), simulated.get_blue());
+    }
+}
@@ -0,0 +1,147 @@
+use std::ops::{
+    Add,
+    Div,
+    Mul,
+    Sub
+};
+
+use super::Color;
+
+/// A color with floating point components. Unlike Color, the components are not limited to the range [0.0, 1.0],
+/// which makes this type suitable for HDR values and for intermediate results of accumulating operations (like
+/// averaging, blurring or gradient math) that would lose too much precision when rounded to u8 after each step.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct ColorF {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32
+}
+
+impl ColorF {
+
+    pub const BLACK: ColorF = ColorF::from_rgb(0.0, 0.0, 0.0);
+    pub const WHITE: ColorF = ColorF::from_rgb(1.0, 1.0, 1.0);
+    pub const TRANSPARENT: ColorF = ColorF::new(0.0, 0.0, 0.0, 0.0);
+
+    pub const fn new(r: f32, g: f32, b: f32, a: f32) -> ColorF {
+        ColorF {
+            r,
+            g,
+            b,
+            a
+        }
+    }
+
+    pub const fn from_rgb(r: f32, g: f32, b: f32) -> ColorF {
+        Self::new(r, g, b, 1.0)
+    }
+
+    /// Converts this color to a Color. Components outside the range [0.0, 1.0] will be clamped and the
+    /// components will be rounded to the nearest u8 value.
+    pub fn to_color(&self) -> Color {
+        Color::from_rgba(f32_to_u8(self.r), f32_to_u8(self.g), f32_to_u8(self.b), f32_to_u8(self.a))
+    }
+
+    /// Returns a copy of this color with all components clamped to the range [0.0, 1.0].
+    pub fn clamped(&self) -> ColorF {
+        ColorF::new(clamp(self.r), clamp(self.g), clamp(self.b), clamp(self.a))
+    }
+
+    /// Linearly interpolates between this color (when progress is 0.0) and the other color (when progress is 1.0).
+    pub fn lerp(&self, other: ColorF, progress: f32) -> ColorF {
+        *self + (other - *self) * progress
+    }
+
+    pub fn to_array(&self) -> [f32; 4] {
+        [self.r, self.g, self.b, self.a]
+    }
+}
+
+impl From<Color> for ColorF {
+
+    fn from(color: Color) -> ColorF {
+        ColorF::new(color.get_red_float(), color.get_green_float(), color.get_blue_float(), color.get_alpha_float())
+    }
+}
+
+impl From<ColorF> for Color {
+
+    fn from(color: ColorF) -> Color {
+        color.to_color()
+    }
+}
+
+impl Add for ColorF {
+
+    type Output = ColorF;
+
+    fn add(self, other: ColorF) -> ColorF {
+        ColorF::new(self.r + other.r, self.g + other.g, self.b + other.b, self.a + other.a)
+    }
+}
+
+impl Sub for ColorF {
+
+    type Output = ColorF;
+
+    fn sub(self, other: ColorF) -> ColorF {
+        ColorF::new(self.r - other.r, self.g - other.g, self.b - other.b, self.a - other.a)
+    }
+}
+
+impl Mul<f32> for ColorF {
+
+    type Output = ColorF;
+
+    fn mul(self, factor: f32) -> ColorF {
+        ColorF::new(self.r * factor, self.g * factor, self.b * factor, self.a * factor)
+    }
+}
+
+impl Div<f32> for ColorF {
+
+    type Output = ColorF;
+
+    fn div(self, divisor: f32) -> ColorF {
+        ColorF::new(self.r / divisor, self.g / divisor, self.b / divisor, self.a / divisor)
+    }
+}
+
+fn clamp(value: f32) -> f32 {
+    value.clamp(0.0, 1.0)
+}
+
+fn f32_to_u8(value: f32) -> u8 {
+    (clamp(value) * 255.0).round() as u8
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_conversions(){
+        for color in &[Color::BLACK, Color::WHITE, Color::RED, Color::TRANSPARENT, Color::from_rgba(1, 100, 201, 254)] {
+            assert_eq!(*color, ColorF::from(*color).to_color());
+        }
+        assert_eq!(Color::from_rgba(255, 0, 128, 255), ColorF::new(1.5, -0.5, 0.5, 1.0).to_color());
+    }
+
+    #[test]
+    fn test_average(){
+        // Averaging in u8 space would round 1 and 2 to either 1 or 2 at every step
+        let sum = ColorF::from(Color::from_rgb(1, 1, 1)) + ColorF::from(Color::from_rgb(2, 2, 2));
+        let average = sum / 2.0;
+        assert!((average.r * 255.0 - 1.5).abs() < 0.0001);
+        assert_eq!(Color::from_rgb(2, 2, 2), (sum / 2.0 + ColorF::from_rgb(0.5, 0.5, 0.5) / 255.0).to_color());
+    }
+
+    #[test]
+    fn test_lerp(){
+        assert_eq!(ColorF::new(0.5, 0.5, 0.5, 1.0), ColorF::BLACK.lerp(ColorF::WHITE, 0.5));
+        assert_eq!(ColorF::BLACK, ColorF::BLACK.lerp(ColorF::WHITE, 0.0));
+        assert_eq!(ColorF::new(0.5, 0.25, 0.0, 0.5), ColorF::new(1.0, 0.5, 0.0, 1.0).lerp(ColorF::TRANSPARENT, 0.5));
+    }
+}
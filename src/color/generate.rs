@@ -0,0 +1,105 @@
+use super::Color;
+use super::hsl::Hsl;
+
+/// The fraction of the color wheel between two consecutive colors of the golden ratio sequence
+const GOLDEN_RATIO_CONJUGATE: f64 = 0.618_033_988_749_894_9;
+
+impl Color {
+
+    /// Gets the color at the given index of a sequence of visually distinct colors. Consecutive colors have hues that
+    /// are the golden angle apart, so any number of consecutive colors is spread evenly over the color wheel. This is
+    /// useful for dynamically created items, like chart series or the cursors of other users.
+    pub fn golden_ratio_sequence(index: usize) -> Color {
+        // Use f64, because an f32 doesn't have enough precision for the fraction once the index gets large
+        let hue = ((index as f64 * GOLDEN_RATIO_CONJUGATE).fract() * 360.0) as f32;
+        Hsl {
+            hue,
+            saturation: 0.65,
+            lightness: 0.5
+        }.to_color(255)
+    }
+}
+
+/// Generates random pastel colors (with a random hue, but with a high lightness and moderate saturation). The
+/// generator is seeded, so the same seed will always produce the same colors. This makes it possible to give an item
+/// (like a user) a stable color by using its ID as seed.
+#[derive(Clone,Debug)]
+pub struct PastelGenerator {
+    state: u64
+}
+
+impl PastelGenerator {
+
+    pub fn new(seed: u64) -> PastelGenerator {
+        PastelGenerator {
+            state: seed
+        }
+    }
+
+    pub fn next_color(&mut self) -> Color {
+        let random = self.next_random();
+        let hue = (random & 0xFFFF) as f32 / 65536.0 * 360.0;
+        let saturation = 0.55 + ((random >> 16) & 0xFF) as f32 / 255.0 * 0.25;
+        let lightness = 0.75 + ((random >> 24) & 0xFF) as f32 / 255.0 * 0.1;
+        Hsl {
+            hue,
+            saturation,
+            lightness
+        }.to_color(255)
+    }
+
+    /// The SplitMix64 algorithm: fast, tiny, and good enough for picking colors
+    fn next_random(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+impl Iterator for PastelGenerator {
+
+    type Item = Color;
+
+    fn next(&mut self) -> Option<Color> {
+        Some(self.next_color())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_golden_ratio_sequence(){
+        let colors: Vec<Color> = (0..10).map(Color::golden_ratio_sequence).collect();
+        for (index, color) in colors.iter().enumerate() {
+            for other in &colors[index + 1..] {
+                assert!(color.delta_e(*other) > 10.0, "{} and {} are too similar", color, other);
+            }
+        }
+        assert_eq!(Color::golden_ratio_sequence(3), Color::golden_ratio_sequence(3));
+
+        // Colors with large indices must still differ from their neighbours
+        let far: Vec<Color> = (1_000_000..1_000_010).map(Color::golden_ratio_sequence).collect();
+        for (index, color) in far.iter().enumerate() {
+            for other in &far[index + 1..] {
+                assert!(color.delta_e(*other) > 10.0, "{} and {} are too similar", color, other);
+            }
+        }
+    }
+
+    #[test]
+    fn test_pastel_generator(){
+        let first: Vec<Color> = PastelGenerator::new(42).take(5).collect();
+        let second: Vec<Color> = PastelGenerator::new(42).take(5).collect();
+        assert_eq!(first, second);
+        assert_ne!(first, PastelGenerator::new(43).take(5).collect::<Vec<Color>>());
+
+        for color in PastelGenerator::new(1).take(100) {
+            assert!(Hsl::from_color(color).lightness >= 0.74);
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use super::{
+    Color,
+    ColorF
+};
+
+/// A color stop of a Gradient: the color the gradient has at the given position.
+#[derive(Clone,Copy)]
+pub struct GradientStop {
+    /// The position of this stop, in the range [0.0, 1.0]
+    pub position: f32,
+    pub color: Color
+}
+
+/// A one-dimensional color gradient, defined by a list of color stops. Between two stops, the colors are linearly
+/// interpolated. Before the first stop and after the last stop, the color of that stop is used.
+#[derive(Clone)]
+pub struct Gradient {
+    stops: Vec<GradientStop>
+}
+
+impl Gradient {
+
+    /// Creates a gradient with the given stops. The stops don't need to be sorted, positions outside the range
+    /// [0.0, 1.0] will be clamped, and stops whose position is NaN will be ignored.
+    pub fn new(mut stops: Vec<GradientStop>) -> Gradient {
+        stops.retain(|stop| !stop.position.is_nan());
+        for stop in &mut stops {
+            stop.position = stop.position.clamp(0.0, 1.0);
+        }
+        stops.sort_by(|a, b| a.position.total_cmp(&b.position));
+        Gradient {
+            stops
+        }
+    }
+
+    /// Creates a gradient that goes from the start color (at position 0.0) to the end color (at position 1.0).
+    pub fn between(start: Color, end: Color) -> Gradient {
+        Gradient::new(vec![
+            GradientStop { position: 0.0, color: start },
+            GradientStop { position: 1.0, color: end }
+        ])
+    }
+
+    pub fn get_stops(&self) -> &[GradientStop] {
+        &self.stops
+    }
+
+    /// Gets the color of this gradient at the given position. If this gradient doesn't have any stops,
+    /// Color::TRANSPARENT is returned.
+    pub fn color_at(&self, position: f32) -> Color {
+        self.color_at_float(position).to_color()
+    }
+
+    fn color_at_float(&self, position: f32) -> ColorF {
+        let first = match self.stops.first() {
+            Some(first) => first,
+            None => return ColorF::TRANSPARENT
+        };
+        if position <= first.position {
+            return ColorF::from(first.color);
+        }
+
+        for window in self.stops.windows(2) {
+            let (left, right) = (window[0], window[1]);
+            if position <= right.position {
+                let span = right.position - left.position;
+                let progress = if span > 0.0 { (position - left.position) / span } else { 1.0 };
+                return ColorF::from(left.color).lerp(ColorF::from(right.color), progress);
+            }
+        }
+
+        ColorF::from(self.stops[self.stops.len() - 1].color)
+    }
+
+    /// Samples this gradient at the centers of width pixels and returns the result as RGBA bytes (4 bytes per pixel).
+    /// The result can be uploaded as a width x 1 texture and used as lookup table in a shader.
+    pub fn rasterize(&self, width: u32) -> Vec<u8> {
+        let mut pixels = Vec::with_capacity(4 * width as usize);
+        for x in 0..width {
+            let color = self.color_at((x as f32 + 0.5) / width as f32);
+            pixels.push(color.get_red());
+            pixels.push(color.get_green());
+            pixels.push(color.get_blue());
+            pixels.push(color.get_alpha());
+        }
+        pixels
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_color_at(){
+        let gradient = Gradient::new(vec![
+            GradientStop { position: 1.0, color: Color::BLUE },
+            GradientStop { position: 0.25, color: Color::RED },
+            GradientStop { position: 0.5, color: Color::GREEN }
+        ]);
+        assert_eq!(Color::RED, gradient.color_at(0.0));
+        assert_eq!(Color::RED, gradient.color_at(0.25));
+        assert_eq!(Color::from_rgb(128, 128, 0), gradient.color_at(0.375));
+        assert_eq!(Color::GREEN, gradient.color_at(0.5));
+        assert_eq!(Color::BLUE, gradient.color_at(1.0));
+        assert_eq!(Color::BLUE, gradient.color_at(2.0));
+
+        assert_eq!(Color::TRANSPARENT, Gradient::new(Vec::new()).color_at(0.5));
+    }
+
+    #[test]
+    fn test_invalid_positions() {
+        let gradient = Gradient::new(vec![
+            GradientStop { position: f32::NAN, color: Color::GREEN },
+            GradientStop { position: f32::INFINITY, color: Color::BLUE },
+            GradientStop { position: -3.0, color: Color::RED }
+        ]);
+        assert_eq!(vec![0.0, 1.0], gradient.get_stops().iter().map(|stop| stop.position).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_rasterize(){
+        let gradient = Gradient::between(Color::BLACK, Color::from_rgba(255, 255, 255, 0));
+        assert_eq!(vec![32, 32, 32, 223, 96, 96, 96, 159, 159, 159, 159, 96, 223, 223, 223, 32], gradient.rasterize(4));
+        assert!(gradient.rasterize(0).is_empty());
+        assert_eq!(4 * 256, gradient.rasterize(256).len());
+    }
+}
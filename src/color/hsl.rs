@@ -0,0 +1,131 @@
+use super::Color;
+
+/// A color in the HSL color space. The hue is in degrees in the range [0.0, 360.0) and the saturation and lightness
+/// are in the range [0.0, 1.0].
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub(crate) struct Hsl {
+    pub hue: f32,
+    pub saturation: f32,
+    pub lightness: f32
+}
+
+impl Hsl {
+
+    pub fn from_color(color: Color) -> Hsl {
+        let r = color.get_red_float();
+        let g = color.get_green_float();
+        let b = color.get_blue_float();
+
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let lightness = (max + min) / 2.0;
+        let delta = max - min;
+
+        if delta == 0.0 {
+            return Hsl {
+                hue: 0.0,
+                saturation: 0.0,
+                lightness
+            };
+        }
+
+        let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+        let hue = if max == r {
+            60.0 * ((g - b) / delta)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+
+        Hsl {
+            hue: hue.rem_euclid(360.0),
+            saturation: saturation.min(1.0),
+            lightness
+        }
+    }
+
+    pub fn to_color(self, alpha: u8) -> Color {
+        let chroma = (1.0 - (2.0 * self.lightness - 1.0).abs()) * self.saturation;
+        let hue = self.hue.rem_euclid(360.0) / 60.0;
+        let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+
+        let (r, g, b) = match hue as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x)
+        };
+        let m = self.lightness - chroma / 2.0;
+
+        let to_u8 = |value: f32| ((value + m).clamp(0.0, 1.0) * 255.0).round() as u8;
+        Color::from_rgba(to_u8(r), to_u8(g), to_u8(b), alpha)
+    }
+}
+
+impl Color {
+
+    /// Rotates the hue of this color by the given number of degrees (in HSL space), keeping its saturation, lightness
+    /// and alpha. Negative values rotate in the opposite direction.
+    pub fn rotate_hue(&self, degrees: f32) -> Color {
+        let mut hsl = Hsl::from_color(*self);
+        hsl.hue = (hsl.hue + degrees).rem_euclid(360.0);
+        hsl.to_color(self.get_alpha())
+    }
+
+    /// Returns a copy of this color with the given saturation (in HSL space), which will be clamped to the range
+    /// [0.0, 1.0]. The hue, lightness and alpha are preserved.
+    pub fn with_saturation(&self, saturation: f32) -> Color {
+        let mut hsl = Hsl::from_color(*self);
+        hsl.saturation = saturation.clamp(0.0, 1.0);
+        hsl.to_color(self.get_alpha())
+    }
+
+    /// Flips the lightness of this color in HSL space (so the lightness becomes 1.0 minus the lightness), while keeping
+    /// its hue, saturation and alpha. Unlike inverted, this turns light blue into dark blue rather than dark orange,
+    /// which makes it useful to quickly derive dark theme colors from light theme colors.
+    pub fn inverted_luminance(&self) -> Color {
+        let mut hsl = Hsl::from_color(*self);
+        hsl.lightness = 1.0 - hsl.lightness;
+        hsl.to_color(self.get_alpha())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_round_trip(){
+        for color in &[Color::BLACK, Color::WHITE, Color::RED, Color::GREEN, Color::BLUE, Color::from_rgba(12, 200, 99, 3),
+                Color::from_rgb(250, 128, 114), Color::from_rgb(70, 130, 180)] {
+            assert_eq!(*color, Hsl::from_color(*color).to_color(color.get_alpha()));
+        }
+    }
+
+    #[test]
+    fn test_rotate_hue(){
+        assert_eq!(Color::GREEN, Color::RED.rotate_hue(120.0));
+        assert_eq!(Color::BLUE, Color::RED.rotate_hue(-120.0));
+        assert_eq!(Color::RED, Color::RED.rotate_hue(720.0));
+        assert_eq!(Color::from_rgba(0, 0, 255, 40), Color::from_rgba(0, 255, 0, 40).rotate_hue(120.0));
+        assert_eq!(Color::WHITE, Color::WHITE.rotate_hue(90.0));
+    }
+
+    #[test]
+    fn test_with_saturation(){
+        assert_eq!(Color::from_rgb(128, 128, 128), Color::from_rgb(191, 64, 64).with_saturation(0.0));
+        assert_eq!(Color::from_rgb(255, 0, 0), Color::from_rgb(191, 64, 64).with_saturation(1.0));
+        assert_eq!(Color::from_rgb(255, 0, 0), Color::from_rgb(191, 64, 64).with_saturation(5.0));
+    }
+
+    #[test]
+    fn test_inverted_luminance(){
+        assert_eq!(Color::WHITE, Color::BLACK.inverted_luminance());
+        assert_eq!(Color::RED, Color::RED.inverted_luminance());
+        assert_eq!(Color::from_rgba(0, 0, 128, 7), Color::from_rgba(127, 127, 255, 7).inverted_luminance());
+    }
+}
@@ -0,0 +1,90 @@
+use super::Color;
+use super::linear::srgb_to_linear;
+
+/// A color in the CIELAB color space (using the D65 white point). Euclidean distances in this color space are a
+/// much better approximation of perceived color differences than distances in RGB space.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub(crate) struct Lab {
+    pub l: f32,
+    pub a: f32,
+    pub b: f32
+}
+
+impl Lab {
+
+    pub fn from_color(color: Color) -> Lab {
+        let r = srgb_to_linear(color.get_red_float());
+        let g = srgb_to_linear(color.get_green_float());
+        let b = srgb_to_linear(color.get_blue_float());
+
+        let x = (0.4124 * r + 0.3576 * g + 0.1805 * b) / 0.95047;
+        let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+        let z = (0.0193 * r + 0.1192 * g + 0.9505 * b) / 1.08883;
+
+        let fx = lab_f(x);
+        let fy = lab_f(y);
+        let fz = lab_f(z);
+
+        Lab {
+            l: 116.0 * fy - 16.0,
+            a: 500.0 * (fx - fy),
+            b: 200.0 * (fy - fz)
+        }
+    }
+
+    pub fn distance_squared(&self, other: Lab) -> f32 {
+        let dl = self.l - other.l;
+        let da = self.a - other.a;
+        let db = self.b - other.b;
+        dl * dl + da * da + db * db
+    }
+}
+
+impl Color {
+
+    /// Computes the perceptual difference between this color and the other color, using the CIE76 formula (the
+    /// euclidean distance in CIELAB space). A difference of about 2.3 is the smallest difference most people can notice,
+    /// and the difference between black and white is 100. Alpha is ignored.
+    pub fn delta_e(&self, other: Color) -> f32 {
+        Lab::from_color(*self).distance_squared(Lab::from_color(other)).sqrt()
+    }
+}
+
+fn lab_f(t: f32) -> f32 {
+    let delta: f32 = 6.0 / 29.0;
+    if t > delta * delta * delta {
+        t.cbrt()
+    } else {
+        t / (3.0 * delta * delta) + 4.0 / 29.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn assert_lab(expected: (f32, f32, f32), actual: Lab) {
+        assert!((expected.0 - actual.l).abs() < 0.1, "Expected L {} but got {}", expected.0, actual.l);
+        assert!((expected.1 - actual.a).abs() < 0.1, "Expected a {} but got {}", expected.1, actual.a);
+        assert!((expected.2 - actual.b).abs() < 0.1, "Expected b {} but got {}", expected.2, actual.b);
+    }
+
+    #[test]
+    fn test_from_color(){
+        assert_lab((0.0, 0.0, 0.0), Lab::from_color(Color::BLACK));
+        assert_lab((100.0, 0.0, 0.0), Lab::from_color(Color::WHITE));
+        assert_lab((53.24, 80.09, 67.20), Lab::from_color(Color::RED));
+        assert_lab((87.73, -86.18, 83.18), Lab::from_color(Color::GREEN));
+        assert_lab((32.30, 79.19, -107.86), Lab::from_color(Color::BLUE));
+    }
+
+    #[test]
+    fn test_delta_e(){
+        assert!((Color::BLACK.delta_e(Color::WHITE) - 100.0).abs() < 0.1);
+        assert_eq!(0.0, Color::RED.delta_e(Color::RED));
+        assert_eq!(0.0, Color::RED.delta_e(Color::RED.with_alpha(0)));
+        assert!(Color::from_rgb(100, 100, 100).delta_e(Color::from_rgb(101, 100, 100)) < 2.3);
+        assert!(Color::RED.delta_e(Color::BLUE) > 100.0);
+    }
+}
@@ -0,0 +1,31 @@
+/// Converts an sRGB-encoded color component in the range [0.0, 1.0] to linear light.
+pub(crate) fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Converts a linear light color component in the range [0.0, 1.0] to sRGB encoding.
+pub(crate) fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_round_trip(){
+        for step in 0..=255 {
+            let value = step as f32 / 255.0;
+            assert!((value - linear_to_srgb(srgb_to_linear(value))).abs() < 0.0001);
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use super::{
+    Color,
+    ColorF
+};
+
+/// A 4x5 color transformation matrix, like the feColorMatrix filter of SVG. The rows compute the red, green, blue and
+/// alpha output components, and the columns are the weights of the red, green, blue and alpha input components,
+/// followed by a constant offset. All components are treated as floats in the range [0.0, 1.0].
+/// 
+/// Many color filters (grayscale, sepia, brightness, contrast...) can be expressed as such a matrix, and a sequence of
+/// filters can be combined into a single matrix with then.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct ColorMatrix {
+    pub rows: [[f32; 5]; 4]
+}
+
+impl ColorMatrix {
+
+    pub const IDENTITY: ColorMatrix = ColorMatrix::new([
+        [1.0, 0.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0, 0.0]
+    ]);
+
+    pub const fn new(rows: [[f32; 5]; 4]) -> ColorMatrix {
+        ColorMatrix {
+            rows
+        }
+    }
+
+    /// Converts colors to grayscale. An amount of 0.0 leaves colors unchanged and an amount of 1.0 fully converts them
+    /// to gray, just like the CSS grayscale() filter.
+    pub fn grayscale(amount: f32) -> ColorMatrix {
+        let a = 1.0 - amount.clamp(0.0, 1.0);
+        ColorMatrix::new([
+            [0.2126 + 0.7874 * a, 0.7152 - 0.7152 * a, 0.0722 - 0.0722 * a, 0.0, 0.0],
+            [0.2126 - 0.2126 * a, 0.7152 + 0.2848 * a, 0.0722 - 0.0722 * a, 0.0, 0.0],
+            [0.2126 - 0.2126 * a, 0.7152 - 0.7152 * a, 0.0722 + 0.9278 * a, 0.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0, 0.0]
+        ])
+    }
+
+    /// Gives colors a brownish, old-photo look. An amount of 0.0 leaves colors unchanged and an amount of 1.0 applies
+    /// the full effect, just like the CSS sepia() filter.
+    pub fn sepia(amount: f32) -> ColorMatrix {
+        let a = 1.0 - amount.clamp(0.0, 1.0);
+        ColorMatrix::new([
+            [0.393 + 0.607 * a, 0.769 - 0.769 * a, 0.189 - 0.189 * a, 0.0, 0.0],
+            [0.349 - 0.349 * a, 0.686 + 0.314 * a, 0.168 - 0.168 * a, 0.0, 0.0],
+            [0.272 - 0.272 * a, 0.534 - 0.534 * a, 0.131 + 0.869 * a, 0.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0, 0.0]
+        ])
+    }
+
+    /// Multiplies the red, green and blue components by the given factor, just like the CSS brightness() filter.
+    pub fn brightness(factor: f32) -> ColorMatrix {
+        ColorMatrix::new([
+            [factor, 0.0, 0.0, 0.0, 0.0],
+            [0.0, factor, 0.0, 0.0, 0.0],
+            [0.0, 0.0, factor, 0.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0, 0.0]
+        ])
+    }
+
+    /// Scales the distance of the red, green and blue components from 0.5 by the given factor, just like the CSS
+    /// contrast() filter. A factor of 0.0 turns every color into gray and a factor of 1.0 leaves colors unchanged.
+    pub fn contrast(factor: f32) -> ColorMatrix {
+        let offset = 0.5 - 0.5 * factor;
+        ColorMatrix::new([
+            [factor, 0.0, 0.0, 0.0, offset],
+            [0.0, factor, 0.0, 0.0, offset],
+            [0.0, 0.0, factor, 0.0, offset],
+            [0.0, 0.0, 0.0, 1.0, 0.0]
+        ])
+    }
+
+    /// Combines this matrix with the next matrix. Applying the result is equivalent to applying this matrix first and
+    /// then applying the next matrix (without clamping in between).
+    pub fn then(&self, next: &ColorMatrix) -> ColorMatrix {
+        let mut rows = [[0.0; 5]; 4];
+        for (row_index, row) in rows.iter_mut().enumerate() {
+            let next_row = next.rows[row_index];
+            for (column, value) in row.iter_mut().enumerate() {
+                *value = (0..4).map(|k| next_row[k] * self.rows[k][column]).sum();
+            }
+            row[4] += next_row[4];
+        }
+        ColorMatrix::new(rows)
+    }
+
+    /// Applies this matrix to the given color, without clamping the result.
+    pub fn apply_float(&self, color: ColorF) -> ColorF {
+        let input = [color.r, color.g, color.b, color.a];
+        let transform = |row: [f32; 5]| row[0] * input[0] + row[1] * input[1] + row[2] * input[2] + row[3] * input[3] + row[4];
+        ColorF::new(transform(self.rows[0]), transform(self.rows[1]), transform(self.rows[2]), transform(self.rows[3]))
+    }
+
+    /// Applies this matrix to the given color. The components of the result are clamped to the valid range.
+    pub fn apply(&self, color: Color) -> Color {
+        self.apply_float(ColorF::from(color)).to_color()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_identity(){
+        for color in &[Color::RED, Color::from_rgba(1, 2, 3, 4), Color::TRANSPARENT] {
+            assert_eq!(*color, ColorMatrix::IDENTITY.apply(*color));
+            assert_eq!(*color, ColorMatrix::grayscale(0.0).apply(*color));
+            assert_eq!(*color, ColorMatrix::sepia(0.0).apply(*color));
+            assert_eq!(*color, ColorMatrix::brightness(1.0).apply(*color));
+            assert_eq!(*color, ColorMatrix::contrast(1.0).apply(*color));
+        }
+    }
+
+    #[test]
+    fn test_presets(){
+        let gray = ColorMatrix::grayscale(1.0).apply(Color::RED.with_alpha(100));
+        assert_eq!(Color::from_rgba(54, 54, 54, 100), gray);
+
+        assert_eq!(Color::from_rgb(255, 255, 239), ColorMatrix::sepia(1.0).apply(Color::WHITE));
+        assert_eq!(Color::from_rgb(50, 100, 128), ColorMatrix::brightness(0.5).apply(Color::from_rgb(100, 200, 255)));
+        assert_eq!(Color::from_rgb(128, 128, 128), ColorMatrix::contrast(0.0).apply(Color::BLUE));
+    }
+
+    #[test]
+    fn test_then(){
+        let color = Color::from_rgb(200, 100, 50);
+        let combined = ColorMatrix::brightness(0.5).then(&ColorMatrix::contrast(2.0));
+        assert_eq!(ColorMatrix::contrast(2.0).apply(ColorMatrix::brightness(0.5).apply(color)), combined.apply(color));
+        assert_eq!(ColorMatrix::sepia(1.0), ColorMatrix::IDENTITY.then(&ColorMatrix::sepia(1.0)));
+        assert_eq!(ColorMatrix::sepia(1.0), ColorMatrix::sepia(1.0).then(&ColorMatrix::IDENTITY));
+    }
+}
@@ -0,0 +1,269 @@
+mod deficiency;
+mod float;
+mod generate;
+mod gradient;
+mod hsl;
+mod lab;
+mod linear;
+mod matrix;
+mod palette;
+mod parse;
+mod temperature;
+mod text_color_set;
+mod text_colors_builder;
+mod theme;
+pub use deficiency::*;
+pub use float::*;
+pub use generate::*;
+pub use gradient::*;
+pub use matrix::*;
+pub use palette::*;
+pub use parse::*;
+pub use text_color_set::*;
+pub use text_colors_builder::*;
+pub use theme::*;
+
+#[derive(Clone,Copy,PartialEq,Eq)]
+pub struct Color {
+    red: u8,
+    green: u8,
+    blue: u8,
+    alpha: u8
+}
+
+impl Color {
+
+    pub const BLACK: Color = Color::from_rgb(0, 0, 0);
+    pub const WHITE: Color = Color::from_rgb(255, 255, 255);
+
+    pub const RED: Color = Color::from_rgb(255, 0, 0);
+    pub const GREEN: Color = Color::from_rgb(0, 255, 0);
+    pub const BLUE: Color = Color::from_rgb(0, 0, 255);
+
+    pub const TRANSPARENT: Color = Color::from_rgba(0,0,0,0);
+
+    pub const fn from_rgb(red: u8, green: u8, blue: u8) -> Color {
+        Color {
+            red,
+            green,
+            blue,
+            alpha: 255
+        }
+    }
+
+    pub const fn from_rgba(red: u8, green: u8, blue: u8, alpha: u8) -> Color {
+        Color {
+            red,
+            green,
+            blue,
+            alpha
+        }
+    }
+
+    pub fn get_red_float(&self) -> f32 {
+        u8_to_f32(self.red)
+    }
+
+    pub fn get_green_float(&self) -> f32 {
+        u8_to_f32(self.green)
+    }
+
+    pub fn get_blue_float(&self) -> f32 {
+        u8_to_f32(self.blue)
+    }
+
+    pub fn get_alpha_float(&self) -> f32 {
+        u8_to_f32(self.alpha)
+    }
+
+    /// Gets the red, green, blue and alpha components of this color as floats in the range [0.0, 1.0], in that order.
+    /// This is the format expected by uniform4fv.
+    pub fn to_float_array(&self) -> [f32; 4] {
+        [self.get_red_float(), self.get_green_float(), self.get_blue_float(), self.get_alpha_float()]
+    }
+
+    /// Gets the red, green and blue components of this color as floats in the range [0.0, 1.0], in that order.
+    /// This is the format expected by uniform3fv.
+    pub fn to_rgb_float_array(&self) -> [f32; 3] {
+        [self.get_red_float(), self.get_green_float(), self.get_blue_float()]
+    }
+
+    pub fn get_red(&self) -> u8 {
+        self.red
+    }
+
+    pub fn get_green(&self) -> u8 {
+        self.green
+    }
+
+    pub fn get_blue(&self) -> u8 {
+        self.blue
+    }
+
+    pub fn get_alpha(&self) -> u8 {
+        self.alpha
+    }
+
+    pub const fn with_red(self, red: u8) -> Color {
+        Color {
+            red,
+            ..self
+        }
+    }
+
+    pub const fn with_green(self, green: u8) -> Color {
+        Color {
+            green,
+            ..self
+        }
+    }
+
+    pub const fn with_blue(self, blue: u8) -> Color {
+        Color {
+            blue,
+            ..self
+        }
+    }
+
+    /// Returns the complementary color: each of the red, green and blue components is replaced by 255 minus that
+    /// component. The alpha is preserved.
+    pub const fn inverted(&self) -> Color {
+        Color {
+            red: 255 - self.red,
+            green: 255 - self.green,
+            blue: 255 - self.blue,
+            alpha: self.alpha
+        }
+    }
+
+    /// Returns a copy of this color with the given alpha. Since this is a const fn, it can be used to define
+    /// (partially) transparent variants of color constants.
+    pub const fn with_alpha(self, alpha: u8) -> Color {
+        Color {
+            alpha,
+            ..self
+        }
+    }
+}
+
+/// Formats as #RRGGBBAA
+impl std::fmt::Debug for Color {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "#{:02X}{:02X}{:02X}{:02X}", self.red, self.green, self.blue, self.alpha)
+    }
+}
+
+/// Formats as #RRGGBB when the color is fully opaque, and as #RRGGBBAA otherwise
+impl std::fmt::Display for Color {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.alpha == u8::MAX {
+            write!(f, "#{:02X}{:02X}{:02X}", self.red, self.green, self.blue)
+        } else {
+            write!(f, "#{:02X}{:02X}{:02X}{:02X}", self.red, self.green, self.blue, self.alpha)
+        }
+    }
+}
+
+#[derive(Clone,Copy)]
+pub struct TextColors {
+
+    pub fill_color: Color,
+    pub stroke_color: Color,
+    pub background_color: Color
+}
+
+impl TextColors {
+
+    pub const fn new(fill_color: Color, stroke_color: Color, background_color: Color) -> TextColors {
+        TextColors {
+            fill_color,
+            stroke_color,
+            background_color
+        }
+    }
+
+    pub const fn create_label(fill_color: Color, stroke_color: Color) -> TextColors {
+        TextColors {
+            fill_color,
+            stroke_color,
+            background_color: Color::TRANSPARENT
+        }
+    }
+
+    pub const fn create_simple_label(color: Color) -> TextColors {
+        Self::create_label(color, color)
+    }
+
+    pub const BLACK_LABEL: TextColors = Self::create_simple_label(Color::BLACK);
+
+    pub const fn create_simple_button(background_color: Color) -> TextColors {
+        TextColors {
+            fill_color: Color::BLACK,
+            stroke_color: Color::BLACK,
+            background_color
+        }
+    }
+
+    pub fn is_fully_solid(&self) -> bool {
+        is_fully_solid(self.fill_color) && is_fully_solid(self.stroke_color) && is_fully_solid(self.background_color)
+    }
+
+    pub fn has_partial_transparency(&self) -> bool {
+        has_partial_transparency(self.fill_color) || has_partial_transparency(self.stroke_color) || has_partial_transparency(self.background_color)
+    }
+}
+
+fn is_fully_solid(color: Color) -> bool {
+    color.alpha == u8::MAX
+}
+
+fn has_partial_transparency(color: Color) -> bool {
+    color.alpha > 0 && !is_fully_solid(color)
+}
+
+fn u8_to_f32(value: u8) -> f32 {
+    value as f32 / 255.0
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_to_float_array(){
+        assert_eq!([1.0, 0.0, 0.0, 1.0], Color::RED.to_float_array());
+        assert_eq!([0.0, 0.0, 0.0, 0.0], Color::TRANSPARENT.to_float_array());
+        assert_eq!([0.0, 1.0, 1.0], Color::from_rgba(0, 255, 255, 100).to_rgb_float_array());
+        assert_eq!([0.2, 0.4, 0.6, 0.8], Color::from_rgba(51, 102, 153, 204).to_float_array());
+    }
+
+    #[test]
+    fn test_with(){
+        const HALF_RED: Color = Color::RED.with_alpha(128);
+        assert_eq!(Color::from_rgba(255, 0, 0, 128), HALF_RED);
+        assert_eq!(Color::from_rgb(1, 2, 3), Color::BLACK.with_red(1).with_green(2).with_blue(3));
+    }
+
+    #[test]
+    fn test_inverted(){
+        assert_eq!(Color::WHITE, Color::BLACK.inverted());
+        assert_eq!(Color::from_rgba(0, 255, 255, 10), Color::RED.with_alpha(10).inverted());
+        assert_eq!(Color::from_rgb(12, 34, 56), Color::from_rgb(12, 34, 56).inverted().inverted());
+    }
+
+    #[test]
+    fn test_debug(){
+        assert_eq!("#FF0000FF", format!("{:?}", Color::RED));
+        assert_eq!("#0A141E28", format!("{:?}", Color::from_rgba(10, 20, 30, 40)));
+    }
+
+    #[test]
+    fn test_display(){
+        assert_eq!("#FF0000", Color::RED.to_string());
+        assert_eq!("#0A141E28", Color::from_rgba(10, 20, 30, 40).to_string());
+        assert_eq!("#00000000", Color::TRANSPARENT.to_string());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,72 @@
+use super::Color;
+use super::lab::Lab;
+
+/// A fixed set of colors, for instance the colors of a theme or the limited color set of a retro-style renderer.
+#[derive(Clone)]
+pub struct Palette {
+    colors: Vec<Color>
+}
+
+impl Palette {
+
+    pub fn new(colors: Vec<Color>) -> Palette {
+        Palette {
+            colors
+        }
+    }
+
+    pub fn get_colors(&self) -> &[Color] {
+        &self.colors
+    }
+
+    /// Finds the color in this palette that looks the most like the given color. The comparison is done in the CIELAB
+    /// color space, so the result is based on perceived similarity rather than on the raw RGB values. Differences in
+    /// alpha are taken into account as well.
+    /// 
+    /// If this palette is empty, the given color is returned unchanged.
+    pub fn nearest(&self, color: Color) -> Color {
+        let target = Lab::from_color(color);
+        let mut nearest = color;
+        let mut nearest_distance = f32::INFINITY;
+
+        for candidate in &self.colors {
+            // Scale the alpha difference to the same range as the lightness (0 to 100)
+            let alpha_difference = (candidate.get_alpha_float() - color.get_alpha_float()) * 100.0;
+            let distance = Lab::from_color(*candidate).distance_squared(target) + alpha_difference * alpha_difference;
+            if distance < nearest_distance {
+                nearest = *candidate;
+                nearest_distance = distance;
+            }
+        }
+
+        nearest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_nearest(){
+        let palette = Palette::new(vec![Color::BLACK, Color::WHITE, Color::RED, Color::GREEN, Color::BLUE]);
+        assert_eq!(Color::BLACK, palette.nearest(Color::from_rgb(30, 30, 30)));
+        assert_eq!(Color::WHITE, palette.nearest(Color::from_rgb(200, 210, 220)));
+        assert_eq!(Color::RED, palette.nearest(Color::from_rgb(200, 40, 30)));
+        assert_eq!(Color::BLUE, palette.nearest(Color::from_rgb(20, 10, 150)));
+        assert_eq!(Color::GREEN, palette.nearest(Color::from_rgb(0, 160, 0)));
+    }
+
+    #[test]
+    fn test_nearest_alpha(){
+        let palette = Palette::new(vec![Color::BLACK, Color::TRANSPARENT]);
+        assert_eq!(Color::TRANSPARENT, palette.nearest(Color::from_rgba(0, 0, 0, 10)));
+        assert_eq!(Color::BLACK, palette.nearest(Color::from_rgba(0, 0, 0, 250)));
+    }
+
+    #[test]
+    fn test_nearest_empty(){
+        assert_eq!(Color::RED, Palette::new(Vec::new()).nearest(Color::RED));
+    }
+}
@@ -0,0 +1,198 @@
+use std::str::FromStr;
+
+use super::Color;
+
+/// The reasons why a color can't be parsed, see Color::parse.
+#[derive(Clone,Debug,PartialEq)]
+pub enum ColorParseError {
+    /// The input was empty (or only whitespace)
+    Empty,
+    /// A hex color didn't have 3, 4, 6 or 8 digits after the '#'
+    InvalidHexLength(usize),
+    /// A hex color contained a character that is not a hexadecimal digit
+    InvalidHexDigit(char),
+    /// The input was not a hex color or rgb(a) function, and not a known color name either
+    UnknownName(String),
+    /// An rgb(a) function had the wrong number of components
+    WrongComponentCount {
+        expected: usize,
+        actual: usize
+    },
+    /// A component of an rgb(a) function was not a number
+    InvalidComponent(String),
+    /// A component of an rgb(a) function was outside its allowed range ([0, 255] for the color components and
+    /// [0.0, 1.0] for the alpha component)
+    OutOfRangeComponent(f32)
+}
+
+impl std::fmt::Display for ColorParseError {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ColorParseError::Empty => write!(f, "The color is empty"),
+            ColorParseError::InvalidHexLength(length) => write!(f, "A hex color must have 3, 4, 6 or 8 digits, but this one has {}", length),
+            ColorParseError::InvalidHexDigit(digit) => write!(f, "'{}' is not a hexadecimal digit", digit),
+            ColorParseError::UnknownName(name) => write!(f, "Unknown color name '{}'", name),
+            ColorParseError::WrongComponentCount { expected, actual } => write!(f, "Expected {} color components, but got {}", expected, actual),
+            ColorParseError::InvalidComponent(component) => write!(f, "'{}' is not a valid color component", component),
+            ColorParseError::OutOfRangeComponent(value) => write!(f, "The color component {} is out of range", value)
+        }
+    }
+}
+
+impl std::error::Error for ColorParseError {}
+
+impl Color {
+
+    /// Parses a color from a string, using the same notation as CSS. The following formats are supported:
+    /// 
+    /// - Hex colors: #RGB, #RGBA, #RRGGBB and #RRGGBBAA
+    /// - Functions: rgb(255, 128, 0) and rgba(255, 128, 0, 0.5)
+    /// - The basic CSS color names (like "red", "navy" and "transparent"). Note that "green" is (0, 128, 0) in CSS,
+    ///   so it is not the same as Color::GREEN (which is "lime" in CSS).
+    /// 
+    /// Leading and trailing whitespace is ignored, and names and functions are case-insensitive.
+    pub fn parse(input: &str) -> Result<Color, ColorParseError> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Err(ColorParseError::Empty);
+        }
+
+        if let Some(hex) = input.strip_prefix('#') {
+            return parse_hex(hex);
+        }
+
+        let lower = input.to_ascii_lowercase();
+        if let Some(arguments) = lower.strip_prefix("rgba(").and_then(|rest| rest.strip_suffix(')')) {
+            return parse_function(arguments, true);
+        }
+        if let Some(arguments) = lower.strip_prefix("rgb(").and_then(|rest| rest.strip_suffix(')')) {
+            return parse_function(arguments, false);
+        }
+
+        parse_name(&lower).ok_or_else(|| ColorParseError::UnknownName(input.to_string()))
+    }
+}
+
+impl FromStr for Color {
+
+    type Err = ColorParseError;
+
+    fn from_str(input: &str) -> Result<Color, ColorParseError> {
+        Color::parse(input)
+    }
+}
+
+fn parse_hex(hex: &str) -> Result<Color, ColorParseError> {
+    let mut digits = Vec::with_capacity(8);
+    for character in hex.chars() {
+        digits.push(character.to_digit(16).ok_or(ColorParseError::InvalidHexDigit(character))? as u8);
+    }
+
+    match digits.len() {
+        3 | 4 => {
+            // Each digit is repeated, so #F80 is the same as #FF8800
+            let expand = |index: usize| digits[index] * 17;
+            let alpha = if digits.len() == 4 { expand(3) } else { 255 };
+            Ok(Color::from_rgba(expand(0), expand(1), expand(2), alpha))
+        }, 6 | 8 => {
+            let combine = |index: usize| digits[index] * 16 + digits[index + 1];
+            let alpha = if digits.len() == 8 { combine(6) } else { 255 };
+            Ok(Color::from_rgba(combine(0), combine(2), combine(4), alpha))
+        }, length => Err(ColorParseError::InvalidHexLength(length))
+    }
+}
+
+fn parse_function(arguments: &str, has_alpha: bool) -> Result<Color, ColorParseError> {
+    let components: Vec<&str> = arguments.split(',').map(|component| component.trim()).collect();
+    let expected = if has_alpha { 4 } else { 3 };
+    if components.len() != expected {
+        return Err(ColorParseError::WrongComponentCount {
+            expected,
+            actual: components.len()
+        });
+    }
+
+    let parse_component = |component: &str, max: f32| {
+        let value = component.parse::<f32>().map_err(|_| ColorParseError::InvalidComponent(component.to_string()))?;
+        if value < 0.0 || value > max || value.is_nan() {
+            Err(ColorParseError::OutOfRangeComponent(value))
+        } else {
+            Ok(value)
+        }
+    };
+
+    let red = parse_component(components[0], 255.0)?.round() as u8;
+    let green = parse_component(components[1], 255.0)?.round() as u8;
+    let blue = parse_component(components[2], 255.0)?.round() as u8;
+    let alpha = if has_alpha {
+        (parse_component(components[3], 1.0)? * 255.0).round() as u8
+    } else {
+        255
+    };
+    Ok(Color::from_rgba(red, green, blue, alpha))
+}
+
+fn parse_name(name: &str) -> Option<Color> {
+    let color = match name {
+        "transparent" => Color::TRANSPARENT,
+        "black" => Color::BLACK,
+        "white" => Color::WHITE,
+        "red" => Color::RED,
+        "lime" => Color::GREEN,
+        "blue" => Color::BLUE,
+        "green" => Color::from_rgb(0, 128, 0),
+        "silver" => Color::from_rgb(192, 192, 192),
+        "gray" | "grey" => Color::from_rgb(128, 128, 128),
+        "maroon" => Color::from_rgb(128, 0, 0),
+        "purple" => Color::from_rgb(128, 0, 128),
+        "fuchsia" | "magenta" => Color::from_rgb(255, 0, 255),
+        "olive" => Color::from_rgb(128, 128, 0),
+        "yellow" => Color::from_rgb(255, 255, 0),
+        "navy" => Color::from_rgb(0, 0, 128),
+        "teal" => Color::from_rgb(0, 128, 128),
+        "aqua" | "cyan" => Color::from_rgb(0, 255, 255),
+        "orange" => Color::from_rgb(255, 165, 0),
+        _ => return None
+    };
+    Some(color)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_parse_hex(){
+        assert_eq!(Color::from_rgb(255, 136, 0), Color::parse("#F80").unwrap());
+        assert_eq!(Color::from_rgba(255, 136, 0, 34), Color::parse("#f802").unwrap());
+        assert_eq!(Color::from_rgb(18, 52, 86), Color::parse("#123456").unwrap());
+        assert_eq!(Color::from_rgba(18, 52, 86, 120), Color::parse("  #12345678 ").unwrap());
+
+        assert_eq!(Some(ColorParseError::InvalidHexLength(5)), Color::parse("#12345").err());
+        assert_eq!(Some(ColorParseError::InvalidHexLength(0)), Color::parse("#").err());
+        assert_eq!(Some(ColorParseError::InvalidHexDigit('g')), Color::parse("#12g").err());
+    }
+
+    #[test]
+    fn test_parse_function(){
+        assert_eq!(Color::from_rgb(255, 128, 0), Color::parse("rgb(255, 128, 0)").unwrap());
+        assert_eq!(Color::from_rgba(1, 2, 3, 128), Color::parse("RGBA(1,2,3,0.5)").unwrap());
+
+        assert_eq!(Some(ColorParseError::WrongComponentCount { expected: 3, actual: 2 }), Color::parse("rgb(1, 2)").err());
+        assert_eq!(Some(ColorParseError::OutOfRangeComponent(256.0)), Color::parse("rgb(1, 256, 3)").err());
+        assert_eq!(Some(ColorParseError::OutOfRangeComponent(2.0)), Color::parse("rgba(1, 2, 3, 2)").err());
+        assert_eq!(Some(ColorParseError::InvalidComponent("x".to_string())), Color::parse("rgb(1, x, 3)").err());
+    }
+
+    #[test]
+    fn test_parse_name(){
+        assert_eq!(Color::RED, "red".parse().unwrap());
+        assert_eq!(Color::TRANSPARENT, "Transparent".parse().unwrap());
+        assert_eq!(Color::from_rgb(0, 128, 0), "green".parse().unwrap());
+
+        assert_eq!(Some(ColorParseError::UnknownName("reddish".to_string())), Color::parse("reddish").err());
+        assert_eq!(Some(ColorParseError::Empty), Color::parse("  ").err());
+    }
+}
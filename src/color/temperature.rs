@@ -0,0 +1,57 @@
+use super::Color;
+
+impl Color {
+
+    /// Approximates the color of a black-body radiator with the given temperature in Kelvin, using the approximation of
+    /// Tanner Helland. Low temperatures (around 2000K) give warm orange colors, 6500K is roughly neutral white and high
+    /// temperatures (above 10000K) give cool blue colors. The temperature is clamped to the range [1000, 40000].
+    pub fn from_temperature(kelvin: f32) -> Color {
+        let temperature = kelvin.clamp(1000.0, 40000.0) / 100.0;
+
+        let red = if temperature <= 66.0 {
+            255.0
+        } else {
+            329.698_73 * (temperature - 60.0).powf(-0.133_204_76)
+        };
+
+        let green = if temperature <= 66.0 {
+            99.470_8 * temperature.ln() - 161.119_57
+        } else {
+            288.122_16 * (temperature - 60.0).powf(-0.075_514_85)
+        };
+
+        let blue = if temperature >= 66.0 {
+            255.0
+        } else if temperature <= 19.0 {
+            0.0
+        } else {
+            138.517_73 * (temperature - 10.0).ln() - 305.044_8
+        };
+
+        let to_u8 = |value: f32| value.clamp(0.0, 255.0).round() as u8;
+        Color::from_rgb(to_u8(red), to_u8(green), to_u8(blue))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_from_temperature(){
+        assert_eq!(Color::from_rgb(255, 68, 0), Color::from_temperature(1000.0));
+        assert_eq!(Color::from_rgb(255, 68, 0), Color::from_temperature(0.0));
+
+        // Neutral white should be close to white
+        let neutral = Color::from_temperature(6600.0);
+        assert_eq!(255, neutral.get_red());
+        assert!(neutral.get_green() > 240 && neutral.get_blue() > 240);
+
+        // Warm colors have more red than blue, cool colors have more blue than red
+        let warm = Color::from_temperature(2700.0);
+        assert!(warm.get_red() > warm.get_blue() + 100);
+        let cool = Color::from_temperature(15000.0);
+        assert!(cool.get_blue() > cool.get_red());
+    }
+}
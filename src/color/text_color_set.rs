@@ -0,0 +1,130 @@
+use super::{
+    Color,
+    TextColors
+};
+use super::hsl::Hsl;
+
+/// The TextColors of a component for each of its interaction states.
+#[derive(Clone,Copy)]
+pub struct TextColorSet {
+
+    pub base: TextColors,
+    pub hover: TextColors,
+    pub pressed: TextColors,
+    pub disabled: TextColors
+}
+
+/// The interaction states a component can be in, see TextColorSet::get.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum InteractionState {
+    Base,
+    Hover,
+    Pressed,
+    Disabled
+}
+
+impl TextColorSet {
+
+    pub const fn new(base: TextColors, hover: TextColors, pressed: TextColors, disabled: TextColors) -> TextColorSet {
+        TextColorSet {
+            base,
+            hover,
+            pressed,
+            disabled
+        }
+    }
+
+    /// Creates a TextColorSet that uses the same TextColors for every state. This is useful for components that don't
+    /// react to interaction, like labels.
+    pub const fn uniform(colors: TextColors) -> TextColorSet {
+        Self::new(colors, colors, colors, colors)
+    }
+
+    /// Creates a TextColorSet with the given base colors and automatically derived colors for the other states:
+    /// the hover colors are slightly lighter (or darker for colors that are already light), the pressed colors are
+    /// shifted further in the same direction, and the disabled colors are grayed out and half transparent.
+    pub fn derive_from_base(base: TextColors) -> TextColorSet {
+        TextColorSet {
+            base,
+            hover: map_text_colors(base, |color| shade(color, HOVER_SHADE)),
+            pressed: map_text_colors(base, |color| shade(color, PRESSED_SHADE)),
+            disabled: map_text_colors(base, gray_out)
+        }
+    }
+
+    pub fn get(&self, state: InteractionState) -> TextColors {
+        match state {
+            InteractionState::Base => self.base,
+            InteractionState::Hover => self.hover,
+            InteractionState::Pressed => self.pressed,
+            InteractionState::Disabled => self.disabled
+        }
+    }
+}
+
+const HOVER_SHADE: f32 = 0.08;
+const PRESSED_SHADE: f32 = 0.16;
+
+fn map_text_colors<F: Fn(Color) -> Color>(colors: TextColors, mapper: F) -> TextColors {
+    TextColors::new(mapper(colors.fill_color), mapper(colors.stroke_color), mapper(colors.background_color))
+}
+
+/// Moves the lightness of the given color away from the nearest extreme by the given amount, so that both light and
+/// dark colors get a visible change.
+fn shade(color: Color, amount: f32) -> Color {
+    let mut hsl = Hsl::from_color(color);
+    if hsl.lightness > 0.5 {
+        hsl.lightness = (hsl.lightness - amount).max(0.0);
+    } else {
+        hsl.lightness = (hsl.lightness + amount).min(1.0);
+    }
+    hsl.to_color(color.get_alpha())
+}
+
+fn gray_out(color: Color) -> Color {
+    color.with_saturation(0.0).with_alpha(color.get_alpha() / 2)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_derive_from_base(){
+        let base = TextColors::create_simple_button(Color::from_rgb(200, 50, 50));
+        let set = TextColorSet::derive_from_base(base);
+
+        assert_eq!(base.background_color, set.base.background_color);
+        assert_ne!(set.hover.background_color, base.background_color);
+        assert_ne!(set.pressed.background_color, set.hover.background_color);
+
+        // The background is dark-ish, so it should become lighter
+        let base_lightness = Hsl::from_color(base.background_color).lightness;
+        let hover_lightness = Hsl::from_color(set.hover.background_color).lightness;
+        let pressed_lightness = Hsl::from_color(set.pressed.background_color).lightness;
+        assert!(base_lightness < hover_lightness && hover_lightness < pressed_lightness);
+
+        // Black text should become lighter, not stay black
+        assert_ne!(set.hover.fill_color, Color::BLACK);
+
+        let disabled = set.disabled.background_color;
+        assert_eq!(disabled.get_red(), disabled.get_green());
+        assert_eq!(127, disabled.get_alpha());
+    }
+
+    #[test]
+    fn test_light_colors_get_darker(){
+        let set = TextColorSet::derive_from_base(TextColors::create_simple_button(Color::WHITE));
+        assert!(set.hover.background_color.get_red() < 255);
+        assert!(set.pressed.background_color.get_red() < set.hover.background_color.get_red());
+    }
+
+    #[test]
+    fn test_transparency_is_preserved(){
+        let set = TextColorSet::derive_from_base(TextColors::BLACK_LABEL);
+        assert_eq!(0, set.hover.background_color.get_alpha());
+        assert_eq!(0, set.pressed.background_color.get_alpha());
+        assert_eq!(0, set.disabled.background_color.get_alpha());
+    }
+}
@@ -0,0 +1,97 @@
+use super::{
+    Color,
+    TextColors
+};
+
+/// A builder for TextColors. Start with TextColors::builder(), TextColorsBuilder::label() or TextColorsBuilder::button(),
+/// override the colors that should be different, and finish with build(). All methods are const, so the builder can
+/// also be used to define constants.
+#[derive(Clone,Copy)]
+pub struct TextColorsBuilder {
+    fill_color: Color,
+    stroke_color: Color,
+    background_color: Color
+}
+
+impl TextColorsBuilder {
+
+    /// Starts with black text on a transparent background.
+    pub const fn label() -> TextColorsBuilder {
+        TextColorsBuilder {
+            fill_color: Color::BLACK,
+            stroke_color: Color::BLACK,
+            background_color: Color::TRANSPARENT
+        }
+    }
+
+    /// Starts with black text on a white background.
+    pub const fn button() -> TextColorsBuilder {
+        TextColorsBuilder {
+            fill_color: Color::BLACK,
+            stroke_color: Color::BLACK,
+            background_color: Color::WHITE
+        }
+    }
+
+    pub const fn fill(self, fill_color: Color) -> TextColorsBuilder {
+        TextColorsBuilder {
+            fill_color,
+            ..self
+        }
+    }
+
+    pub const fn stroke(self, stroke_color: Color) -> TextColorsBuilder {
+        TextColorsBuilder {
+            stroke_color,
+            ..self
+        }
+    }
+
+    /// Sets both the fill color and the stroke color.
+    pub const fn text(self, text_color: Color) -> TextColorsBuilder {
+        TextColorsBuilder {
+            fill_color: text_color,
+            stroke_color: text_color,
+            ..self
+        }
+    }
+
+    pub const fn background(self, background_color: Color) -> TextColorsBuilder {
+        TextColorsBuilder {
+            background_color,
+            ..self
+        }
+    }
+
+    pub const fn build(self) -> TextColors {
+        TextColors::new(self.fill_color, self.stroke_color, self.background_color)
+    }
+}
+
+impl TextColors {
+
+    /// Creates a TextColorsBuilder, starting with black text on a transparent background.
+    pub const fn builder() -> TextColorsBuilder {
+        TextColorsBuilder::label()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const WARNING_BUTTON: TextColors = TextColorsBuilder::button().text(Color::WHITE).background(Color::RED).build();
+
+    fn same_colors(a: TextColors, b: TextColors) -> bool {
+        a.fill_color == b.fill_color && a.stroke_color == b.stroke_color && a.background_color == b.background_color
+    }
+
+    #[test]
+    fn test_builder(){
+        assert!(same_colors(TextColors::BLACK_LABEL, TextColors::builder().build()));
+        assert!(same_colors(TextColors::create_label(Color::RED, Color::BLUE), TextColorsBuilder::label().fill(Color::RED).stroke(Color::BLUE).build()));
+        assert!(same_colors(TextColors::create_simple_button(Color::GREEN), TextColorsBuilder::button().background(Color::GREEN).build()));
+        assert!(same_colors(TextColors::new(Color::WHITE, Color::WHITE, Color::RED), WARNING_BUTTON));
+    }
+}
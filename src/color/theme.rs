@@ -0,0 +1,142 @@
+use super::{
+    Color,
+    Palette,
+    TextColorSet,
+    TextColors,
+    TextColorsBuilder
+};
+
+/// The standard colors of an application: a palette with the colors that can be used for content (like chart series)
+/// and the colors of the standard widgets.
+#[derive(Clone)]
+pub struct Theme {
+
+    pub palette: Palette,
+
+    /// The color that should be used to clear the viewport
+    pub background: Color,
+    /// The background color of panels and other containers on top of the background
+    pub surface: Color,
+    /// The color of borders and separators
+    pub border: Color,
+    /// The color that is used to highlight focused or selected elements
+    pub accent: Color,
+
+    pub label: TextColorSet,
+    pub button: TextColorSet,
+    pub accent_button: TextColorSet
+}
+
+impl Theme {
+
+    /// Dark text on light backgrounds
+    pub fn light() -> Theme {
+        let accent = Color::from_rgb(21, 101, 192);
+        Theme {
+            palette: Palette::new(vec![
+                accent, Color::from_rgb(216, 67, 21), Color::from_rgb(56, 142, 60),
+                Color::from_rgb(251, 192, 45), Color::from_rgb(123, 31, 162), Color::from_rgb(0, 151, 167)
+            ]),
+            background: Color::from_rgb(250, 250, 250),
+            surface: Color::WHITE,
+            border: Color::from_rgb(200, 200, 200),
+            accent,
+            label: TextColorSet::uniform(TextColors::create_simple_label(Color::from_rgb(33, 33, 33))),
+            button: button_colors(Color::from_rgb(33, 33, 33), Color::from_rgb(224, 224, 224)),
+            accent_button: button_colors(Color::WHITE, accent)
+        }
+    }
+
+    /// Light text on dark backgrounds
+    pub fn dark() -> Theme {
+        let accent = Color::from_rgb(100, 181, 246);
+        Theme {
+            palette: Palette::new(vec![
+                accent, Color::from_rgb(255, 138, 101), Color::from_rgb(129, 199, 132),
+                Color::from_rgb(255, 241, 118), Color::from_rgb(206, 147, 216), Color::from_rgb(77, 208, 225)
+            ]),
+            background: Color::from_rgb(18, 18, 18),
+            surface: Color::from_rgb(33, 33, 33),
+            border: Color::from_rgb(66, 66, 66),
+            accent,
+            label: TextColorSet::uniform(TextColors::create_simple_label(Color::from_rgb(238, 238, 238))),
+            button: button_colors(Color::from_rgb(238, 238, 238), Color::from_rgb(55, 55, 55)),
+            accent_button: button_colors(Color::BLACK, accent)
+        }
+    }
+}
+
+/// Like TextColorSet::derive_from_base, but the text keeps its color when the button is hovered or pressed. Shading the
+/// text as well would move it towards the background, which makes strongly colored buttons hard to read.
+fn button_colors(text: Color, background: Color) -> TextColorSet {
+    let mut colors = TextColorSet::derive_from_base(TextColorsBuilder::button().text(text).background(background).build());
+    colors.hover.fill_color = text;
+    colors.pressed.fill_color = text;
+    colors
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use crate::color::hsl::Hsl;
+
+    /// The WCAG contrast ratio between the two (opaque) colors, in the range [1.0, 21.0]
+    fn contrast_ratio(a: Color, b: Color) -> f32 {
+        let luminance = |color: Color| {
+            let linear = |channel: f32| if channel <= 0.040_45 { channel / 12.92 } else { ((channel + 0.055) / 1.055).powf(2.4) };
+            0.2126 * linear(color.get_red_float()) + 0.7152 * linear(color.get_green_float()) + 0.0722 * linear(color.get_blue_float())
+        };
+        let (lighter, darker) = if luminance(a) > luminance(b) { (luminance(a), luminance(b)) } else { (luminance(b), luminance(a)) };
+        (lighter + 0.05) / (darker + 0.05)
+    }
+
+    fn assert_readable(theme: &Theme) {
+        for background in &[theme.background, theme.surface] {
+            let ratio = contrast_ratio(theme.label.base.fill_color, *background);
+            assert!(ratio >= 7.0, "The label text has a contrast ratio of only {}", ratio);
+        }
+        for set in &[theme.button, theme.accent_button] {
+            let ratio = contrast_ratio(set.base.fill_color, set.base.background_color);
+            assert!(ratio >= 4.5, "The button text has a contrast ratio of only {}", ratio);
+            for colors in &[set.hover, set.pressed] {
+                let ratio = contrast_ratio(colors.fill_color, colors.background_color);
+                assert!(ratio >= 3.0, "The hovered or pressed button text has a contrast ratio of only {} ({} on {})", ratio, colors.fill_color, colors.background_color);
+            }
+        }
+    }
+
+    fn lightness(color: Color) -> f32 {
+        Hsl::from_color(color).lightness
+    }
+
+    #[test]
+    fn test_contrast(){
+        assert_readable(&Theme::light());
+        assert_readable(&Theme::dark());
+    }
+
+    #[test]
+    fn test_dark_theme(){
+        let (light, dark) = (Theme::light(), Theme::dark());
+
+        // The dark theme uses light text on dark backgrounds, and the surfaces get lighter as they get closer
+        assert!(lightness(dark.background) < 0.1);
+        assert!(lightness(dark.background) < lightness(dark.surface));
+        assert!(lightness(dark.surface) < lightness(dark.border));
+        assert!(lightness(dark.label.base.fill_color) > 0.9);
+        assert!(lightness(light.background) > 0.9);
+        assert!(lightness(light.label.base.fill_color) < 0.2);
+
+        // The palettes have the same roles, and each palette starts with its accent color
+        assert_eq!(light.palette.get_colors().len(), dark.palette.get_colors().len());
+        assert_eq!(dark.accent, dark.palette.get_colors()[0]);
+        assert_eq!(light.accent, light.palette.get_colors()[0]);
+
+        // The accent and palette colors must be distinguishable from the dark background
+        for color in dark.palette.get_colors() {
+            assert!(contrast_ratio(*color, dark.background) >= 4.5, "{} is too dark for the dark theme", color);
+        }
+    }
+}
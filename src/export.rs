@@ -0,0 +1,232 @@
+use wasm_bindgen::JsCast;
+use wasm_bindgen::JsValue;
+
+use web_sys::{
+    CanvasRenderingContext2d,
+    Document,
+    HtmlAnchorElement,
+    HtmlCanvasElement,
+    WebGlRenderingContext
+};
+
+use crate::{
+    GlInitError,
+    Region,
+    try_get_gl
+};
+
+/// Describes the size and resolution of an image that should be exported with export_for_print.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct PrintSettings {
+    /// The part of the viewport that should be exported
+    pub region: Region,
+    /// The width the image should have on paper, in inches. The height is determined by the aspect ratio of the region.
+    pub width_inches: f32,
+    /// The number of dots (pixels) per inch, typically 300 for print
+    pub dpi: f32
+}
+
+impl PrintSettings {
+
+    /// Computes the size (in pixels) of the exported image, given the size of the canvas the scene is normally
+    /// rendered on. The canvas size is needed to preserve the aspect ratio of the region.
+    pub fn get_pixel_size(&self, canvas_width: u32, canvas_height: u32) -> (u32, u32) {
+        let width = (self.width_inches * self.dpi).round().max(1.0);
+        let region_pixel_width = self.region.get_float_width() * canvas_width as f32;
+        let region_pixel_height = self.region.get_float_height() * canvas_height as f32;
+        let height = (width * region_pixel_height / region_pixel_width).round().max(1.0);
+        (width as u32, height as u32)
+    }
+}
+
+/// Maps the GL coordinates of the scene (in the range [-1.0, 1.0], like the float coordinates of Region) to the GL
+/// coordinates of the tile that is being rendered by export_for_print. The render function of export_for_print must
+/// apply it to all its vertex positions, typically in the vertex shader:
+/// `gl_Position = vec4(a_position * u_scale + u_offset, 0.0, 1.0);`
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct TileTransform {
+    pub scale_x: f32,
+    pub scale_y: f32,
+    pub offset_x: f32,
+    pub offset_y: f32
+}
+
+impl TileTransform {
+
+    /// Maps the point (x, y) in the GL coordinates of the scene to the GL coordinates of the tile.
+    pub fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+        (x * self.scale_x + self.offset_x, y * self.scale_y + self.offset_y)
+    }
+
+    /// Computes the transform of the tile that covers the pixels [tile_x, tile_x + tile_width) x [tile_y, tile_y +
+    /// tile_height) of an image of image_width x image_height pixels showing the given region of the scene. Like in
+    /// canvases, tile_y is counted from the top of the image.
+    fn for_tile(region: Region, image_size: (u32, u32), tile_position: (u32, u32), tile_size: (u32, u32)) -> TileTransform {
+        let (image_width, image_height) = (image_size.0 as f32, image_size.1 as f32);
+        let (tile_width, tile_height) = (tile_size.0 as f32, tile_size.1 as f32);
+        let tile_x = tile_position.0 as f32;
+        // The canvas coordinates go downwards, but the GL coordinates go upwards
+        let tile_bottom = image_height - tile_position.1 as f32 - tile_height;
+
+        // The scene coordinate x is at pixel (x - min_x) * image_width / region_width of the image, and pixel p of
+        // the image is at tile coordinate 2 * (p - tile_x) / tile_width - 1
+        let scale_x = 2.0 * image_width / (region.get_float_width() * tile_width);
+        let scale_y = 2.0 * image_height / (region.get_float_height() * tile_height);
+        TileTransform {
+            scale_x,
+            scale_y,
+            offset_x: -region.get_float_min_x() * scale_x - 2.0 * tile_x / tile_width - 1.0,
+            offset_y: -region.get_float_min_y() * scale_y - 2.0 * tile_bottom / tile_height - 1.0
+        }
+    }
+}
+
+/// The reasons why export_for_print can fail.
+#[derive(Debug)]
+pub enum PrintExportError {
+    /// No WebGL context could be acquired for rendering the tiles
+    Context(GlInitError),
+    /// A browser call failed
+    Failed(JsValue)
+}
+
+impl std::fmt::Display for PrintExportError {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PrintExportError::Context(error) => write!(f, "Failed to get a WebGL context for the export: {}", error),
+            PrintExportError::Failed(reason) => write!(f, "The export failed: {:?}", reason)
+        }
+    }
+}
+
+impl std::error::Error for PrintExportError {}
+
+impl From<GlInitError> for PrintExportError {
+
+    fn from(error: GlInitError) -> Self {
+        PrintExportError::Context(error)
+    }
+}
+
+/// The maximum width and height of the tiles. Larger tiles need fewer render calls, but not all devices can handle
+/// large drawing buffers.
+const MAX_TILE_SIZE: u32 = 2048;
+
+/// Re-renders the scene at print resolution and returns a (detached) canvas containing the resulting image. Browser
+/// printing of WebGL canvases only uses the resolution of the canvas on the screen, which looks very blurry on paper.
+/// 
+/// The render function will be called once for each tile of the image, with a new WebGL context (whose viewport covers
+/// the tile) and the TileTransform of the tile. It should render the scene in the same way as it is normally rendered
+/// (using Region coordinates), but with the TileTransform applied to its positions. Since only the tile needs to fit in
+/// the viewport, there is no limit on the resolution apart from the memory needed for the output canvas. Note that the
+/// render function must create its own GL resources because the context is different from the context of the source
+/// canvas.
+/// 
+/// The source canvas is only used to determine the aspect ratio of the exported image.
+pub fn export_for_print<F: FnMut(&WebGlRenderingContext, TileTransform)>(source: &HtmlCanvasElement, settings: PrintSettings, mut render: F)
+        -> Result<HtmlCanvasElement, PrintExportError> {
+    let document = get_document();
+    let (width, height) = settings.get_pixel_size(source.width(), source.height());
+
+    let output = create_canvas(&document, width, height)?;
+    let output_context = output.get_context("2d").map_err(PrintExportError::Failed)?
+        .expect("A new canvas should have a 2d context").dyn_into::<CanvasRenderingContext2d>()
+        .expect("The 2d context should be an instance of CanvasRenderingContext2d");
+
+    let tile_canvas = create_canvas(&document, MAX_TILE_SIZE.min(width), MAX_TILE_SIZE.min(height))?;
+    let gl = try_get_gl(&tile_canvas)?;
+
+    let mut tile_y = 0;
+    while tile_y < height {
+        let tile_height = MAX_TILE_SIZE.min(height - tile_y);
+        let mut tile_x = 0;
+        while tile_x < width {
+            let tile_width = MAX_TILE_SIZE.min(width - tile_x);
+            tile_canvas.set_width(tile_width);
+            tile_canvas.set_height(tile_height);
+
+            gl.viewport(0, 0, tile_width as i32, tile_height as i32);
+            let transform = TileTransform::for_tile(settings.region, (width, height), (tile_x, tile_y), (tile_width, tile_height));
+            render(&gl, transform);
+
+            output_context.draw_image_with_html_canvas_element(&tile_canvas, tile_x as f64, tile_y as f64)
+                .map_err(PrintExportError::Failed)?;
+            tile_x += tile_width;
+        }
+        tile_y += tile_height;
+    }
+
+    Ok(output)
+}
+
+/// Lets the browser download the current content of the given canvas as a PNG image with the given file name.
+pub fn download_canvas_image(canvas: &HtmlCanvasElement, file_name: &str) -> Result<(), JsValue> {
+    let data_url = canvas.to_data_url_with_type("image/png")?;
+    let anchor = get_document().create_element("a")?.dyn_into::<HtmlAnchorElement>()
+        .expect("An a element should be an instance of HtmlAnchorElement");
+    anchor.set_href(&data_url);
+    anchor.set_download(file_name);
+    anchor.click();
+    Ok(())
+}
+
+fn get_document() -> Document {
+    web_sys::window().expect("There should be a window").document().expect("The window should have a document")
+}
+
+fn create_canvas(document: &Document, width: u32, height: u32) -> Result<HtmlCanvasElement, PrintExportError> {
+    let canvas = document.create_element("canvas").map_err(PrintExportError::Failed)?
+        .dyn_into::<HtmlCanvasElement>().expect("A canvas element should be an instance of HtmlCanvasElement");
+    canvas.set_width(width);
+    canvas.set_height(height);
+    Ok(canvas)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_get_pixel_size(){
+        let entire = PrintSettings {
+            region: Region::entire_viewport(),
+            width_inches: 8.0,
+            dpi: 300.0
+        };
+        assert_eq!((2400, 1200), entire.get_pixel_size(800, 400));
+
+        let left_half = PrintSettings {
+            region: Region::new(-10_000, -10_000, 0, 10_000),
+            width_inches: 4.0,
+            dpi: 150.0
+        };
+        assert_eq!((600, 600), left_half.get_pixel_size(800, 400));
+    }
+
+    fn assert_maps(transform: TileTransform, from: (f32, f32), to: (f32, f32)) {
+        let (x, y) = transform.apply(from.0, from.1);
+        assert!((x - to.0).abs() < 0.001 && (y - to.1).abs() < 0.001, "{:?} was mapped to {:?} instead of {:?}", from, (x, y), to);
+    }
+
+    #[test]
+    fn test_tile_transform(){
+        // A single tile of the entire viewport doesn't need a transform
+        let entire = TileTransform::for_tile(Region::new(-10_000, -10_000, 9_999, 9_999), (100, 50), (0, 0), (100, 50));
+        assert_maps(entire, (-1.0, -1.0), (-1.0, -1.0));
+        assert_maps(entire, (0.5, 0.25), (0.5, 0.25));
+
+        // The top-right quarter of an image of the left half of the viewport
+        let left_half = Region::new(-10_000, -10_000, -1, 9_999);
+        let tile = TileTransform::for_tile(left_half, (200, 100), (100, 0), (100, 50));
+        assert_maps(tile, (-0.5, 0.0), (-1.0, -1.0));
+        assert_maps(tile, (0.0, 1.0), (1.0, 1.0));
+
+        // A tiny region at a high resolution only needs a tile-sized viewport
+        let tiny = Region::new(0, 0, 9, 9);
+        let tile = TileTransform::for_tile(tiny, (10_000, 10_000), (0, 9_000), (1_000, 1_000));
+        assert_maps(tile, (0.0, 0.0), (-1.0, -1.0));
+        assert_maps(tile, (0.0001, 0.0001), (1.0, 1.0));
+    }
+}
@@ -0,0 +1,104 @@
+use web_sys::WebGlRenderingContext as GL;
+
+/// The blend factors of a BlendMode, as passed to blendFuncSeparate.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub struct BlendFactors {
+
+    pub source_rgb: u32,
+    pub destination_rgb: u32,
+    pub source_alpha: u32,
+    pub destination_alpha: u32
+}
+
+/// The commonly needed ways to blend the output of the fragment shader with the content of the drawing buffer.
+///
+/// The canvas is composited onto the page with premultiplied alpha (unless the premultipliedAlpha context option is
+/// disabled), so the alpha channel of the drawing buffer must stay consistent with its colors. All modes take care of
+/// that by blending the alpha channel separately, which is easy to forget when calling blendFunc directly.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum BlendMode {
+
+    /// Regular transparency, for shaders that output colors that are not premultiplied (like QuadBatch and
+    /// ShapeRenderer)
+    Alpha,
+    /// Regular transparency, for shaders that output premultiplied colors (like most textures drawn to a canvas)
+    PremultipliedAlpha,
+    /// Adds the colors (weighted by the source alpha) to the drawing buffer, for glows and highlights
+    Additive,
+    /// Multiplies the colors in the drawing buffer with the output colors, for shadows and tinting. The source alpha
+    /// is ignored.
+    Multiply,
+    /// Disables blending, so the output overwrites the drawing buffer
+    None
+}
+
+impl BlendMode {
+
+    /// Gets the blend factors of this mode, or None if blending should be disabled.
+    pub fn factors(self) -> Option<BlendFactors> {
+        let (source_rgb, destination_rgb, source_alpha, destination_alpha) = match self {
+            BlendMode::Alpha => (GL::SRC_ALPHA, GL::ONE_MINUS_SRC_ALPHA, GL::ONE, GL::ONE_MINUS_SRC_ALPHA),
+            BlendMode::PremultipliedAlpha => (GL::ONE, GL::ONE_MINUS_SRC_ALPHA, GL::ONE, GL::ONE_MINUS_SRC_ALPHA),
+            // The alpha of the drawing buffer is kept as is, since additive and multiplicative blending don't cover anything
+            BlendMode::Additive => (GL::SRC_ALPHA, GL::ONE, GL::ZERO, GL::ONE),
+            BlendMode::Multiply => (GL::DST_COLOR, GL::ZERO, GL::ZERO, GL::ONE),
+            BlendMode::None => return None
+        };
+        Some(BlendFactors {
+            source_rgb,
+            destination_rgb,
+            source_alpha,
+            destination_alpha
+        })
+    }
+
+    /// Enables (or disables) blending and sets the blend equation and factors of the context to this mode.
+    pub fn apply(self, gl: &GL) {
+        match self.factors() {
+            Some(factors) => {
+                gl.enable(GL::BLEND);
+                gl.blend_equation(GL::FUNC_ADD);
+                gl.blend_func_separate(factors.source_rgb, factors.destination_rgb, factors.source_alpha, factors.destination_alpha);
+            },
+            None => gl.disable(GL::BLEND)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// Blends a single channel in the same way as GL would with FUNC_ADD. The source alpha is source_alpha.
+    fn blend(source_factor: u32, destination_factor: u32, source_alpha: f32, source: f32, destination: f32) -> f32 {
+        let factor = |factor| match factor {
+            GL::ZERO => 0.0,
+            GL::ONE => 1.0,
+            GL::SRC_ALPHA => source_alpha,
+            GL::ONE_MINUS_SRC_ALPHA => 1.0 - source_alpha,
+            _ => panic!("Unexpected factor")
+        };
+        source * factor(source_factor) + destination * factor(destination_factor)
+    }
+
+    #[test]
+    fn test_alpha_keeps_premultiplied_drawing_buffer() {
+        let factors = BlendMode::Alpha.factors().unwrap();
+
+        // Drawing half-transparent white onto a cleared (fully transparent) drawing buffer
+        let color = blend(factors.source_rgb, factors.destination_rgb, 0.5, 1.0, 0.0);
+        let alpha = blend(factors.source_alpha, factors.destination_alpha, 0.5, 0.5, 0.0);
+
+        // The result must be premultiplied: color 0.5 with alpha 0.5, rather than alpha 0.25
+        assert_eq!((0.5, 0.5), (color, alpha));
+    }
+
+    #[test]
+    fn test_factors() {
+        assert_eq!(None, BlendMode::None.factors());
+        let factors = BlendMode::PremultipliedAlpha.factors().unwrap();
+        assert_eq!((GL::ONE, GL::ONE_MINUS_SRC_ALPHA), (factors.source_rgb, factors.destination_rgb));
+        assert_eq!(GL::ONE, BlendMode::Additive.factors().unwrap().destination_alpha);
+    }
+}
@@ -0,0 +1,263 @@
+use js_sys::{
+    Float32Array,
+    Uint16Array
+};
+
+use web_sys::{
+    WebGlBuffer,
+    WebGlRenderingContext as GL
+};
+
+/// The usage hint of a buffer, which tells the driver how often the data will be changed.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum BufferUsage {
+    /// The data is uploaded once and drawn many times
+    Static,
+    /// The data is changed repeatedly and drawn many times
+    Dynamic,
+    /// The data is uploaded once and drawn at most a few times
+    Stream
+}
+
+impl BufferUsage {
+
+    fn to_gl(self) -> u32 {
+        match self {
+            BufferUsage::Static => GL::STATIC_DRAW,
+            BufferUsage::Dynamic => GL::DYNAMIC_DRAW,
+            BufferUsage::Stream => GL::STREAM_DRAW
+        }
+    }
+}
+
+/// The reasons why creating or updating a VertexBuffer or IndexBuffer can fail.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum BufferError {
+    /// createBuffer returned null, which normally means that the context is lost
+    CreationFailed,
+    /// A sub-range update would write past the end of the data that was uploaded earlier
+    OutOfRange {
+        offset: usize,
+        length: usize,
+        buffer_length: usize
+    }
+}
+
+impl std::fmt::Display for BufferError {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            BufferError::CreationFailed => write!(f, "Failed to create the buffer (is the context lost?)"),
+            BufferError::OutOfRange { offset, length, buffer_length } => write!(f,
+                "Can't update {} elements at offset {} of a buffer with only {} elements", length, offset, buffer_length
+            )
+        }
+    }
+}
+
+impl std::error::Error for BufferError {}
+
+fn check_range(offset: usize, length: usize, buffer_length: usize) -> Result<(), BufferError> {
+    if offset + length > buffer_length {
+        Err(BufferError::OutOfRange { offset, length, buffer_length })
+    } else {
+        Ok(())
+    }
+}
+
+/// A GL buffer containing vertex data (floats). All methods bind the buffer to ARRAY_BUFFER before using it.
+pub struct VertexBuffer {
+    buffer: WebGlBuffer,
+    usage: BufferUsage,
+    length: usize
+}
+
+impl VertexBuffer {
+
+    pub fn new(gl: &GL, usage: BufferUsage) -> Result<VertexBuffer, BufferError> {
+        Ok(VertexBuffer {
+            buffer: gl.create_buffer().ok_or(BufferError::CreationFailed)?,
+            usage,
+            length: 0
+        })
+    }
+
+    pub fn bind(&self, gl: &GL) {
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(&self.buffer));
+    }
+
+    /// Replaces the content of this buffer with the given data.
+    pub fn upload(&mut self, gl: &GL, data: &[f32]) {
+        self.bind(gl);
+        gl.buffer_data_with_array_buffer_view(GL::ARRAY_BUFFER, &Float32Array::from(data), self.usage.to_gl());
+        self.length = data.len();
+    }
+
+    /// Overwrites part of the content of this buffer, starting at the given offset (in floats, not bytes).
+    pub fn update(&self, gl: &GL, offset: usize, data: &[f32]) -> Result<(), BufferError> {
+        check_range(offset, data.len(), self.length)?;
+        self.bind(gl);
+        gl.buffer_sub_data_with_i32_and_array_buffer_view(GL::ARRAY_BUFFER, (offset * 4) as i32, &Float32Array::from(data));
+        Ok(())
+    }
+
+    /// Gets the number of floats that were uploaded most recently.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    pub fn get_buffer(&self) -> &WebGlBuffer {
+        &self.buffer
+    }
+
+    /// Deletes the buffer. This struct must not be used anymore after calling this.
+    pub fn delete(self, gl: &GL) {
+        gl.delete_buffer(Some(&self.buffer));
+    }
+}
+
+/// A GL buffer containing 16-bit indices. All methods bind the buffer to ELEMENT_ARRAY_BUFFER before using it.
+pub struct IndexBuffer {
+    buffer: WebGlBuffer,
+    usage: BufferUsage,
+    length: usize
+}
+
+impl IndexBuffer {
+
+    pub fn new(gl: &GL, usage: BufferUsage) -> Result<IndexBuffer, BufferError> {
+        Ok(IndexBuffer {
+            buffer: gl.create_buffer().ok_or(BufferError::CreationFailed)?,
+            usage,
+            length: 0
+        })
+    }
+
+    pub fn bind(&self, gl: &GL) {
+        gl.bind_buffer(GL::ELEMENT_ARRAY_BUFFER, Some(&self.buffer));
+    }
+
+    /// Replaces the content of this buffer with the given indices.
+    pub fn upload_u16(&mut self, gl: &GL, indices: &[u16]) {
+        self.bind(gl);
+        gl.buffer_data_with_array_buffer_view(GL::ELEMENT_ARRAY_BUFFER, &Uint16Array::from(indices), self.usage.to_gl());
+        self.length = indices.len();
+    }
+
+    /// Overwrites part of the content of this buffer, starting at the given offset (in indices, not bytes).
+    pub fn update_u16(&self, gl: &GL, offset: usize, indices: &[u16]) -> Result<(), BufferError> {
+        check_range(offset, indices.len(), self.length)?;
+        self.bind(gl);
+        gl.buffer_sub_data_with_i32_and_array_buffer_view(GL::ELEMENT_ARRAY_BUFFER, (offset * 2) as i32, &Uint16Array::from(indices));
+        Ok(())
+    }
+
+    /// Gets the number of indices that were uploaded most recently.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    pub fn get_buffer(&self) -> &WebGlBuffer {
+        &self.buffer
+    }
+
+    /// Deletes the buffer. This struct must not be used anymore after calling this.
+    pub fn delete(self, gl: &GL) {
+        gl.delete_buffer(Some(&self.buffer));
+    }
+}
+
+/// A float attribute in a VertexLayout.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub struct VertexAttribute {
+    /// The attribute location, see ShaderProgram::attribute_location
+    pub location: u32,
+    /// The number of floats of this attribute (between 1 and 4)
+    pub components: u32,
+    /// The offset of this attribute within a vertex, in bytes
+    pub offset: u32
+}
+
+/// Describes how the float attributes of a vertex are interleaved in a VertexBuffer. The attributes are stored in the
+/// order in which they are added, without padding.
+#[derive(Clone,Debug,Default,PartialEq,Eq)]
+pub struct VertexLayout {
+    attributes: Vec<VertexAttribute>
+}
+
+impl VertexLayout {
+
+    pub fn new() -> VertexLayout {
+        VertexLayout {
+            attributes: Vec::new()
+        }
+    }
+
+    /// Adds an attribute with the given location and number of floats after the attributes that were added before.
+    pub fn attribute(mut self, location: u32, components: u32) -> VertexLayout {
+        let offset = self.get_stride();
+        self.attributes.push(VertexAttribute { location, components, offset });
+        self
+    }
+
+    pub fn get_attributes(&self) -> &[VertexAttribute] {
+        &self.attributes
+    }
+
+    /// Gets the size of a vertex, in bytes.
+    pub fn get_stride(&self) -> u32 {
+        self.attributes.iter().map(|attribute| attribute.components * 4).sum()
+    }
+
+    /// Gets the number of floats per vertex.
+    pub fn floats_per_vertex(&self) -> u32 {
+        self.attributes.iter().map(|attribute| attribute.components).sum()
+    }
+
+    /// Binds the vertex buffer and sets up (and enables) all attributes of this layout.
+    pub fn apply(&self, gl: &GL, buffer: &VertexBuffer) {
+        buffer.bind(gl);
+        self.set_pointers(gl);
+    }
+
+    /// Sets up (and enables) all attributes of this layout for the buffer that is currently bound to ARRAY_BUFFER.
+    pub(crate) fn set_pointers(&self, gl: &GL) {
+        let stride = self.get_stride() as i32;
+        for attribute in &self.attributes {
+            gl.enable_vertex_attrib_array(attribute.location);
+            gl.vertex_attrib_pointer_with_i32(attribute.location, attribute.components as i32, GL::FLOAT, false, stride, attribute.offset as i32);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_vertex_layout() {
+        let layout = VertexLayout::new().attribute(0, 2).attribute(3, 4).attribute(1, 1);
+        assert_eq!(28, layout.get_stride());
+        assert_eq!(7, layout.floats_per_vertex());
+        assert_eq!(&[
+            VertexAttribute { location: 0, components: 2, offset: 0 },
+            VertexAttribute { location: 3, components: 4, offset: 8 },
+            VertexAttribute { location: 1, components: 1, offset: 24 }
+        ], layout.get_attributes());
+    }
+
+    #[test]
+    fn test_check_range() {
+        assert_eq!(Ok(()), check_range(2, 3, 5));
+        assert_eq!(Err(BufferError::OutOfRange { offset: 3, length: 3, buffer_length: 5 }), check_range(3, 3, 5));
+    }
+}
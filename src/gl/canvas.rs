@@ -0,0 +1,64 @@
+use js_sys::Object;
+
+use wasm_bindgen::JsValue;
+
+use web_sys::{
+    HtmlCanvasElement,
+    OffscreenCanvas,
+    WebGlRenderingContext as GL
+};
+
+/// The kinds of canvases that can be rendered to: a regular HtmlCanvasElement on the main thread, or an OffscreenCanvas
+/// (which can also be used in a Web Worker). The context and texture helpers of this crate accept both kinds.
+pub trait GlCanvas {
+
+    /// Gets the width of the drawing buffer of this canvas, in pixels
+    fn get_width(&self) -> u32;
+
+    /// Gets the height of the drawing buffer of this canvas, in pixels
+    fn get_height(&self) -> u32;
+
+    /// Calls getContext(context_id, options) on this canvas
+    fn get_context_object(&self, context_id: &str, options: &JsValue) -> Result<Option<Object>, JsValue>;
+
+    /// Uploads the current content of this canvas to the texture that is currently bound to TEXTURE_2D
+    fn upload_to_texture(&self, gl: &GL) -> Result<(), JsValue>;
+}
+
+impl GlCanvas for HtmlCanvasElement {
+
+    fn get_width(&self) -> u32 {
+        self.width()
+    }
+
+    fn get_height(&self) -> u32 {
+        self.height()
+    }
+
+    fn get_context_object(&self, context_id: &str, options: &JsValue) -> Result<Option<Object>, JsValue> {
+        self.get_context_with_context_options(context_id, options)
+    }
+
+    fn upload_to_texture(&self, gl: &GL) -> Result<(), JsValue> {
+        gl.tex_image_2d_with_u32_and_u32_and_canvas(GL::TEXTURE_2D, 0, GL::RGBA as i32, GL::RGBA, GL::UNSIGNED_BYTE, self)
+    }
+}
+
+impl GlCanvas for OffscreenCanvas {
+
+    fn get_width(&self) -> u32 {
+        self.width()
+    }
+
+    fn get_height(&self) -> u32 {
+        self.height()
+    }
+
+    fn get_context_object(&self, context_id: &str, options: &JsValue) -> Result<Option<Object>, JsValue> {
+        self.get_context_with_context_options(context_id, options)
+    }
+
+    fn upload_to_texture(&self, gl: &GL) -> Result<(), JsValue> {
+        gl.tex_image_2d_with_u32_and_u32_and_offscreen_canvas(GL::TEXTURE_2D, 0, GL::RGBA as i32, GL::RGBA, GL::UNSIGNED_BYTE, self)
+    }
+}
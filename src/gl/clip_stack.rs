@@ -0,0 +1,156 @@
+use web_sys::WebGlRenderingContext as GL;
+
+use crate::{
+    BufferUsage,
+    PixelRect,
+    Region,
+    RendererError,
+    ShaderProgram,
+    VertexBuffer,
+    VertexLayout,
+    gl_pixel_rect
+};
+
+const VERTEX_SHADER: &str = "
+attribute vec2 a_position;
+
+void main() {
+    gl_Position = vec4(a_position, 0.0, 1.0);
+}
+";
+
+const FRAGMENT_SHADER: &str = "
+precision mediump float;
+
+void main() {
+    gl_FragColor = vec4(1.0);
+}
+";
+
+/// The stencil buffer has (at least) 8 bits, so this is the deepest nesting that can be represented
+const MAX_DEPTH: usize = 255;
+
+/// Clips everything that is drawn to the intersection of a stack of Regions, using the stencil buffer. Unlike scissor
+/// rectangles, the clip areas are drawn as geometry, so this will also work for clip areas that are not rectangles.
+/// Nested scrolling containers can simply push their region before drawing their children and pop it afterwards.
+///
+/// The context must have a stencil buffer (see ContextOptions::stencil), and nothing else should change the stencil
+/// state while the stack is not empty.
+pub struct ClipStack {
+    program: ShaderProgram,
+    layout: VertexLayout,
+    vertex_buffer: VertexBuffer,
+    clip_rects: Vec<(PixelRect, (u32, u32))>
+}
+
+impl ClipStack {
+
+    pub fn new(gl: &GL) -> Result<ClipStack, RendererError> {
+        let program = ShaderProgram::compile(gl, VERTEX_SHADER, FRAGMENT_SHADER)?;
+        let position = program.attribute_location("a_position").expect("The clip shader uses a_position");
+        let vertex_buffer = match VertexBuffer::new(gl, BufferUsage::Dynamic) {
+            Ok(vertex_buffer) => vertex_buffer,
+            Err(error) => {
+                program.delete(gl);
+                return Err(error.into());
+            }
+        };
+        Ok(ClipStack {
+            program,
+            layout: VertexLayout::new().attribute(position, 2),
+            vertex_buffer,
+            clip_rects: Vec::new()
+        })
+    }
+
+    /// Gets the number of regions on this stack
+    pub fn depth(&self) -> usize {
+        self.clip_rects.len()
+    }
+
+    /// Restricts all drawing to the intersection of the given region (of a canvas with the given size in pixels) and
+    /// the regions that are already on the stack. This uses the current program and vertex buffer bindings, so they
+    /// need to be bound again afterwards.
+    ///
+    /// Panics if the stack is already 255 regions deep.
+    pub fn push(&mut self, gl: &GL, region: Region, canvas_size: (u32, u32)) {
+        assert!(self.clip_rects.len() < MAX_DEPTH, "The clip stack can't be deeper than {} regions", MAX_DEPTH);
+        if self.clip_rects.is_empty() {
+            gl.clear_stencil(0);
+            gl.clear(GL::STENCIL_BUFFER_BIT);
+            gl.enable(GL::STENCIL_TEST);
+        }
+
+        let rect = gl_pixel_rect(region, canvas_size);
+        self.draw_stencil(gl, rect, canvas_size, GL::INCR);
+        self.clip_rects.push((rect, canvas_size));
+        self.apply_stencil_test(gl);
+    }
+
+    /// Removes the region that was pushed last, so that drawing is restricted to the remaining regions again. Like
+    /// push, this uses the current program and vertex buffer bindings.
+    ///
+    /// Panics if the stack is empty.
+    pub fn pop(&mut self, gl: &GL) {
+        let (rect, canvas_size) = *self.clip_rects.last().expect("The clip stack shouldn't be empty");
+        self.draw_stencil(gl, rect, canvas_size, GL::DECR);
+        self.clip_rects.pop();
+
+        if self.clip_rects.is_empty() {
+            gl.disable(GL::STENCIL_TEST);
+        } else {
+            self.apply_stencil_test(gl);
+        }
+    }
+
+    /// Only lets the pixels through that are inside every region on the stack
+    fn apply_stencil_test(&self, gl: &GL) {
+        gl.stencil_func(GL::EQUAL, self.clip_rects.len() as i32, 0xFF);
+        gl.stencil_op(GL::KEEP, GL::KEEP, GL::KEEP);
+    }
+
+    /// Increments or decrements the stencil values of the pixels in the rect that are inside all regions on the stack
+    fn draw_stencil(&mut self, gl: &GL, rect: PixelRect, canvas_size: (u32, u32), operation: u32) {
+        gl.color_mask(false, false, false, false);
+        gl.stencil_func(GL::EQUAL, self.clip_rects.len() as i32, 0xFF);
+        gl.stencil_op(GL::KEEP, GL::KEEP, operation);
+
+        self.program.use_program(gl);
+        self.vertex_buffer.upload(gl, &clip_vertices(rect, canvas_size));
+        self.layout.apply(gl, &self.vertex_buffer);
+        gl.draw_arrays(GL::TRIANGLE_STRIP, 0, 4);
+
+        gl.color_mask(true, true, true, true);
+    }
+
+    /// Deletes the GL resources of this stack. This struct must not be used anymore after calling this.
+    pub fn delete(self, gl: &GL) {
+        self.program.delete(gl);
+        self.vertex_buffer.delete(gl);
+    }
+}
+
+/// Converts the rect (in GL window coordinates) to the 4 vertices of a triangle strip in clip space
+fn clip_vertices(rect: PixelRect, canvas_size: (u32, u32)) -> [f32; 8] {
+    let to_clip = |pixel: i32, size: u32| pixel as f32 / size as f32 * 2.0 - 1.0;
+    let min_x = to_clip(rect.x, canvas_size.0);
+    let min_y = to_clip(rect.y, canvas_size.1);
+    let max_x = to_clip(rect.x + rect.width, canvas_size.0);
+    let max_y = to_clip(rect.y + rect.height, canvas_size.1);
+    [min_x, min_y, max_x, min_y, min_x, max_y, max_x, max_y]
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_clip_vertices() {
+        let whole = gl_pixel_rect(Region::entire_viewport(), (800, 600));
+        assert_eq!([-1.0, -1.0, 1.0, -1.0, -1.0, 1.0, 1.0, 1.0], clip_vertices(whole, (800, 600)));
+
+        let rect = PixelRect { x: 200, y: 150, width: 400, height: 300 };
+        assert_eq!([-0.5, -0.5, 0.5, -0.5, -0.5, 0.5, 0.5, 0.5], clip_vertices(rect, (800, 600)));
+    }
+}
@@ -0,0 +1,205 @@
+use web_sys::WebGlRenderingContext as GL;
+
+use crate::{
+    Arena,
+    Color,
+    DirtyRegionTracker,
+    Handle,
+    LogLevel,
+    Region,
+    gl_scissor_region,
+    log_enabled_for,
+    log_target
+};
+
+/// The log target of the compositor, which can be used to enable its debug messages (see set_log_filter)
+pub const COMPOSITOR_LOG_TARGET: &str = "compositor";
+
+/// The function that draws a component. The region is the dirty region that is being redrawn: the scissor test is
+/// already limited to it, but components can use it to skip work outside of it.
+type RenderFunction = Box<dyn FnMut(&GL, Region)>;
+
+struct CompositedComponent {
+    region: Region,
+    render: RenderFunction
+}
+
+/// Only redraws the parts of the canvas that changed. Every frame, the background and the components that overlap
+/// with a dirty region are redrawn with the scissor test limited to that region, and the rest of the canvas keeps its
+/// content from the previous frame. When nothing is dirty, the frame is skipped entirely.
+///
+/// This requires the drawing buffer to be preserved between frames, which is the default of ContextOptions (see
+/// ContextOptions::preserve_drawing_buffer).
+pub struct Compositor {
+
+    components: Arena<CompositedComponent>,
+    // The components in the order in which they are drawn (back to front)
+    order: Vec<Handle>,
+    dirty: DirtyRegionTracker,
+    // The dirty regions that were redrawn during the last frame
+    redrawn: Vec<Region>,
+    background: Color
+}
+
+impl Compositor {
+
+    /// Creates a compositor without components, which fills the dirty regions with the background color before
+    /// drawing the components in them. Everything is dirty at the start, so the first frame redraws the entire canvas.
+    pub fn new(background: Color) -> Compositor {
+        let mut dirty = DirtyRegionTracker::new();
+        dirty.mark_all_dirty();
+        Compositor {
+            components: Arena::new(),
+            order: Vec::new(),
+            dirty,
+            redrawn: Vec::new(),
+            background
+        }
+    }
+
+    /// Adds a component that occupies the given region, and will be drawn in front of all previously added components.
+    /// The region of the component becomes dirty.
+    pub fn add_component(&mut self, region: Region, render: RenderFunction) -> Handle {
+        let handle = self.components.insert(CompositedComponent {
+            region,
+            render
+        });
+        self.order.push(handle);
+        self.dirty.mark_dirty(region);
+        handle
+    }
+
+    /// Removes the component, and marks its region as dirty.
+    pub fn remove_component(&mut self, component: Handle) {
+        if let Some(removed) = self.components.remove(component) {
+            self.order.retain(|handle| *handle != component);
+            self.dirty.mark_dirty(removed.region);
+        }
+    }
+
+    /// Moves (or resizes) the component, and marks both its old and new region as dirty.
+    pub fn set_component_region(&mut self, component: Handle, region: Region) {
+        if let Some(component) = self.components.get_mut(component) {
+            self.dirty.mark_dirty(component.region);
+            self.dirty.mark_dirty(region);
+            component.region = region;
+        }
+    }
+
+    /// Marks the region of the component as dirty, which should be done when its content changes.
+    pub fn mark_component_dirty(&mut self, component: Handle) {
+        if let Some(component) = self.components.get(component) {
+            self.dirty.mark_dirty(component.region);
+        }
+    }
+
+    pub fn mark_dirty(&mut self, region: Region) {
+        self.dirty.mark_dirty(region);
+    }
+
+    /// Marks the entire canvas as dirty. This is needed after the canvas was resized or the context was restored,
+    /// since the drawing buffer is gone in that case.
+    pub fn mark_all_dirty(&mut self) {
+        self.dirty.mark_all_dirty();
+    }
+
+    /// Changes the background color, which makes everything dirty.
+    pub fn set_background(&mut self, background: Color) {
+        self.background = background;
+        self.dirty.mark_all_dirty();
+    }
+
+    /// Returns true if the next call to render_frame will draw something.
+    pub fn needs_redraw(&self) -> bool {
+        !self.dirty.is_clean()
+    }
+
+    /// Gets the regions of all components, in the order in which they are drawn (back to front).
+    pub fn get_component_regions(&self) -> Vec<Region> {
+        self.order.iter().map(|handle| self.components.get(*handle)
+            .expect("The order only contains handles of existing components").region).collect()
+    }
+
+    /// Gets the dirty regions that were redrawn during the last call to render_frame (which is empty if that frame was
+    /// skipped).
+    pub fn get_redrawn_regions(&self) -> &[Region] {
+        &self.redrawn
+    }
+
+    /// Redraws the dirty regions of the canvas (which has the given size in pixels), and clears them. Returns false if
+    /// the frame was skipped because nothing was dirty. The scissor test is disabled afterwards.
+    pub fn render_frame(&mut self, gl: &GL, canvas_size: (u32, u32)) -> bool {
+        self.redrawn.clear();
+        if self.dirty.is_clean() {
+            return false;
+        }
+
+        let component_regions = self.get_component_regions();
+
+        gl.enable(GL::SCISSOR_TEST);
+        let background = self.background;
+        gl.clear_color(background.get_red_float(), background.get_green_float(), background.get_blue_float(), background.get_alpha_float());
+        self.redrawn = self.dirty.take();
+        if log_enabled_for(COMPOSITOR_LOG_TARGET, LogLevel::Debug) {
+            log_target(LogLevel::Debug, COMPOSITOR_LOG_TARGET, &format!("Redrawing the dirty regions {:?}", self.redrawn));
+        }
+        for (dirty_region, affected) in plan_frame(&self.redrawn, &component_regions) {
+            gl_scissor_region(gl, dirty_region, canvas_size);
+            gl.clear(GL::COLOR_BUFFER_BIT);
+            for index in affected {
+                let component = self.components.get_mut(self.order[index]).expect("The order only contains handles of existing components");
+                (component.render)(gl, dirty_region);
+            }
+        }
+        gl.disable(GL::SCISSOR_TEST);
+        true
+    }
+}
+
+/// Determines which components (given by their regions, in drawing order) need to be drawn for each dirty region.
+fn plan_frame(dirty_regions: &[Region], component_regions: &[Region]) -> Vec<(Region, Vec<usize>)> {
+    dirty_regions.iter().map(|dirty_region| {
+        let affected = component_regions.iter().enumerate()
+            .filter(|(_, region)| region.intersects_with(*dirty_region))
+            .map(|(index, _)| index).collect();
+        (*dirty_region, affected)
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_plan_frame() {
+        let components = [Region::new(0, 0, 100, 100), Region::new(200, 0, 300, 100), Region::new(50, 50, 250, 80)];
+        let dirty = [Region::new(10, 10, 20, 20), Region::new(1_000, 1_000, 2_000, 2_000), Region::new(240, 60, 260, 70)];
+        assert_eq!(vec![
+            (dirty[0], vec![0]),
+            (dirty[1], vec![]),
+            (dirty[2], vec![1, 2])
+        ], plan_frame(&dirty, &components));
+    }
+
+    #[test]
+    fn test_skip_clean_frames() {
+        let mut compositor = Compositor::new(Color::WHITE);
+        assert!(compositor.needs_redraw());
+        compositor.dirty.take();
+        assert!(!compositor.needs_redraw());
+
+        let component = compositor.add_component(Region::new(0, 0, 100, 100), Box::new(|_, _| {}));
+        assert_eq!(&[Region::new(0, 0, 100, 100)], compositor.dirty.get_regions());
+        compositor.dirty.take();
+
+        compositor.set_component_region(component, Region::new(500, 0, 600, 100));
+        assert_eq!(&[Region::new(0, 0, 100, 100), Region::new(500, 0, 600, 100)], compositor.dirty.get_regions());
+        compositor.dirty.take();
+
+        compositor.remove_component(component);
+        compositor.remove_component(component);
+        assert_eq!(&[Region::new(500, 0, 600, 100)], compositor.dirty.get_regions());
+        assert!(compositor.order.is_empty());
+    }
+}
@@ -0,0 +1,150 @@
+use js_sys::Object;
+
+use wasm_bindgen::{
+    JsCast,
+    JsValue
+};
+
+use web_sys::{
+    HtmlCanvasElement,
+    OffscreenCanvas,
+    WebGlRenderingContext,
+    WebGl2RenderingContext
+};
+
+use crate::{
+    ContextOptions,
+    GlCanvas
+};
+
+/// The reasons why a WebGL context can't be acquired.
+#[derive(Debug)]
+pub enum GlInitError {
+    /// getContext threw an exception, for instance because the canvas already has a context of another type
+    GetContextFailed(JsValue),
+    /// getContext returned null: the browser doesn't support this kind of context, WebGL is disabled or blocked
+    /// (which is common in privacy-focused browsers), or failIfMajorPerformanceCaveat prevented it
+    Unavailable,
+    /// getContext returned an object that is not a context of the requested kind
+    WrongType
+}
+
+impl std::fmt::Display for GlInitError {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            GlInitError::GetContextFailed(reason) => write!(f, "getContext failed: {:?}", reason),
+            GlInitError::Unavailable => write!(f, "WebGL is not available (it might be unsupported, disabled or blocked)"),
+            GlInitError::WrongType => write!(f, "getContext returned an object of the wrong type")
+        }
+    }
+}
+
+impl std::error::Error for GlInitError {}
+
+/// Acquires a WebGL context for the canvas. This panics if WebGL is not available, so use try_get_gl instead when
+/// applications should show a fallback message in that case.
+pub fn get_gl(canvas: &HtmlCanvasElement) -> WebGlRenderingContext {
+    get_gl_with_options(canvas, ContextOptions::new())
+}
+
+/// Like get_gl, but uses the given context options instead of the default ones.
+pub fn get_gl_with_options<C: GlCanvas>(canvas: &C, options: ContextOptions) -> WebGlRenderingContext {
+    try_get_gl_with_options(canvas, options).unwrap_or_else(|error| panic!("Failed to get the webgl context: {}", error))
+}
+
+/// Like get_gl, but for an OffscreenCanvas. This also works inside a Web Worker, which allows heavy user interfaces
+/// to render without blocking the main thread.
+pub fn get_gl_offscreen(canvas: &OffscreenCanvas) -> WebGlRenderingContext {
+    get_gl_with_options(canvas, ContextOptions::new())
+}
+
+/// Like get_gl, but acquires a WebGL2 context. This will panic if the browser doesn't support WebGL2, so use
+/// GlContext::new instead if WebGL1 should be used as fallback.
+pub fn get_gl2(canvas: &HtmlCanvasElement) -> WebGl2RenderingContext {
+    get_gl2_with_options(canvas, ContextOptions::new())
+}
+
+/// Like get_gl2, but uses the given context options instead of the default ones.
+pub fn get_gl2_with_options<C: GlCanvas>(canvas: &C, options: ContextOptions) -> WebGl2RenderingContext {
+    try_get_gl2_with_options(canvas, options).unwrap_or_else(|error| panic!("Failed to get the webgl2 context: {}", error))
+}
+
+/// Like get_gl, but returns an error instead of panicking when the context can't be acquired.
+pub fn try_get_gl(canvas: &HtmlCanvasElement) -> Result<WebGlRenderingContext, GlInitError> {
+    try_get_gl_with_options(canvas, ContextOptions::new())
+}
+
+/// Like get_gl_with_options, but returns an error instead of panicking when the context can't be acquired.
+pub fn try_get_gl_with_options<C: GlCanvas>(canvas: &C, options: ContextOptions) -> Result<WebGlRenderingContext, GlInitError> {
+    get_context(canvas, "webgl", options)?.ok_or(GlInitError::Unavailable)?.dyn_into::<WebGlRenderingContext>()
+        .map_err(|_| GlInitError::WrongType)
+}
+
+/// Like get_gl2_with_options, but returns an error instead of panicking when the context can't be acquired.
+pub fn try_get_gl2_with_options<C: GlCanvas>(canvas: &C, options: ContextOptions) -> Result<WebGl2RenderingContext, GlInitError> {
+    get_context(canvas, "webgl2", options)?.ok_or(GlInitError::Unavailable)?.dyn_into::<WebGl2RenderingContext>()
+        .map_err(|_| GlInitError::WrongType)
+}
+
+fn get_context<C: GlCanvas>(canvas: &C, context_id: &str, options: ContextOptions) -> Result<Option<Object>, GlInitError> {
+    canvas.get_context_object(context_id, &options.to_js()).map_err(GlInitError::GetContextFailed)
+}
+
+/// A WebGL1 or WebGL2 context. Renderers can use gl() for everything WebGL1 supports, and opportunistically use
+/// WebGL2 features (like vertex array objects and instancing) when gl2() returns Some.
+#[derive(Clone,Debug)]
+pub enum GlContext {
+
+    WebGl1(WebGlRenderingContext),
+    WebGl2(WebGl2RenderingContext)
+}
+
+impl GlContext {
+
+    /// Acquires a WebGL2 context for the canvas if the browser supports it, and a WebGL1 context otherwise. The canvas
+    /// can be an HtmlCanvasElement or an OffscreenCanvas.
+    pub fn new<C: GlCanvas>(canvas: &C) -> GlContext {
+        Self::with_options(canvas, ContextOptions::new())
+    }
+
+    /// Like new, but uses the given context options instead of the default ones.
+    pub fn with_options<C: GlCanvas>(canvas: &C, options: ContextOptions) -> GlContext {
+        Self::try_with_options(canvas, options).unwrap_or_else(|error| panic!("Failed to get a webgl context: {}", error))
+    }
+
+    /// Like new, but returns an error instead of panicking when neither WebGL2 nor WebGL1 is available.
+    pub fn try_new<C: GlCanvas>(canvas: &C) -> Result<GlContext, GlInitError> {
+        Self::try_with_options(canvas, ContextOptions::new())
+    }
+
+    /// Like with_options, but returns an error instead of panicking when neither WebGL2 nor WebGL1 is available.
+    pub fn try_with_options<C: GlCanvas>(canvas: &C, options: ContextOptions) -> Result<GlContext, GlInitError> {
+        match try_get_gl2_with_options(canvas, options) {
+            Ok(gl2) => Ok(GlContext::WebGl2(gl2)),
+            Err(GlInitError::Unavailable) => Ok(GlContext::WebGl1(try_get_gl_with_options(canvas, options)?)),
+            Err(error) => Err(error)
+        }
+    }
+
+    pub fn is_webgl2(&self) -> bool {
+        matches!(self, GlContext::WebGl2(_))
+    }
+
+    /// Gets this context as WebGL1 context. WebGL2 contexts support all methods of WebGL1 contexts, so this also works
+    /// for WebGL2 contexts (even though WebGL2RenderingContext is not a subclass of WebGLRenderingContext).
+    pub fn gl(&self) -> &WebGlRenderingContext {
+        match self {
+            GlContext::WebGl1(gl) => gl,
+            GlContext::WebGl2(gl2) => gl2.unchecked_ref::<WebGlRenderingContext>()
+        }
+    }
+
+    /// Gets the WebGL2 context, or None if this is only a WebGL1 context.
+    pub fn gl2(&self) -> Option<&WebGl2RenderingContext> {
+        match self {
+            GlContext::WebGl1(_) => None,
+            GlContext::WebGl2(gl2) => Some(gl2)
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use js_sys::Reflect;
+
+use wasm_bindgen::JsValue;
+
+use web_sys::WebGlRenderingContext as GL;
+
+use crate::{
+    ContextOptions,
+    PowerPreference
+};
+
+/// The attributes that a WebGL context actually got, which can differ from the requested ContextOptions: browsers are
+/// allowed to ignore antialias, and some devices don't support stencil buffers (or fall back to software rendering).
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub struct ContextAttributes {
+
+    pub alpha: bool,
+    pub antialias: bool,
+    pub depth: bool,
+    pub stencil: bool,
+    pub premultiplied_alpha: bool,
+    pub preserve_drawing_buffer: bool,
+    pub desynchronized: bool,
+    pub fail_if_major_performance_caveat: bool,
+    pub power_preference: PowerPreference
+}
+
+/// Gets the actual attributes of the context, or None if the context is lost.
+pub fn context_attributes(gl: &GL) -> Option<ContextAttributes> {
+    let attributes = gl.get_context_attributes()?;
+    let get = |name: &str| Reflect::get(&attributes, &JsValue::from_str(name)).ok();
+    Some(ContextAttributes::from_lookup(
+        |name| get(name).and_then(|value| value.as_bool()),
+        get("powerPreference").and_then(|value| value.as_string())
+    ))
+}
+
+impl ContextAttributes {
+
+    /// Creates the attributes from the lookup function, which gets the (JavaScript) name of an attribute. Attributes
+    /// that are missing are assumed to be false.
+    fn from_lookup(lookup: impl Fn(&str) -> Option<bool>, power_preference: Option<String>) -> ContextAttributes {
+        let get = |name| lookup(name).unwrap_or(false);
+        ContextAttributes {
+            alpha: get("alpha"),
+            antialias: get("antialias"),
+            depth: get("depth"),
+            stencil: get("stencil"),
+            premultiplied_alpha: get("premultipliedAlpha"),
+            preserve_drawing_buffer: get("preserveDrawingBuffer"),
+            desynchronized: get("desynchronized"),
+            fail_if_major_performance_caveat: get("failIfMajorPerformanceCaveat"),
+            power_preference: power_preference.and_then(|name| PowerPreference::from_name(&name)).unwrap_or(PowerPreference::Default)
+        }
+    }
+
+    fn get(&self, name: &str) -> Option<bool> {
+        match name {
+            "alpha" => Some(self.alpha),
+            "antialias" => Some(self.antialias),
+            "depth" => Some(self.depth),
+            "stencil" => Some(self.stencil),
+            "premultipliedAlpha" => Some(self.premultiplied_alpha),
+            "preserveDrawingBuffer" => Some(self.preserve_drawing_buffer),
+            "desynchronized" => Some(self.desynchronized),
+            "failIfMajorPerformanceCaveat" => Some(self.fail_if_major_performance_caveat),
+            _ => None
+        }
+    }
+
+    /// Gets the (JavaScript) names of the options that were set explicitly, but were not honored by the browser.
+    pub fn unhonored_options(&self, options: ContextOptions) -> Vec<&'static str> {
+        options.entries().into_iter().filter(|(name, value)| self.get(name) != Some(*value)).map(|(name, _)| name).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_unhonored_options() {
+        let attributes = ContextAttributes::from_lookup(|name| match name {
+            "antialias" | "preserveDrawingBuffer" => Some(true),
+            "stencil" => None,
+            _ => Some(false)
+        }, None);
+        assert!(attributes.antialias && attributes.preserve_drawing_buffer);
+        assert!(!attributes.stencil && !attributes.depth);
+        assert_eq!(PowerPreference::Default, attributes.power_preference);
+
+        assert!(attributes.unhonored_options(ContextOptions::new()).is_empty());
+        assert_eq!(vec!["stencil"], attributes.unhonored_options(ContextOptions::new().antialias(true).stencil(true)));
+        assert_eq!(vec!["antialias", "preserveDrawingBuffer"], attributes.unhonored_options(
+            ContextOptions::new().antialias(false).preserve_drawing_buffer(false).depth(false)
+        ));
+    }
+}
@@ -0,0 +1,87 @@
+use std::cell::{
+    Cell,
+    RefCell
+};
+use std::rc::{
+    Rc,
+    Weak
+};
+
+use wasm_bindgen::JsCast;
+use wasm_bindgen::closure::Closure;
+
+use web_sys::{
+    Event,
+    HtmlCanvasElement
+};
+
+use crate::WeakVec;
+
+/// Watches a canvas for the webglcontextlost and webglcontextrestored events. Browsers can take the GPU context away
+/// from long-running (background) tabs at any time, which destroys all GL resources. This guard makes sure the
+/// context can be restored, and calls the restore callbacks once it has been restored, so that they can re-create
+/// their shaders, buffers and textures.
+/// 
+/// The event listeners are removed when this guard is dropped.
+pub struct GlContextGuard {
+    canvas: HtmlCanvasElement,
+    lost: Rc<Cell<bool>>,
+    restore_callbacks: Rc<RefCell<WeakVec<dyn FnMut()>>>,
+    on_lost: Closure<dyn FnMut(Event)>,
+    on_restored: Closure<dyn FnMut()>
+}
+
+impl GlContextGuard {
+
+    pub fn new(canvas: &HtmlCanvasElement) -> GlContextGuard {
+        let lost = Rc::new(Cell::new(false));
+        let restore_callbacks: Rc<RefCell<WeakVec<dyn FnMut()>>> = Rc::new(RefCell::new(WeakVec::new()));
+
+        let lost_clone = Rc::clone(&lost);
+        let on_lost = Closure::wrap(Box::new(move |event: Event| {
+            // Without this, the browser won't try to restore the context
+            event.prevent_default();
+            lost_clone.set(true);
+        }) as Box<dyn FnMut(Event)>);
+
+        let lost_clone = Rc::clone(&lost);
+        let callbacks_clone = Rc::clone(&restore_callbacks);
+        let on_restored = Closure::wrap(Box::new(move || {
+            lost_clone.set(false);
+            WeakVec::for_each_mut_shared(&callbacks_clone, |callback| callback());
+        }) as Box<dyn FnMut()>);
+
+        canvas.add_event_listener_with_callback("webglcontextlost", on_lost.as_ref().unchecked_ref())
+            .expect("Should be able to listen for webglcontextlost");
+        canvas.add_event_listener_with_callback("webglcontextrestored", on_restored.as_ref().unchecked_ref())
+            .expect("Should be able to listen for webglcontextrestored");
+
+        GlContextGuard {
+            canvas: canvas.clone(),
+            lost,
+            restore_callbacks,
+            on_lost,
+            on_restored
+        }
+    }
+
+    /// Checks if the context is currently lost. While it is lost, all GL calls are silently ignored, so rendering can
+    /// be skipped.
+    pub fn is_lost(&self) -> bool {
+        self.lost.get()
+    }
+
+    /// Adds a callback that will be called each time the context is restored after it was lost, until the last Rc to
+    /// the callback is dropped.
+    pub fn add_restore_callback(&self, callback: Weak<RefCell<dyn FnMut()>>) {
+        self.restore_callbacks.borrow_mut().push(callback);
+    }
+}
+
+impl Drop for GlContextGuard {
+
+    fn drop(&mut self) {
+        let _ = self.canvas.remove_event_listener_with_callback("webglcontextlost", self.on_lost.as_ref().unchecked_ref());
+        let _ = self.canvas.remove_event_listener_with_callback("webglcontextrestored", self.on_restored.as_ref().unchecked_ref());
+    }
+}
@@ -0,0 +1,230 @@
+use js_sys::{
+    Object,
+    Reflect
+};
+
+use wasm_bindgen::JsValue;
+
+/// The GPU that the browser should prefer for a WebGL context, on devices that have more than one.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum PowerPreference {
+
+    /// Let the browser decide
+    Default,
+    /// Prefer the GPU that uses the least power (normally the integrated GPU), to save battery
+    LowPower,
+    /// Prefer the fastest GPU (normally the discrete GPU)
+    HighPerformance
+}
+
+impl PowerPreference {
+
+    /// Gets the JavaScript name of this preference, for instance "low-power".
+    pub fn get_name(self) -> &'static str {
+        match self {
+            PowerPreference::Default => "default",
+            PowerPreference::LowPower => "low-power",
+            PowerPreference::HighPerformance => "high-performance"
+        }
+    }
+
+    /// Gets the preference with the given JavaScript name, or None if the name is unknown.
+    pub fn from_name(name: &str) -> Option<PowerPreference> {
+        match name {
+            "default" => Some(PowerPreference::Default),
+            "low-power" => Some(PowerPreference::LowPower),
+            "high-performance" => Some(PowerPreference::HighPerformance),
+            _ => None
+        }
+    }
+}
+
+/// The options (context attributes) that are passed to getContext when a WebGL context is created. Every option that
+/// is not set explicitly is left to the browser default, except preserveDrawingBuffer, which is true by default
+/// because the rest of this crate only redraws the parts of the canvas that changed.
+/// 
+/// All methods are const, so the options can also be defined as constants.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub struct ContextOptions {
+    alpha: Option<bool>,
+    antialias: Option<bool>,
+    depth: Option<bool>,
+    stencil: Option<bool>,
+    premultiplied_alpha: Option<bool>,
+    preserve_drawing_buffer: Option<bool>,
+    desynchronized: Option<bool>,
+    fail_if_major_performance_caveat: Option<bool>,
+    power_preference: Option<PowerPreference>
+}
+
+impl ContextOptions {
+
+    /// The options used by get_gl.
+    pub const fn new() -> ContextOptions {
+        ContextOptions {
+            alpha: None,
+            antialias: None,
+            depth: None,
+            stencil: None,
+            premultiplied_alpha: None,
+            preserve_drawing_buffer: Some(true),
+            desynchronized: None,
+            fail_if_major_performance_caveat: None,
+            power_preference: None
+        }
+    }
+
+    /// Whether the canvas has an alpha channel (so the page behind it can shine through).
+    pub const fn alpha(self, alpha: bool) -> ContextOptions {
+        ContextOptions {
+            alpha: Some(alpha),
+            ..self
+        }
+    }
+
+    /// Whether the drawing buffer should use MSAA (multisample anti-aliasing). Browsers are allowed to ignore this,
+    /// so use msaa_samples to check whether it was honored, and fall back to an FxaaPass if it wasn't.
+    pub const fn antialias(self, antialias: bool) -> ContextOptions {
+        ContextOptions {
+            antialias: Some(antialias),
+            ..self
+        }
+    }
+
+    /// Whether the drawing buffer has a depth buffer of at least 16 bits. This is needed to order widgets with Layer
+    /// and DepthPass instead of the drawing order.
+    pub const fn depth(self, depth: bool) -> ContextOptions {
+        ContextOptions {
+            depth: Some(depth),
+            ..self
+        }
+    }
+
+    /// Whether the drawing buffer has a stencil buffer of at least 8 bits.
+    pub const fn stencil(self, stencil: bool) -> ContextOptions {
+        ContextOptions {
+            stencil: Some(stencil),
+            ..self
+        }
+    }
+
+    /// Whether the page compositor should assume that the colors in the drawing buffer have premultiplied alpha.
+    pub const fn premultiplied_alpha(self, premultiplied_alpha: bool) -> ContextOptions {
+        ContextOptions {
+            premultiplied_alpha: Some(premultiplied_alpha),
+            ..self
+        }
+    }
+
+    /// Whether the drawing buffer keeps its content after it has been presented. If this is false, everything needs
+    /// to be redrawn during every frame.
+    pub const fn preserve_drawing_buffer(self, preserve_drawing_buffer: bool) -> ContextOptions {
+        ContextOptions {
+            preserve_drawing_buffer: Some(preserve_drawing_buffer),
+            ..self
+        }
+    }
+
+    /// Hints the browser to reduce latency by decoupling the canvas from the event loop.
+    pub const fn desynchronized(self, desynchronized: bool) -> ContextOptions {
+        ContextOptions {
+            desynchronized: Some(desynchronized),
+            ..self
+        }
+    }
+
+    /// Whether context creation should fail if the system performance is low, for instance because the browser
+    /// would fall back to software rendering. This is useful for benchmarks, and for apps that would rather show a
+    /// simpler user interface than a slow one.
+    ///
+    /// Context creation fails when this is true on such a system, in which case try_get_gl_with_options returns
+    /// GlInitError::Unavailable (and get_gl_with_options panics). Apps that can handle slow systems should then try
+    /// again with relaxed().
+    pub const fn fail_if_major_performance_caveat(self, fail_if_major_performance_caveat: bool) -> ContextOptions {
+        ContextOptions {
+            fail_if_major_performance_caveat: Some(fail_if_major_performance_caveat),
+            ..self
+        }
+    }
+
+    /// Which GPU the browser should prefer. Battery-sensitive apps should use LowPower. This is only a hint: context
+    /// creation will not fail when the preferred GPU is not available, so relaxed() keeps it.
+    pub const fn power_preference(self, power_preference: PowerPreference) -> ContextOptions {
+        ContextOptions {
+            power_preference: Some(power_preference),
+            ..self
+        }
+    }
+
+    /// Gets a copy of these options without the option that can cause context creation to fail:
+    /// failIfMajorPerformanceCaveat. Use this as fallback when context creation with these options failed.
+    pub const fn relaxed(self) -> ContextOptions {
+        ContextOptions {
+            fail_if_major_performance_caveat: None,
+            ..self
+        }
+    }
+
+    /// Gets the (JavaScript) names and values of the boolean options that have been set.
+    pub(crate) fn entries(&self) -> Vec<(&'static str, bool)> {
+        let options = [
+            ("alpha", self.alpha),
+            ("antialias", self.antialias),
+            ("depth", self.depth),
+            ("stencil", self.stencil),
+            ("premultipliedAlpha", self.premultiplied_alpha),
+            ("preserveDrawingBuffer", self.preserve_drawing_buffer),
+            ("desynchronized", self.desynchronized),
+            ("failIfMajorPerformanceCaveat", self.fail_if_major_performance_caveat)
+        ];
+        options.iter().filter_map(|(name, value)| value.map(|value| (*name, value))).collect()
+    }
+
+    pub(crate) fn to_js(self) -> Object {
+        let options = Object::new();
+        for (name, value) in self.entries() {
+            Reflect::set(&options, &JsValue::from_str(name), &JsValue::from_bool(value)).expect("Should be able to set context options");
+        }
+        if let Some(power_preference) = self.power_preference {
+            Reflect::set(&options, &JsValue::from_str("powerPreference"), &JsValue::from_str(power_preference.get_name()))
+                .expect("Should be able to set context options");
+        }
+        options
+    }
+}
+
+impl Default for ContextOptions {
+
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_entries() {
+        assert_eq!(vec![("preserveDrawingBuffer", true)], ContextOptions::new().entries());
+
+        const OPTIONS: ContextOptions = ContextOptions::new().antialias(false).preserve_drawing_buffer(false).stencil(true);
+        assert_eq!(vec![("antialias", false), ("stencil", true), ("preserveDrawingBuffer", false)], OPTIONS.entries());
+    }
+
+    #[test]
+    fn test_relaxed() {
+        let options = ContextOptions::new().stencil(true).fail_if_major_performance_caveat(true).power_preference(PowerPreference::LowPower);
+        assert_eq!(vec![("stencil", true), ("preserveDrawingBuffer", true), ("failIfMajorPerformanceCaveat", true)], options.entries());
+        assert_eq!(ContextOptions::new().stencil(true).power_preference(PowerPreference::LowPower), options.relaxed());
+    }
+
+    #[test]
+    fn test_power_preference_names() {
+        for preference in [PowerPreference::Default, PowerPreference::LowPower, PowerPreference::HighPerformance].iter() {
+            assert_eq!(Some(*preference), PowerPreference::from_name(preference.get_name()));
+        }
+        assert_eq!(None, PowerPreference::from_name("turbo"));
+    }
+}
@@ -0,0 +1,130 @@
+use std::fmt::Debug;
+
+use web_sys::{
+    WebGlBuffer,
+    WebGlFramebuffer,
+    WebGlProgram,
+    WebGlRenderingContext as GL,
+    WebGlTexture,
+    WebGlUniformLocation
+};
+
+use crate::error;
+
+/// Generates methods that forward to the method of the wrapped context with the same name, and check for errors
+/// afterwards.
+macro_rules! checked_gl_calls {
+    ($($name:ident($($argument:ident: $argument_type:ty),*);)*) => {
+        $(
+            pub fn $name(&self, $($argument: $argument_type),*) {
+                self.gl.$name($($argument),*);
+                self.check_errors(stringify!($name), &[$(&$argument as &dyn Debug),*]);
+            }
+        )*
+    };
+}
+
+/// Wraps a WebGL context and calls getError after every operation that goes through the wrapper. Any error is logged
+/// with util::error, together with the name and arguments of the call that caused it. Without this, most mistakes
+/// (like binding a deleted texture or drawing out of bounds) silently result in a black canvas.
+///
+/// Checking for errors after every call forces the GPU to synchronize, so this is only available with the debug-gl
+/// feature and should not be used in release builds. Operations that are not wrapped can still be performed through
+/// gl(), and checked manually with check.
+pub struct DebugGl {
+    gl: GL
+}
+
+impl DebugGl {
+
+    pub fn new(gl: GL) -> DebugGl {
+        DebugGl {
+            gl
+        }
+    }
+
+    /// Gets the wrapped context
+    pub fn gl(&self) -> &GL {
+        &self.gl
+    }
+
+    /// Logs all errors that occurred since the last check, and returns the number of errors. The operation is only
+    /// used in the log message.
+    pub fn check(&self, operation: &str) -> usize {
+        self.check_errors(operation, &[])
+    }
+
+    fn check_errors(&self, operation: &str, arguments: &[&dyn Debug]) -> usize {
+        let mut num_errors = 0;
+        loop {
+            // A context can have multiple error flags set at the same time, and getError only clears one of them
+            let code = self.gl.get_error();
+            if code == GL::NO_ERROR || code == GL::CONTEXT_LOST_WEBGL {
+                return num_errors;
+            }
+
+            num_errors += 1;
+            error(&format!("GL error {} after {}", error_name(code), format_call(operation, arguments)));
+        }
+    }
+
+    checked_gl_calls! {
+        active_texture(texture: u32);
+        bind_buffer(target: u32, buffer: Option<&WebGlBuffer>);
+        bind_framebuffer(target: u32, framebuffer: Option<&WebGlFramebuffer>);
+        bind_texture(target: u32, texture: Option<&WebGlTexture>);
+        blend_func(source_factor: u32, destination_factor: u32);
+        clear(mask: u32);
+        clear_color(red: f32, green: f32, blue: f32, alpha: f32);
+        disable(capability: u32);
+        draw_arrays(mode: u32, first: i32, count: i32);
+        draw_elements_with_i32(mode: u32, count: i32, index_type: u32, offset: i32);
+        enable(capability: u32);
+        enable_vertex_attrib_array(index: u32);
+        scissor(x: i32, y: i32, width: i32, height: i32);
+        tex_parameteri(target: u32, parameter: u32, value: i32);
+        uniform1f(location: Option<&WebGlUniformLocation>, x: f32);
+        uniform1i(location: Option<&WebGlUniformLocation>, x: i32);
+        uniform2f(location: Option<&WebGlUniformLocation>, x: f32, y: f32);
+        uniform4f(location: Option<&WebGlUniformLocation>, x: f32, y: f32, z: f32, w: f32);
+        use_program(program: Option<&WebGlProgram>);
+        vertex_attrib_pointer_with_i32(index: u32, size: i32, component_type: u32, normalized: bool, stride: i32, offset: i32);
+        viewport(x: i32, y: i32, width: i32, height: i32);
+    }
+}
+
+fn error_name(error: u32) -> String {
+    match error {
+        GL::INVALID_ENUM => "INVALID_ENUM".to_string(),
+        GL::INVALID_VALUE => "INVALID_VALUE".to_string(),
+        GL::INVALID_OPERATION => "INVALID_OPERATION".to_string(),
+        GL::INVALID_FRAMEBUFFER_OPERATION => "INVALID_FRAMEBUFFER_OPERATION".to_string(),
+        GL::OUT_OF_MEMORY => "OUT_OF_MEMORY".to_string(),
+        other => format!("{:#x}", other)
+    }
+}
+
+fn format_call(operation: &str, arguments: &[&dyn Debug]) -> String {
+    let arguments: Vec<String> = arguments.iter().map(|argument| format!("{:?}", argument)).collect();
+    format!("{}({})", operation, arguments.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_error_name() {
+        assert_eq!("INVALID_OPERATION", error_name(GL::INVALID_OPERATION));
+        assert_eq!("0x1234", error_name(0x1234));
+    }
+
+    #[test]
+    fn test_format_call() {
+        assert_eq!("clear()", format_call("clear", &[]));
+        assert_eq!("viewport(0, 0, 800, 600)", format_call("viewport", &[&0, &0, &800, &600]));
+        let no_texture: Option<&WebGlTexture> = None;
+        assert_eq!("bind_texture(3553, None)", format_call("bind_texture", &[&GL::TEXTURE_2D, &no_texture]));
+    }
+}
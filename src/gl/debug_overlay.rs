@@ -0,0 +1,219 @@
+use web_sys::WebGlRenderingContext as GL;
+
+use crate::{
+    BlendMode,
+    Color,
+    Compositor,
+    FULL_UV,
+    FrameStats,
+    QuadBatch,
+    Region,
+    RendererError,
+    TextColors,
+    TextRenderer,
+    pixel_size_to_region,
+    text_region
+};
+
+/// The width of the outlines, in pixels
+const OUTLINE_WIDTH: u32 = 2;
+
+const FPS_FONT: &str = "14px monospace";
+const FPS_COLORS: TextColors = TextColors::new(Color::WHITE, Color::BLACK, Color::from_rgba(0, 0, 0, 160));
+
+const DIRTY_REGION_COLOR: Color = Color::from_rgba(255, 0, 0, 200);
+const COMPONENT_REGION_COLOR: Color = Color::from_rgba(0, 200, 255, 160);
+
+/// Draws diagnostics on top of the WebGL canvas: the FPS (from a FrameStats), outlines of the dirty regions that were
+/// redrawn in the last frame, and outlines of the component regions. Each of them can be toggled, and the overlay as
+/// a whole can be toggled at runtime (for instance with a keyboard shortcut), in which case it draws nothing.
+///
+/// Since the overlay draws over the content of the canvas, the regions it covered must be redrawn in the next frame.
+/// render_compositor_frame takes care of that by marking them dirty in the Compositor. The outlines of these regions
+/// are not shown in the next frame, so that the overlay doesn't show its own traces.
+pub struct DebugOverlay {
+
+    batch: QuadBatch,
+    text_renderer: TextRenderer,
+
+    enabled: bool,
+    show_fps: bool,
+    show_dirty_regions: bool,
+    show_component_regions: bool,
+
+    // The regions that were covered by the overlay in the previous frame
+    covered: Vec<Region>
+}
+
+impl DebugOverlay {
+
+    /// Creates a disabled overlay that shows everything once it is enabled.
+    pub fn new(gl: &GL) -> Result<DebugOverlay, RendererError> {
+        Ok(DebugOverlay {
+            batch: QuadBatch::new(gl)?,
+            text_renderer: TextRenderer::new(),
+            enabled: false,
+            show_fps: true,
+            show_dirty_regions: true,
+            show_component_regions: true,
+            covered: Vec::new()
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Enables the overlay if it is disabled, and disables it otherwise.
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+    }
+
+    pub fn set_show_fps(&mut self, show_fps: bool) {
+        self.show_fps = show_fps;
+    }
+
+    pub fn set_show_dirty_regions(&mut self, show_dirty_regions: bool) {
+        self.show_dirty_regions = show_dirty_regions;
+    }
+
+    pub fn set_show_component_regions(&mut self, show_component_regions: bool) {
+        self.show_component_regions = show_component_regions;
+    }
+
+    /// Lets the compositor render a frame, and draws the overlay on top of it (if it is enabled). The regions covered
+    /// by the overlay are marked dirty, so the compositor will keep rendering frames while the overlay is enabled.
+    /// Returns whether anything was drawn.
+    pub fn render_compositor_frame(
+        &mut self, gl: &GL, canvas_size: (u32, u32), compositor: &mut Compositor, frame_stats: Option<&FrameStats>
+    ) -> Result<bool, RendererError> {
+        let rendered = compositor.render_frame(gl, canvas_size);
+        if !self.enabled {
+            self.covered.clear();
+            return Ok(rendered);
+        }
+
+        self.draw(gl, canvas_size, frame_stats, compositor.get_redrawn_regions(), &compositor.get_component_regions())?;
+        for region in &self.covered {
+            compositor.mark_dirty(*region);
+        }
+        Ok(true)
+    }
+
+    /// Draws the overlay on top of the canvas (which has the given size in pixels), unless it is disabled. This can be
+    /// used without Compositor, but the caller is then responsible for redrawing the covered regions (see
+    /// get_covered_regions) in the next frame.
+    pub fn draw(
+        &mut self, gl: &GL, canvas_size: (u32, u32), frame_stats: Option<&FrameStats>,
+        dirty_regions: &[Region], component_regions: &[Region]
+    ) -> Result<(), RendererError> {
+        let previously_covered = std::mem::take(&mut self.covered);
+        if !self.enabled {
+            return Ok(());
+        }
+
+        let (canvas_width, canvas_height) = canvas_size;
+        let outline_width = pixel_size_to_region(OUTLINE_WIDTH, OUTLINE_WIDTH, canvas_width, canvas_height);
+        let mut z = 0;
+        let mut push_outline = |batch: &mut QuadBatch, covered: &mut Vec<Region>, region: Region, color: Color| {
+            for side in outline(region, outline_width).iter() {
+                z += 1;
+                batch.push_color(*side, color, z);
+                covered.push(*side);
+            }
+        };
+
+        if self.show_component_regions {
+            for region in component_regions {
+                push_outline(&mut self.batch, &mut self.covered, *region, COMPONENT_REGION_COLOR);
+            }
+        }
+        if self.show_dirty_regions {
+            for region in dirty_regions.iter().filter(|region| !previously_covered.iter().any(|covered| region.is_covered_by(*covered))) {
+                push_outline(&mut self.batch, &mut self.covered, *region, DIRTY_REGION_COLOR);
+            }
+        }
+
+        let mut fps_text = None;
+        if self.show_fps {
+            let label = match frame_stats {
+                Some(stats) => fps_label(stats.fps(), stats.average()),
+                None => fps_label(None, None)
+            };
+            let rendered = self.text_renderer.render(gl, &label, FPS_FONT, FPS_COLORS)?;
+            let region = text_region(Region::entire_viewport(), rendered.size_in_region(canvas_width, canvas_height));
+            self.batch.push_texture(region, &rendered.texture, FULL_UV, Color::WHITE, z + 1);
+            self.covered.push(region);
+            fps_text = Some(rendered);
+        }
+
+        BlendMode::Alpha.apply(gl);
+        self.batch.flush(gl);
+        if let Some(text) = fps_text {
+            text.texture.delete(gl);
+        }
+        Ok(())
+    }
+
+    /// Gets the regions that were covered by the overlay during the last call to draw.
+    pub fn get_covered_regions(&self) -> &[Region] {
+        &self.covered
+    }
+
+    /// Deletes the GL resources of this overlay. This struct must not be used anymore after calling this.
+    pub fn delete(self, gl: &GL) {
+        self.batch.delete(gl);
+    }
+}
+
+/// Gets the bottom, top, left and right side of the outline of the region, where outline_width is the (horizontal,
+/// vertical) width of the outline in Region coordinates. The outline is inside the region.
+fn outline(region: Region, outline_width: (i32, i32)) -> [Region; 4] {
+    let (width_x, width_y) = outline_width;
+    let (min_x, min_y, max_x, max_y) = (region.get_min_x(), region.get_min_y(), region.get_max_x(), region.get_max_y());
+    [
+        Region::new(min_x, min_y, max_x, (min_y + width_y - 1).min(max_y)),
+        Region::new(min_x, (max_y - width_y + 1).max(min_y), max_x, max_y),
+        Region::new(min_x, min_y, (min_x + width_x - 1).min(max_x), max_y),
+        Region::new((max_x - width_x + 1).max(min_x), min_y, max_x, max_y)
+    ]
+}
+
+fn fps_label(fps: Option<f64>, average_frame_time: Option<f64>) -> String {
+    match (fps, average_frame_time) {
+        (Some(fps), Some(average)) => format!("{:.0} FPS ({:.1} ms)", fps, average),
+        (Some(fps), None) => format!("{:.0} FPS", fps),
+        (None, Some(average)) => format!("? FPS ({:.1} ms)", average),
+        (None, None) => "? FPS".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_outline() {
+        assert_eq!([
+            Region::new(0, 0, 100, 4),
+            Region::new(0, 46, 100, 50),
+            Region::new(0, 0, 9, 50),
+            Region::new(91, 0, 100, 50)
+        ], outline(Region::new(0, 0, 100, 50), (10, 5)));
+
+        // The outline of a tiny region stays inside the region
+        assert_eq!([Region::new(0, 0, 3, 3); 4], outline(Region::new(0, 0, 3, 3), (10, 10)));
+    }
+
+    #[test]
+    fn test_fps_label() {
+        assert_eq!("60 FPS (4.3 ms)", fps_label(Some(59.7), Some(4.26)));
+        assert_eq!("30 FPS", fps_label(Some(30.0), None));
+        assert_eq!("? FPS", fps_label(None, None));
+    }
+}
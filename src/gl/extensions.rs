@@ -0,0 +1,58 @@
+use wasm_bindgen::JsCast;
+
+use web_sys::{
+    AngleInstancedArrays,
+    ExtTextureFilterAnisotropic,
+    OesTextureFloat,
+    OesVertexArrayObject,
+    WebGlRenderingContext as GL,
+    WebglDepthTexture
+};
+
+/// Gets the WebGL extension with the given name, or None if the context doesn't support it.
+pub(crate) fn get_extension<T: JsCast>(gl: &GL, name: &str) -> Option<T> {
+    gl.get_extension(name).ok().flatten().map(|extension| extension.unchecked_into::<T>())
+}
+
+/// The optional WebGL1 extensions that are useful for user interfaces, queried once so that feature detection doesn't
+/// need to be repeated (with error-prone extension name strings) everywhere. Every field is None if the context doesn't
+/// support the corresponding extension.
+///
+/// Note that WebGL2 contexts don't offer the extensions whose functionality became part of WebGL2 (like instanced
+/// arrays and vertex array objects), so the fields for those will be None for WebGL2 contexts even though their
+/// functionality is available. GlContext::gl2 should be checked first in that case.
+#[derive(Clone,Debug)]
+pub struct Extensions {
+
+    /// ANGLE_instanced_arrays
+    pub instanced_arrays: Option<AngleInstancedArrays>,
+    /// OES_vertex_array_object
+    pub vertex_array_object: Option<OesVertexArrayObject>,
+    /// EXT_texture_filter_anisotropic
+    pub anisotropic_filtering: Option<ExtTextureFilterAnisotropic>,
+    /// OES_texture_float
+    pub float_textures: Option<OesTextureFloat>,
+    /// WEBGL_depth_texture
+    pub depth_texture: Option<WebglDepthTexture>
+}
+
+impl Extensions {
+
+    /// Enables and gets all supported extensions of the context.
+    pub fn query(gl: &GL) -> Extensions {
+        Extensions {
+            instanced_arrays: get_extension(gl, "ANGLE_instanced_arrays"),
+            vertex_array_object: get_extension(gl, "OES_vertex_array_object"),
+            anisotropic_filtering: get_extension(gl, "EXT_texture_filter_anisotropic"),
+            float_textures: get_extension(gl, "OES_texture_float"),
+            depth_texture: get_extension(gl, "WEBGL_depth_texture")
+        }
+    }
+
+    /// Gets the highest anisotropy level that textures can use, or None if anisotropic filtering is not supported.
+    pub fn max_anisotropy(&self, gl: &GL) -> Option<f32> {
+        self.anisotropic_filtering.as_ref()?;
+        let max = gl.get_parameter(ExtTextureFilterAnisotropic::MAX_TEXTURE_MAX_ANISOTROPY_EXT).ok()?.as_f64()?;
+        Some(max as f32)
+    }
+}
@@ -0,0 +1,123 @@
+use web_sys::WebGlRenderingContext as GL;
+
+use crate::{
+    BufferUsage,
+    RenderTarget,
+    RendererError,
+    ShaderProgram,
+    VertexBuffer,
+    VertexLayout
+};
+
+const VERTEX_SHADER: &str = "
+attribute vec2 a_position;
+
+varying vec2 v_uv;
+
+void main() {
+    gl_Position = vec4(a_position, 0.0, 1.0);
+    v_uv = a_position * 0.5 + 0.5;
+}
+";
+
+// The well-known simplified FXAA of Timothy Lottes: it only blurs along edges whose luma contrast is large enough
+const FRAGMENT_SHADER: &str = "
+precision mediump float;
+
+uniform sampler2D u_texture;
+uniform vec2 u_texel_size;
+
+varying vec2 v_uv;
+
+const float REDUCE_MIN = 1.0 / 128.0;
+const float REDUCE_MUL = 1.0 / 8.0;
+const float SPAN_MAX = 8.0;
+
+void main() {
+    vec3 luma = vec3(0.299, 0.587, 0.114);
+    float luma_nw = dot(texture2D(u_texture, v_uv + vec2(-1.0, -1.0) * u_texel_size).rgb, luma);
+    float luma_ne = dot(texture2D(u_texture, v_uv + vec2(1.0, -1.0) * u_texel_size).rgb, luma);
+    float luma_sw = dot(texture2D(u_texture, v_uv + vec2(-1.0, 1.0) * u_texel_size).rgb, luma);
+    float luma_se = dot(texture2D(u_texture, v_uv + vec2(1.0, 1.0) * u_texel_size).rgb, luma);
+    vec4 center = texture2D(u_texture, v_uv);
+    float luma_m = dot(center.rgb, luma);
+
+    float luma_min = min(luma_m, min(min(luma_nw, luma_ne), min(luma_sw, luma_se)));
+    float luma_max = max(luma_m, max(max(luma_nw, luma_ne), max(luma_sw, luma_se)));
+
+    vec2 direction = vec2(-((luma_nw + luma_ne) - (luma_sw + luma_se)), (luma_nw + luma_sw) - (luma_ne + luma_se));
+    float direction_reduce = max((luma_nw + luma_ne + luma_sw + luma_se) * 0.25 * REDUCE_MUL, REDUCE_MIN);
+    float inverse_min = 1.0 / (min(abs(direction.x), abs(direction.y)) + direction_reduce);
+    direction = clamp(direction * inverse_min, vec2(-SPAN_MAX), vec2(SPAN_MAX)) * u_texel_size;
+
+    vec4 a = 0.5 * (
+        texture2D(u_texture, v_uv + direction * (1.0 / 3.0 - 0.5)) +
+        texture2D(u_texture, v_uv + direction * (2.0 / 3.0 - 0.5))
+    );
+    vec4 b = a * 0.5 + 0.25 * (
+        texture2D(u_texture, v_uv + direction * -0.5) +
+        texture2D(u_texture, v_uv + direction * 0.5)
+    );
+
+    float luma_b = dot(b.rgb, luma);
+    gl_FragColor = (luma_b < luma_min || luma_b > luma_max) ? a : b;
+}
+";
+
+/// Gets the number of MSAA samples of the drawing buffer of the context (or of the currently bound framebuffer). This
+/// is 0 when the browser refused to antialias, even if the antialias context option was set, in which case an
+/// FxaaPass can be used instead.
+pub fn msaa_samples(gl: &GL) -> i32 {
+    gl.get_parameter(GL::SAMPLES).ok().and_then(|samples| samples.as_f64()).unwrap_or(0.0) as i32
+}
+
+/// A full-screen FXAA (fast approximate anti-aliasing) pass, for contexts without MSAA. The scene should be rendered
+/// to a RenderTarget first, and then copied to the canvas (or another render target) with apply, which smooths the
+/// jagged edges. This is cheaper than MSAA, but also slightly blurs sharp details like small text.
+pub struct FxaaPass {
+    program: ShaderProgram,
+    layout: VertexLayout,
+    vertex_buffer: VertexBuffer
+}
+
+impl FxaaPass {
+
+    pub fn new(gl: &GL) -> Result<FxaaPass, RendererError> {
+        let program = ShaderProgram::compile(gl, VERTEX_SHADER, FRAGMENT_SHADER)?;
+        let position = program.attribute_location("a_position").expect("The FXAA shader uses a_position");
+        let layout = VertexLayout::new().attribute(position, 2);
+
+        let mut vertex_buffer = match VertexBuffer::new(gl, BufferUsage::Static) {
+            Ok(vertex_buffer) => vertex_buffer,
+            Err(error) => {
+                program.delete(gl);
+                return Err(error.into());
+            }
+        };
+        vertex_buffer.upload(gl, &[-1.0, -1.0, 1.0, -1.0, -1.0, 1.0, 1.0, 1.0]);
+
+        Ok(FxaaPass {
+            program,
+            layout,
+            vertex_buffer
+        })
+    }
+
+    /// Draws the anti-aliased content of the source onto the current render target, covering the current viewport.
+    /// Since this overwrites the pixels, blending should be disabled. This uses texture unit 0.
+    pub fn apply(&self, gl: &GL, source: &RenderTarget) {
+        self.program.use_program(gl);
+        source.as_texture().bind(gl, 0);
+        gl.uniform1i(self.program.uniform_location("u_texture"), 0);
+        gl.uniform2f(self.program.uniform_location("u_texel_size"), 1.0 / source.get_width() as f32, 1.0 / source.get_height() as f32);
+
+        self.layout.apply(gl, &self.vertex_buffer);
+        gl.draw_arrays(GL::TRIANGLE_STRIP, 0, 4);
+    }
+
+    /// Deletes the GL resources of this pass. This struct must not be used anymore after calling this.
+    pub fn delete(self, gl: &GL) {
+        self.program.delete(gl);
+        self.vertex_buffer.delete(gl);
+    }
+}
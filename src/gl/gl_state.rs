@@ -0,0 +1,191 @@
+use web_sys::{
+    WebGlBuffer,
+    WebGlProgram,
+    WebGlRenderingContext as GL,
+    WebGlTexture
+};
+
+/// A cached piece of GL state. The value is None while the state is unknown, in which case the next update always
+/// reaches GL.
+#[derive(Clone,Debug)]
+struct Cached<T: PartialEq> {
+    value: Option<T>
+}
+
+impl<T: PartialEq> Cached<T> {
+
+    fn unknown() -> Self {
+        Self {
+            value: None
+        }
+    }
+
+    /// Stores the new value, and returns true if it differs from the cached value (which means that GL needs to be
+    /// called).
+    fn update(&mut self, new_value: T) -> bool {
+        if self.value.as_ref() == Some(&new_value) {
+            false
+        } else {
+            self.value = Some(new_value);
+            true
+        }
+    }
+}
+
+/// Wraps a WebGL context and remembers the state that was set through it (bound program, textures and buffers, blend
+/// state and scissor state), so that redundant calls can be skipped. Each WebGL call has a considerable overhead in
+/// wasm, so this helps renderers that bind the same resources over and over.
+/// 
+/// The state is initially unknown. If other code changes the state directly (without this wrapper), invalidate must
+/// be called afterwards.
+pub struct GlState {
+    gl: GL,
+    program: Cached<Option<WebGlProgram>>,
+    active_texture_unit: Cached<u32>,
+    textures: Vec<Cached<Option<WebGlTexture>>>,
+    array_buffer: Cached<Option<WebGlBuffer>>,
+    element_array_buffer: Cached<Option<WebGlBuffer>>,
+    blend_enabled: Cached<bool>,
+    blend_func: Cached<(u32, u32)>,
+    scissor_enabled: Cached<bool>,
+    scissor_box: Cached<(i32, i32, i32, i32)>,
+    skipped_calls: u64
+}
+
+impl GlState {
+
+    pub fn new(gl: &GL) -> GlState {
+        GlState {
+            gl: gl.clone(),
+            program: Cached::unknown(),
+            active_texture_unit: Cached::unknown(),
+            textures: Vec::new(),
+            array_buffer: Cached::unknown(),
+            element_array_buffer: Cached::unknown(),
+            blend_enabled: Cached::unknown(),
+            blend_func: Cached::unknown(),
+            scissor_enabled: Cached::unknown(),
+            scissor_box: Cached::unknown(),
+            skipped_calls: 0
+        }
+    }
+
+    /// Gets the wrapped context, for all calls that are not cached.
+    pub fn gl(&self) -> &GL {
+        &self.gl
+    }
+
+    /// Forgets all cached state, so that the next call of each method will reach GL again. This must be called after
+    /// the state was changed without this wrapper, and after the context has been restored.
+    pub fn invalidate(&mut self) {
+        let gl = self.gl.clone();
+        let skipped_calls = self.skipped_calls;
+        *self = Self::new(&gl);
+        self.skipped_calls = skipped_calls;
+    }
+
+    /// Gets the number of GL calls that were skipped because they wouldn't change anything.
+    pub fn get_skipped_calls(&self) -> u64 {
+        self.skipped_calls
+    }
+
+    fn count(&mut self, needs_call: bool) -> bool {
+        if !needs_call {
+            self.skipped_calls += 1;
+        }
+        needs_call
+    }
+
+    pub fn use_program(&mut self, program: Option<&WebGlProgram>) {
+        let needs_call = self.program.update(program.cloned());
+        if self.count(needs_call) {
+            self.gl.use_program(program);
+        }
+    }
+
+    /// Binds the texture to TEXTURE_2D of the given texture unit (0 for TEXTURE0, 1 for TEXTURE1...).
+    pub fn bind_texture(&mut self, unit: u32, texture: Option<&WebGlTexture>) {
+        while self.textures.len() <= unit as usize {
+            self.textures.push(Cached::unknown());
+        }
+        let needs_call = self.textures[unit as usize].update(texture.cloned());
+        if self.count(needs_call) {
+            self.active_texture(unit);
+            self.gl.bind_texture(GL::TEXTURE_2D, texture);
+        }
+    }
+
+    fn active_texture(&mut self, unit: u32) {
+        let needs_call = self.active_texture_unit.update(unit);
+        if self.count(needs_call) {
+            self.gl.active_texture(GL::TEXTURE0 + unit);
+        }
+    }
+
+    pub fn bind_array_buffer(&mut self, buffer: Option<&WebGlBuffer>) {
+        let needs_call = self.array_buffer.update(buffer.cloned());
+        if self.count(needs_call) {
+            self.gl.bind_buffer(GL::ARRAY_BUFFER, buffer);
+        }
+    }
+
+    /// Binds the buffer to ELEMENT_ARRAY_BUFFER. Note that this binding is part of the vertex array object state, so
+    /// invalidate should be called after binding another vertex array.
+    pub fn bind_element_array_buffer(&mut self, buffer: Option<&WebGlBuffer>) {
+        let needs_call = self.element_array_buffer.update(buffer.cloned());
+        if self.count(needs_call) {
+            self.gl.bind_buffer(GL::ELEMENT_ARRAY_BUFFER, buffer);
+        }
+    }
+
+    pub fn set_blend_enabled(&mut self, enabled: bool) {
+        let needs_call = self.blend_enabled.update(enabled);
+        if self.count(needs_call) {
+            set_capability(&self.gl, GL::BLEND, enabled);
+        }
+    }
+
+    pub fn blend_func(&mut self, source_factor: u32, destination_factor: u32) {
+        let needs_call = self.blend_func.update((source_factor, destination_factor));
+        if self.count(needs_call) {
+            self.gl.blend_func(source_factor, destination_factor);
+        }
+    }
+
+    pub fn set_scissor_enabled(&mut self, enabled: bool) {
+        let needs_call = self.scissor_enabled.update(enabled);
+        if self.count(needs_call) {
+            set_capability(&self.gl, GL::SCISSOR_TEST, enabled);
+        }
+    }
+
+    pub fn scissor(&mut self, x: i32, y: i32, width: i32, height: i32) {
+        let needs_call = self.scissor_box.update((x, y, width, height));
+        if self.count(needs_call) {
+            self.gl.scissor(x, y, width, height);
+        }
+    }
+}
+
+fn set_capability(gl: &GL, capability: u32, enabled: bool) {
+    if enabled {
+        gl.enable(capability);
+    } else {
+        gl.disable(capability);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_cached() {
+        let mut cached = Cached::unknown();
+        assert!(cached.update(3));
+        assert!(!cached.update(3));
+        assert!(cached.update(4));
+        assert!(!cached.update(4));
+    }
+}
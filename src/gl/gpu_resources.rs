@@ -0,0 +1,311 @@
+use std::cell::{
+    Cell,
+    RefCell
+};
+use std::rc::{
+    Rc,
+    Weak
+};
+
+use web_sys::{
+    WebGlBuffer,
+    WebGlFramebuffer,
+    WebGlProgram,
+    WebGlRenderingContext as GL,
+    WebGlShader,
+    WebGlTexture
+};
+
+use crate::{
+    Arena,
+    Handle,
+    warn
+};
+
+/// A GL object that can be tracked by GpuResources.
+#[derive(Clone,Debug)]
+pub enum GpuResource {
+
+    Buffer(WebGlBuffer),
+    Texture(WebGlTexture),
+    Program(WebGlProgram),
+    Shader(WebGlShader),
+    Framebuffer(WebGlFramebuffer)
+}
+
+impl GpuResource {
+
+    /// Gets the name of the kind of this resource, for instance "buffer".
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            GpuResource::Buffer(_) => "buffer",
+            GpuResource::Texture(_) => "texture",
+            GpuResource::Program(_) => "program",
+            GpuResource::Shader(_) => "shader",
+            GpuResource::Framebuffer(_) => "framebuffer"
+        }
+    }
+
+    fn delete(&self, gl: &GL) {
+        match self {
+            GpuResource::Buffer(buffer) => gl.delete_buffer(Some(buffer)),
+            GpuResource::Texture(texture) => gl.delete_texture(Some(texture)),
+            GpuResource::Program(program) => gl.delete_program(Some(program)),
+            GpuResource::Shader(shader) => gl.delete_shader(Some(shader)),
+            GpuResource::Framebuffer(framebuffer) => gl.delete_framebuffer(Some(framebuffer))
+        }
+    }
+}
+
+impl From<WebGlBuffer> for GpuResource {
+
+    fn from(buffer: WebGlBuffer) -> Self {
+        GpuResource::Buffer(buffer)
+    }
+}
+
+impl From<WebGlTexture> for GpuResource {
+
+    fn from(texture: WebGlTexture) -> Self {
+        GpuResource::Texture(texture)
+    }
+}
+
+impl From<WebGlProgram> for GpuResource {
+
+    fn from(program: WebGlProgram) -> Self {
+        GpuResource::Program(program)
+    }
+}
+
+impl From<WebGlShader> for GpuResource {
+
+    fn from(shader: WebGlShader) -> Self {
+        GpuResource::Shader(shader)
+    }
+}
+
+impl From<WebGlFramebuffer> for GpuResource {
+
+    fn from(framebuffer: WebGlFramebuffer) -> Self {
+        GpuResource::Framebuffer(framebuffer)
+    }
+}
+
+#[derive(Clone)]
+struct TrackedEntry {
+    resource: Rc<GpuResource>,
+    label: String,
+    // Shared with the Tracked handle, and set when the registry deleted (or forgot) the resource
+    removed: Rc<Cell<bool>>
+}
+
+type Entries = Rc<RefCell<Arena<TrackedEntry>>>;
+
+/// Keeps track of all GL objects that were created through it, so that they can be destroyed at once (for instance
+/// when a whole screen is closed) and so that leaks can be found: GL objects are not freed when the JavaScript object
+/// referring to them is garbage collected, so every forgotten delete call wastes GPU memory until the tab is closed.
+///
+/// In debug builds, a warning is printed when a Tracked resource is dropped without being deleted. Such resources
+/// remain in the registry, so destroy_all will still free them. Tracked resources can also be deleted after the
+/// registry itself was dropped.
+#[derive(Clone,Default)]
+pub struct GpuResources {
+    entries: Entries
+}
+
+impl GpuResources {
+
+    pub fn new() -> GpuResources {
+        GpuResources {
+            entries: Rc::new(RefCell::new(Arena::new()))
+        }
+    }
+
+    /// Starts tracking the given resource. The label is used in warnings about leaked resources.
+    pub fn track<T: Clone + Into<GpuResource>>(&self, resource: T, label: &str) -> Tracked<T> {
+        self.track_as(resource.clone(), resource.into(), label)
+    }
+
+    fn track_as<T>(&self, resource: T, entry_resource: GpuResource, label: &str) -> Tracked<T> {
+        let entry = TrackedEntry {
+            resource: Rc::new(entry_resource),
+            label: label.to_string(),
+            removed: Rc::new(Cell::new(false))
+        };
+        let handle = self.entries.borrow_mut().insert(entry.clone());
+        Tracked {
+            resource,
+            entry,
+            handle,
+            entries: Rc::downgrade(&self.entries),
+            deleted: false
+        }
+    }
+
+    /// Gets the number of tracked resources that have not been deleted yet (including leaked ones).
+    pub fn live_count(&self) -> usize {
+        self.entries.borrow().len()
+    }
+
+    /// Describes all tracked resources that have not been deleted yet, for instance "texture 'font atlas'".
+    pub fn describe_live(&self) -> Vec<String> {
+        self.entries.borrow().iter().map(|(_, entry)| describe(entry)).collect()
+    }
+
+    /// Deletes all tracked resources that have not been deleted yet, and returns how many were deleted. The Tracked
+    /// handles to them become invalid, but can still be dropped (or deleted) without problems.
+    pub fn destroy_all(&self, gl: &GL) -> usize {
+        let removed = self.remove_all();
+        for entry in &removed {
+            entry.resource.delete(gl);
+        }
+        removed.len()
+    }
+
+    /// Stops tracking all resources without deleting them. This should be used after the context was lost, since all
+    /// resources are gone already in that case.
+    pub fn forget_all(&self) {
+        self.remove_all();
+    }
+
+    fn remove_all(&self) -> Vec<TrackedEntry> {
+        let mut entries = self.entries.borrow_mut();
+        let handles: Vec<Handle> = entries.iter().map(|(handle, _)| handle).collect();
+        let removed: Vec<TrackedEntry> = handles.into_iter().filter_map(|handle| entries.remove(handle)).collect();
+        for entry in &removed {
+            entry.removed.set(true);
+        }
+        removed
+    }
+}
+
+fn describe(entry: &TrackedEntry) -> String {
+    format!("{} '{}'", entry.resource.kind_name(), entry.label)
+}
+
+/// A GL object that is tracked by GpuResources. It must be deleted with delete before it is dropped, unless the
+/// registry was destroyed (or forgotten) as a whole.
+pub struct Tracked<T> {
+    resource: T,
+    entry: TrackedEntry,
+    handle: Handle,
+    entries: Weak<RefCell<Arena<TrackedEntry>>>,
+    deleted: bool
+}
+
+impl<T> Tracked<T> {
+
+    pub fn get(&self) -> &T {
+        &self.resource
+    }
+
+    /// Deletes the resource and stops tracking it. This does nothing if the registry destroyed (or forgot) it already,
+    /// but it still deletes the resource when the registry itself was dropped.
+    pub fn delete(mut self, gl: &GL) {
+        if let Some(resource) = self.take_deletion() {
+            resource.delete(gl);
+        }
+    }
+
+    /// Marks this resource as deleted, removes it from the registry (if that still exists), and returns the GL object
+    /// that needs to be deleted, or None if the registry took care of it already
+    fn take_deletion(&mut self) -> Option<Rc<GpuResource>> {
+        if self.deleted || self.entry.removed.get() {
+            return None;
+        }
+        self.deleted = true;
+        if let Some(entries) = self.entries.upgrade() {
+            entries.borrow_mut().remove(self.handle);
+        }
+        Some(Rc::clone(&self.entry.resource))
+    }
+
+    /// Describes the resource if dropping this would leak it: when neither this nor the registry deleted it
+    fn describe_leak(&self) -> Option<String> {
+        if self.deleted || self.entry.removed.get() {
+            return None;
+        }
+        Some(describe(&self.entry))
+    }
+}
+
+impl<T> Drop for Tracked<T> {
+
+    fn drop(&mut self) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+        if let Some(description) = self.describe_leak() {
+            warn(&format!("The GPU resource {} was dropped without being deleted", description));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use wasm_bindgen::{
+        JsCast,
+        JsValue
+    };
+
+    // The GL objects can't be created outside the browser, but null stand-ins are enough for the bookkeeping
+    fn fake_texture() -> GpuResource {
+        GpuResource::Texture(JsValue::NULL.unchecked_into())
+    }
+
+    fn fake_buffer() -> GpuResource {
+        GpuResource::Buffer(JsValue::NULL.unchecked_into())
+    }
+
+    #[test]
+    fn test_tracking() {
+        let resources = GpuResources::new();
+        let atlas = resources.track_as((), fake_texture(), "font atlas");
+        let mut quads = resources.track_as((), fake_buffer(), "quads");
+        assert_eq!(2, resources.live_count());
+        assert_eq!(vec!["texture 'font atlas'", "buffer 'quads'"], resources.describe_live());
+
+        assert_eq!(Some("texture 'font atlas'".to_string()), atlas.describe_leak());
+        quads.deleted = true;
+        assert_eq!(None, quads.describe_leak());
+
+        resources.forget_all();
+        assert_eq!(0, resources.live_count());
+        assert!(resources.describe_live().is_empty());
+
+        // Resources that were forgotten by the registry are not leaked by dropping them
+        assert_eq!(None, atlas.describe_leak());
+    }
+
+    #[test]
+    fn test_take_deletion() {
+        let resources = GpuResources::new();
+        let mut texture = resources.track_as((), fake_texture(), "background");
+        assert_eq!(Some("texture"), texture.take_deletion().map(|resource| resource.kind_name()));
+        assert_eq!(0, resources.live_count());
+        assert!(texture.take_deletion().is_none());
+        assert_eq!(None, texture.describe_leak());
+    }
+
+    #[test]
+    fn test_dropped_registry() {
+        let resources = GpuResources::new();
+        let mut forgotten = resources.track_as((), fake_buffer(), "forgotten");
+        resources.forget_all();
+        let mut texture = resources.track_as((), fake_texture(), "background");
+        drop(resources);
+
+        // The registry is gone, so the texture must delete its own GL object
+        assert_eq!(Some("texture 'background'".to_string()), texture.describe_leak());
+        assert_eq!(Some("texture"), texture.take_deletion().map(|resource| resource.kind_name()));
+        assert_eq!(None, texture.describe_leak());
+
+        // The buffer was removed by forget_all before the registry was dropped, so it must not be deleted again
+        assert_eq!(None, forgotten.describe_leak());
+        assert!(forgotten.take_deletion().is_none());
+    }
+}
@@ -0,0 +1,211 @@
+use web_sys::{
+    WebGlRenderingContext as GL,
+    WebGlTexture
+};
+
+use crate::{
+    BufferUsage,
+    Gradient,
+    Region,
+    RendererError,
+    ShaderProgram,
+    TextureError,
+    UniformLocations,
+    VertexBuffer,
+    VertexLayout,
+    uniform_block
+};
+
+const VERTEX_SHADER: &str = "
+attribute vec2 a_local;
+
+uniform vec4 u_region;
+
+varying vec2 v_local;
+
+void main() {
+    gl_Position = vec4(mix(u_region.xy, u_region.zw, a_local), 0.0, 1.0);
+    v_local = a_local;
+}
+";
+
+const FRAGMENT_SHADER: &str = "
+precision mediump float;
+
+uniform sampler2D u_lookup;
+uniform vec2 u_start;
+uniform vec2 u_end;
+uniform bool u_radial;
+
+varying vec2 v_local;
+
+void main() {
+    float position;
+    if (u_radial) {
+        position = length(v_local - u_start) / u_end.x;
+    } else {
+        vec2 direction = u_end - u_start;
+        position = dot(v_local - u_start, direction) / dot(direction, direction);
+    }
+    gl_FragColor = texture2D(u_lookup, vec2(clamp(position, 0.0, 1.0), 0.5));
+}
+";
+
+/// The number of texels of the lookup texture to which the gradient is rasterized
+const LOOKUP_SIZE: u32 = 256;
+
+/// The shape of a gradient fill. All points are in the local coordinates of the filled region, where (0, 0) is its
+/// bottom-left corner and (1, 1) its top-right corner.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum GradientShape {
+
+    /// Position 0.0 of the gradient is at the start point and position 1.0 at the end point, and the color is constant
+    /// perpendicular to the line between them.
+    Linear {
+        start: (f32, f32),
+        end: (f32, f32)
+    },
+    /// Position 0.0 of the gradient is at the center and position 1.0 at the given distance from it. Since the
+    /// distance is in local coordinates, the gradient is elliptical if the region is not square.
+    Radial {
+        center: (f32, f32),
+        radius: f32
+    }
+}
+
+impl GradientShape {
+
+    /// From the top of the region to its bottom, like the default of a CSS linear-gradient
+    pub const TOP_TO_BOTTOM: GradientShape = GradientShape::Linear { start: (0.5, 1.0), end: (0.5, 0.0) };
+    pub const LEFT_TO_RIGHT: GradientShape = GradientShape::Linear { start: (0.0, 0.5), end: (1.0, 0.5) };
+    /// From the center of the region to its edges
+    pub const CENTER_TO_EDGES: GradientShape = GradientShape::Radial { center: (0.5, 0.5), radius: 0.5 };
+}
+
+struct GradientUniforms {
+    region: Region,
+    lookup: i32,
+    start: [f32; 2],
+    end: [f32; 2],
+    radial: bool
+}
+
+uniform_block!(GradientUniforms {
+    region => "u_region",
+    lookup => "u_lookup",
+    start => "u_start",
+    end => "u_end",
+    radial => "u_radial"
+});
+
+impl GradientUniforms {
+
+    fn new(region: Region, shape: GradientShape) -> GradientUniforms {
+        let (start, end, radial) = match shape {
+            GradientShape::Linear { start, end } => ([start.0, start.1], [end.0, end.1], false),
+            // The radius is passed as the x-coordinate of the end
+            GradientShape::Radial { center, radius } => ([center.0, center.1], [radius, 0.0], true)
+        };
+        GradientUniforms {
+            region,
+            lookup: 0,
+            start,
+            end,
+            radial
+        }
+    }
+}
+
+/// Fills regions with linear or radial gradients. The gradient is rasterized to a small lookup texture for each fill,
+/// so any number of color stops is supported. The blend state is not changed, so blending should be enabled to draw
+/// gradients with transparent colors.
+pub struct GradientRenderer {
+    program: ShaderProgram,
+    uniforms: UniformLocations<GradientUniforms>,
+    layout: VertexLayout,
+    vertex_buffer: VertexBuffer,
+    lookup_texture: WebGlTexture
+}
+
+impl GradientRenderer {
+
+    pub fn new(gl: &GL) -> Result<GradientRenderer, RendererError> {
+        let program = ShaderProgram::compile(gl, VERTEX_SHADER, FRAGMENT_SHADER)?;
+        let location = program.attribute_location("a_local").expect("The gradient shader uses its attribute");
+        let layout = VertexLayout::new().attribute(location, 2);
+
+        // When a step fails, the resources of the previous steps are deleted
+        let mut vertex_buffer = match VertexBuffer::new(gl, BufferUsage::Static) {
+            Ok(vertex_buffer) => vertex_buffer,
+            Err(error) => {
+                program.delete(gl);
+                return Err(error.into());
+            }
+        };
+        vertex_buffer.upload(gl, &[0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0]);
+
+        let lookup_texture = match gl.create_texture() {
+            Some(lookup_texture) => lookup_texture,
+            None => {
+                program.delete(gl);
+                vertex_buffer.delete(gl);
+                return Err(TextureError::CreationFailed.into());
+            }
+        };
+        gl.bind_texture(GL::TEXTURE_2D, Some(&lookup_texture));
+        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MIN_FILTER, GL::LINEAR as i32);
+        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MAG_FILTER, GL::LINEAR as i32);
+        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_S, GL::CLAMP_TO_EDGE as i32);
+        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_T, GL::CLAMP_TO_EDGE as i32);
+
+        Ok(GradientRenderer {
+            uniforms: UniformLocations::new(&program),
+            program,
+            layout,
+            vertex_buffer,
+            lookup_texture
+        })
+    }
+
+    /// Fills the region with the gradient, from the top of the region to its bottom.
+    pub fn fill_region_gradient(&self, gl: &GL, region: Region, gradient: &Gradient) -> Result<(), TextureError> {
+        self.fill_region_gradient_shape(gl, region, gradient, GradientShape::TOP_TO_BOTTOM)
+    }
+
+    /// Fills the region with the gradient in the given shape.
+    pub fn fill_region_gradient_shape(&self, gl: &GL, region: Region, gradient: &Gradient, shape: GradientShape) -> Result<(), TextureError> {
+        gl.active_texture(GL::TEXTURE0);
+        gl.bind_texture(GL::TEXTURE_2D, Some(&self.lookup_texture));
+        gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+            GL::TEXTURE_2D, 0, GL::RGBA as i32, LOOKUP_SIZE as i32, 1, 0, GL::RGBA, GL::UNSIGNED_BYTE, Some(&gradient.rasterize(LOOKUP_SIZE))
+        ).map_err(TextureError::UploadFailed)?;
+
+        self.program.use_program(gl);
+        self.uniforms.upload(gl, &GradientUniforms::new(region, shape));
+        self.layout.apply(gl, &self.vertex_buffer);
+        gl.draw_arrays(GL::TRIANGLE_FAN, 0, 4);
+        Ok(())
+    }
+
+    /// Deletes the GL resources of this renderer. This struct must not be used anymore after calling this.
+    pub fn delete(self, gl: &GL) {
+        self.program.delete(gl);
+        self.vertex_buffer.delete(gl);
+        gl.delete_texture(Some(&self.lookup_texture));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_gradient_uniforms() {
+        let linear = GradientUniforms::new(Region::entire_viewport(), GradientShape::TOP_TO_BOTTOM);
+        assert_eq!(([0.5, 1.0], [0.5, 0.0], false), (linear.start, linear.end, linear.radial));
+
+        let radial = GradientUniforms::new(Region::entire_viewport(), GradientShape::Radial { center: (0.25, 0.75), radius: 2.0 });
+        assert_eq!(([0.25, 0.75], [2.0, 0.0], true), (radial.start, radial.end, radial.radial));
+    }
+}
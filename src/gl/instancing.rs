@@ -0,0 +1,62 @@
+use web_sys::{
+    AngleInstancedArrays,
+    WebGl2RenderingContext
+};
+
+use crate::{
+    GlContext,
+    get_extension
+};
+
+#[derive(Clone,Debug)]
+enum InstancingKind {
+    Native(WebGl2RenderingContext),
+    Angle(AngleInstancedArrays)
+}
+
+/// Draws many instances of the same geometry with a single draw call, using the native WebGL2 methods or the
+/// ANGLE_instanced_arrays extension of WebGL1. Instancing is available on practically all devices, but applications
+/// that want to be safe can fall back to one draw call per instance when Instancing::new returns None.
+#[derive(Clone,Debug)]
+pub struct Instancing {
+    kind: InstancingKind
+}
+
+impl Instancing {
+
+    /// Gets instancing support for the given context, or None if the context doesn't support instancing.
+    pub fn new(context: &GlContext) -> Option<Instancing> {
+        let kind = match context.gl2() {
+            Some(gl2) => InstancingKind::Native(gl2.clone()),
+            None => InstancingKind::Angle(get_extension(context.gl(), "ANGLE_instanced_arrays")?)
+        };
+        Some(Instancing {
+            kind
+        })
+    }
+
+    /// Makes the attribute at the given index advance once per divisor instances instead of once per vertex. A divisor
+    /// of 0 restores the normal per-vertex behavior.
+    pub fn vertex_attrib_divisor(&self, index: u32, divisor: u32) {
+        match &self.kind {
+            InstancingKind::Native(gl2) => gl2.vertex_attrib_divisor(index, divisor),
+            InstancingKind::Angle(extension) => extension.vertex_attrib_divisor_angle(index, divisor)
+        }
+    }
+
+    /// Like drawArrays, but draws the vertices instance_count times.
+    pub fn draw_arrays_instanced(&self, mode: u32, first: i32, count: i32, instance_count: i32) {
+        match &self.kind {
+            InstancingKind::Native(gl2) => gl2.draw_arrays_instanced(mode, first, count, instance_count),
+            InstancingKind::Angle(extension) => extension.draw_arrays_instanced_angle(mode, first, count, instance_count)
+        }
+    }
+
+    /// Like drawElements, but draws the elements instance_count times. The offset is in bytes.
+    pub fn draw_elements_instanced(&self, mode: u32, count: i32, index_type: u32, offset: i32, instance_count: i32) {
+        match &self.kind {
+            InstancingKind::Native(gl2) => gl2.draw_elements_instanced_with_i32(mode, count, index_type, offset, instance_count),
+            InstancingKind::Angle(extension) => extension.draw_elements_instanced_angle_with_i32(mode, count, index_type, offset, instance_count)
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use web_sys::WebGlRenderingContext as GL;
+
+/// A z-index that can be converted to a depth value, so that the depth buffer can decide which widget is in front
+/// instead of the drawing order. Layers with a higher z are in front of layers with a lower z.
+///
+/// The z is 16 bits, because WebGL only guarantees a 16-bit depth buffer, so every layer still gets its own depth
+/// value. Renderers should put depth() in the z-coordinate of gl_Position (QuadBatch does this for the z of its quads).
+#[derive(Clone,Copy,Debug,PartialEq,Eq,PartialOrd,Ord,Hash)]
+pub struct Layer {
+    z: i16
+}
+
+impl Layer {
+
+    /// The layer behind all other layers
+    pub const BACK: Layer = Layer::new(i16::MIN);
+    /// The layer in front of all other layers
+    pub const FRONT: Layer = Layer::new(i16::MAX);
+
+    pub const fn new(z: i16) -> Layer {
+        Layer {
+            z
+        }
+    }
+
+    /// Converts a z-index of a wider type to a layer, clamping it to the range of i16.
+    pub fn from_z_index(z: i32) -> Layer {
+        Layer::new(z.clamp(i16::MIN as i32, i16::MAX as i32) as i16)
+    }
+
+    pub fn get_z(&self) -> i16 {
+        self.z
+    }
+
+    /// Gets the clip-space depth (the z-coordinate of gl_Position) of this layer, between -1.0 and 1.0. Layers that are
+    /// more in front get a smaller depth, so the default depth function (LESS) works as expected.
+    pub fn depth(self) -> f32 {
+        // The + 0.5 puts each layer in the middle of its own depth range, which avoids rounding problems
+        (-(self.z as f64 + 0.5) / 32768.0) as f32
+    }
+}
+
+/// The two passes of drawing with the depth buffer. Opaque widgets should be drawn first (preferably front-to-back, so
+/// the GPU can skip the hidden pixels before running the fragment shader), and transparent widgets afterwards (in
+/// back-to-front order, since they need to be blended with what is behind them).
+///
+/// This requires a depth buffer (see ContextOptions::depth), which should be cleared to 1.0 at the start of each frame.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum DepthPass {
+
+    /// Tests against and writes to the depth buffer
+    Opaque,
+    /// Tests against the depth buffer without writing to it, so that transparent widgets don't hide each other
+    Transparent
+}
+
+impl DepthPass {
+
+    /// Enables the depth test and configures it for this pass.
+    pub fn apply(self, gl: &GL) {
+        gl.enable(GL::DEPTH_TEST);
+        gl.depth_func(GL::LESS);
+        gl.depth_mask(self == DepthPass::Opaque);
+    }
+
+    /// Disables the depth test and restores the depth mask, to go back to the painter's algorithm.
+    pub fn disable(gl: &GL) {
+        gl.disable(GL::DEPTH_TEST);
+        gl.depth_mask(true);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_depth_order() {
+        assert!(Layer::new(1).depth() < Layer::new(0).depth());
+        assert!(Layer::FRONT.depth() > -1.0);
+        assert!(Layer::BACK.depth() < 1.0);
+
+        // Every layer must get its own value in a 16-bit depth buffer
+        let to_depth_buffer = |layer: Layer| ((layer.depth() as f64 + 1.0) / 2.0 * 65535.0).round() as u32;
+        assert_ne!(to_depth_buffer(Layer::new(100)), to_depth_buffer(Layer::new(101)));
+        assert_ne!(to_depth_buffer(Layer::FRONT), to_depth_buffer(Layer::new(i16::MAX - 1)));
+    }
+
+    #[test]
+    fn test_from_z_index() {
+        assert_eq!(Layer::new(5), Layer::from_z_index(5));
+        assert_eq!(Layer::FRONT, Layer::from_z_index(1_000_000));
+        assert_eq!(Layer::BACK, Layer::from_z_index(-1_000_000));
+    }
+}
@@ -0,0 +1,80 @@
+mod blend_mode;
+mod buffer;
+mod canvas;
+mod clip_stack;
+mod compositor;
+mod context;
+mod context_attributes;
+mod context_loss;
+mod context_options;
+#[cfg(feature = "debug-gl")]
+mod debug_gl;
+mod debug_overlay;
+mod extensions;
+mod fxaa;
+mod gl_state;
+mod gpu_resources;
+mod gradient_renderer;
+mod instancing;
+mod layer;
+mod nine_slice;
+mod picking;
+mod quad_batch;
+mod region_gl;
+mod render_graph;
+mod render_target;
+mod resize_watcher;
+mod resolution;
+mod renderer;
+mod renderer_error;
+mod screenshot;
+mod sdf_text;
+mod shader;
+mod shape_renderer;
+mod text_renderer;
+mod texture;
+mod texture_settings;
+mod uniforms;
+mod vertex_array;
+#[cfg(feature = "webgpu")]
+mod webgpu;
+pub use blend_mode::*;
+pub use buffer::*;
+pub use canvas::*;
+pub use clip_stack::*;
+pub use compositor::*;
+pub use context::*;
+pub use context_attributes::*;
+pub use context_loss::*;
+pub use context_options::*;
+#[cfg(feature = "debug-gl")]
+pub use debug_gl::*;
+pub use debug_overlay::*;
+pub use extensions::*;
+pub use fxaa::*;
+pub use gl_state::*;
+pub use gpu_resources::*;
+pub use gradient_renderer::*;
+pub use instancing::*;
+pub use layer::*;
+pub use nine_slice::*;
+pub use picking::*;
+pub use quad_batch::*;
+pub use region_gl::*;
+pub use render_graph::*;
+pub use render_target::*;
+pub use resize_watcher::*;
+pub use resolution::*;
+pub use renderer::*;
+pub use renderer_error::*;
+pub use screenshot::*;
+pub use sdf_text::*;
+pub use shader::*;
+pub use shape_renderer::*;
+pub use text_renderer::*;
+pub use texture::*;
+pub use texture_settings::*;
+pub use uniforms::*;
+pub use vertex_array::*;
+#[cfg(feature = "webgpu")]
+pub use webgpu::*;
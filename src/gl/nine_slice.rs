@@ -0,0 +1,121 @@
+use crate::{
+    Color,
+    QuadBatch,
+    Region,
+    Texture,
+    pixels_to_region
+};
+
+/// The widths of the borders of a NineSlice texture, in pixels.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub struct NineSliceInsets {
+    pub left: u32,
+    pub top: u32,
+    pub right: u32,
+    pub bottom: u32
+}
+
+/// A texture (for instance a decorated panel or button) that is divided into 9 slices by its border insets. When it is
+/// drawn onto a region, the corners keep their size, the edges are only stretched along their length, and only the
+/// center is stretched in both directions. This allows a single texture to be used for panels of any size.
+pub struct NineSlice {
+    texture: Texture,
+    insets: NineSliceInsets
+}
+
+impl NineSlice {
+
+    pub fn new(texture: Texture, insets: NineSliceInsets) -> NineSlice {
+        NineSlice {
+            texture,
+            insets
+        }
+    }
+
+    pub fn get_texture(&self) -> &Texture {
+        &self.texture
+    }
+
+    pub fn get_insets(&self) -> NineSliceInsets {
+        self.insets
+    }
+
+    /// Adds the slices needed to stretch this texture over the region to the batch. The borders are drawn at their
+    /// original size in pixels, which is why the size of the canvas (in pixels) is needed. If the region is too small
+    /// for the borders, the borders are shrunk proportionally.
+    pub fn push_to(&self, batch: &mut QuadBatch, region: Region, canvas_size: (u32, u32), tint: Color, z: i32) {
+        let texture_size = (self.texture.get_width(), self.texture.get_height());
+        for (slice_region, uv) in compute_slices(self.insets, texture_size, region, canvas_size) {
+            batch.push_texture(slice_region, &self.texture, uv, tint, z);
+        }
+    }
+}
+
+/// Splits the range [min, max] (inclusive) into up to 3 parts, where the first has length start and the last has
+/// length end (in region units). Empty parts are left out. The third element of each part is its index (0, 1 or 2).
+fn split_range(min: i32, max: i32, start: i32, end: i32) -> Vec<(i32, i32, usize)> {
+    let length = max - min + 1;
+    let (start, end) = if start + end > length {
+        let start = (start as i64 * length as i64 / (start + end) as i64) as i32;
+        (start, length - start)
+    } else {
+        (start, end)
+    };
+
+    let bounds = [(min, min + start - 1), (min + start, max - end), (max - end + 1, max)];
+    bounds.iter().enumerate().filter(|(_, (low, high))| low <= high).map(|(index, (low, high))| (*low, *high, index)).collect()
+}
+
+fn compute_slices(insets: NineSliceInsets, texture_size: (u32, u32), region: Region, canvas_size: (u32, u32)) -> Vec<(Region, [f32; 4])> {
+    let (texture_width, texture_height) = (texture_size.0 as f32, texture_size.1 as f32);
+    let us = [0.0, insets.left as f32 / texture_width, 1.0 - insets.right as f32 / texture_width, 1.0];
+    // The top row of the texture is the row with the highest y in the region
+    let vs = [1.0, 1.0 - insets.bottom as f32 / texture_height, insets.top as f32 / texture_height, 0.0];
+
+    let columns = split_range(region.get_min_x(), region.get_max_x(),
+        pixels_to_region(insets.left, canvas_size.0), pixels_to_region(insets.right, canvas_size.0));
+    let rows = split_range(region.get_min_y(), region.get_max_y(),
+        pixels_to_region(insets.bottom, canvas_size.1), pixels_to_region(insets.top, canvas_size.1));
+
+    let mut slices = Vec::with_capacity(9);
+    for (min_y, max_y, row) in &rows {
+        for (min_x, max_x, column) in &columns {
+            slices.push((Region::new(*min_x, *min_y, *max_x, *max_y), [us[*column], vs[*row + 1], us[*column + 1], vs[*row]]));
+        }
+    }
+    slices
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_split_range() {
+        assert_eq!(vec![(0, 9, 0), (10, 89, 1), (90, 99, 2)], split_range(0, 99, 10, 10));
+        assert_eq!(vec![(0, 49, 0), (50, 99, 2)], split_range(0, 99, 50, 50));
+        // The borders don't fit, so they are shrunk proportionally
+        assert_eq!(vec![(0, 24, 0), (25, 99, 2)], split_range(0, 99, 50, 150));
+        assert_eq!(vec![(0, 99, 1)], split_range(0, 99, 0, 0));
+    }
+
+    #[test]
+    fn test_compute_slices() {
+        let insets = NineSliceInsets { left: 4, top: 4, right: 4, bottom: 4 };
+        let region = Region::new(-10_000, -10_000, -5_000, -5_000);
+        let slices = compute_slices(insets, (16, 16), region, (20_001, 20_001));
+        assert_eq!(9, slices.len());
+
+        // The bottom-left corner
+        assert_eq!((Region::new(-10_000, -10_000, -9_997, -9_997), [0.0, 0.75, 0.25, 1.0]), slices[0]);
+        // The center
+        assert_eq!((Region::new(-9_996, -9_996, -5_004, -5_004), [0.25, 0.25, 0.75, 0.75]), slices[4]);
+        // The top-right corner
+        assert_eq!((Region::new(-5_003, -5_003, -5_000, -5_000), [0.75, 0.0, 1.0, 0.25]), slices[8]);
+
+        // A canvas without pixels has borders without size, so only the center remains
+        let slices = compute_slices(insets, (16, 16), region, (0, 0));
+        assert_eq!(vec![(region, [0.25, 0.25, 0.75, 0.75])], slices);
+    }
+}
@@ -0,0 +1,128 @@
+use wasm_bindgen::JsCast;
+
+use web_sys::{
+    WebGlFramebuffer,
+    WebGlRenderingContext as GL
+};
+
+use crate::{
+    Color,
+    RenderTarget,
+    RenderTargetError
+};
+
+/// The largest id that can be encoded in the red, green and blue channels of a Color
+pub const MAX_PICKING_ID: u32 = 0xFF_FF_FF;
+
+/// Gets the color that a component with the given id should be drawn with onto a PickingBuffer. The id must be
+/// between 1 and MAX_PICKING_ID (0 is reserved for 'nothing').
+pub fn picking_color(id: u32) -> Color {
+    assert!(id != 0 && id <= MAX_PICKING_ID, "Picking ids must be between 1 and {}, but got {}", MAX_PICKING_ID, id);
+    Color::from_rgb((id >> 16) as u8, (id >> 8) as u8, id as u8)
+}
+
+/// The inverse of picking_color. Pixels that are less than half opaque (like the anti-aliased edges of shapes) are
+/// considered to be empty.
+fn picking_id(pixel: [u8; 4]) -> Option<u32> {
+    if pixel[3] < 128 {
+        return None;
+    }
+    let id = ((pixel[0] as u32) << 16) | ((pixel[1] as u32) << 8) | pixel[2] as u32;
+    if id == 0 {
+        None
+    } else {
+        Some(id)
+    }
+}
+
+/// An offscreen render target for precise hit-testing. Every component draws its exact shape onto it using its own
+/// unique color (see picking_color), after which what_is_at tells which component is visible at a given pixel. This
+/// is accurate for rotated, rounded or masked components, for which Region::is_inside is only an approximation.
+///
+/// Blending must be disabled while drawing onto the buffer, because blended colors are not valid ids.
+pub struct PickingBuffer {
+    target: RenderTarget
+}
+
+impl PickingBuffer {
+
+    /// Creates a picking buffer of the given size (in pixels), which is normally the size of the canvas.
+    pub fn new(gl: &GL, width: u32, height: u32) -> Result<PickingBuffer, RenderTargetError> {
+        Ok(PickingBuffer {
+            target: RenderTarget::new(gl, width, height)?
+        })
+    }
+
+    /// Changes the size of this buffer, which discards its content.
+    pub fn resize(&mut self, gl: &GL, width: u32, height: u32) -> Result<(), RenderTargetError> {
+        self.target.resize(gl, width, height)
+    }
+
+    /// Makes this buffer the current render target and clears it, so that the components can draw onto it. Call end
+    /// when all components have been drawn.
+    pub fn begin(&self, gl: &GL) {
+        self.target.bind(gl);
+        gl.disable(GL::BLEND);
+        gl.clear_color(0.0, 0.0, 0.0, 0.0);
+        gl.clear(GL::COLOR_BUFFER_BIT);
+    }
+
+    /// Makes the canvas the current render target again. Note that this doesn't restore the viewport.
+    pub fn end(&self, gl: &GL) {
+        RenderTarget::unbind(gl);
+    }
+
+    /// Gets the id of the component that was drawn at the given pixel (with the origin at the top-left corner, like
+    /// DOM coordinates) during the last begin/end cycle, or None if no component was drawn there. The framebuffer that
+    /// was bound before this call is bound again afterwards, and the viewport is not changed.
+    pub fn what_is_at(&self, gl: &GL, pixel: (i32, i32)) -> Option<u32> {
+        let (x, y) = pixel;
+        let height = self.target.get_height() as i32;
+        if x < 0 || y < 0 || x >= self.target.get_width() as i32 || y >= height {
+            return None;
+        }
+
+        // readPixels doesn't depend on the viewport, so only the framebuffer binding needs to be changed
+        let previous = gl.get_parameter(GL::FRAMEBUFFER_BINDING).ok().and_then(|binding| binding.dyn_into::<WebGlFramebuffer>().ok());
+        let mut result = [0; 4];
+        gl.bind_framebuffer(GL::FRAMEBUFFER, Some(self.target.get_framebuffer()));
+        gl.read_pixels_with_opt_u8_array(x, height - 1 - y, 1, 1, GL::RGBA, GL::UNSIGNED_BYTE, Some(&mut result))
+            .expect("readPixels should not fail");
+        gl.bind_framebuffer(GL::FRAMEBUFFER, previous.as_ref());
+        picking_id(result)
+    }
+
+    /// Deletes the render target of this buffer. This struct must not be used anymore after calling this.
+    pub fn delete(self, gl: &GL) {
+        self.target.delete(gl);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_picking_color_round_trip() {
+        for id in &[1, 255, 256, 0x12_34_56, MAX_PICKING_ID] {
+            let color = picking_color(*id);
+            let pixel = [color.get_red(), color.get_green(), color.get_blue(), color.get_alpha()];
+            assert_eq!(Some(*id), picking_id(pixel));
+        }
+    }
+
+    #[test]
+    fn test_empty_pixels() {
+        assert_eq!(None, picking_id([0, 0, 0, 255]));
+        assert_eq!(None, picking_id([0, 0, 5, 0]));
+        assert_eq!(None, picking_id([0, 0, 5, 100]));
+        assert_eq!(Some(5), picking_id([0, 0, 5, 200]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_picking_color_rejects_zero() {
+        picking_color(0);
+    }
+}
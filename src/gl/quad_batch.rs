@@ -0,0 +1,346 @@
+use web_sys::{
+    WebGlRenderingContext as GL,
+    WebGlTexture
+};
+
+use crate::{
+    BufferUsage,
+    Color,
+    IndexBuffer,
+    Layer,
+    Region,
+    RendererError,
+    ShaderProgram,
+    Texture,
+    TextureError,
+    VertexBuffer,
+    VertexLayout
+};
+
+const VERTEX_SHADER: &str = "
+attribute vec3 a_position;
+attribute vec2 a_tex_coords;
+attribute vec4 a_color;
+
+varying vec2 v_tex_coords;
+varying vec4 v_color;
+
+void main() {
+    gl_Position = vec4(a_position, 1.0);
+    v_tex_coords = a_tex_coords;
+    v_color = a_color;
+}
+";
+
+const FRAGMENT_SHADER: &str = "
+precision mediump float;
+
+uniform sampler2D u_texture;
+
+varying vec2 v_tex_coords;
+varying vec4 v_color;
+
+void main() {
+    gl_FragColor = texture2D(u_texture, v_tex_coords) * v_color;
+}
+";
+
+/// The number of floats per vertex: 3 for the position (including depth), 2 for the texture coordinates and 4 for the
+/// color
+const FLOATS_PER_VERTEX: usize = 9;
+
+/// The maximum number of quads per draw call, such that all vertex indices fit in 16 bits
+const MAX_QUADS_PER_DRAW: usize = 65536 / 4;
+
+/// The texture coordinates of the entire texture, see QuadBatch::push_texture
+pub const FULL_UV: [f32; 4] = [0.0, 0.0, 1.0, 1.0];
+
+#[derive(Clone,Copy,Debug,PartialEq)]
+struct Quad {
+    region: Region,
+    uv: [f32; 4],
+    color: Color,
+    texture_id: usize,
+    z: i32
+}
+
+/// A consecutive range of (sorted) quads that can be drawn with a single draw call.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+struct DrawRange {
+    texture_id: usize,
+    first_quad: usize,
+    num_quads: usize
+}
+
+/// Collects colored and textured quads (rectangles), and draws them with as few draw calls as possible. Quads with a
+/// lower z are drawn before quads with a higher z. Quads with the same z may be reordered to group them by texture,
+/// so they should not overlap each other. The z of each quad is also converted to a depth value (see Layer), so the
+/// depth test can be used as well.
+/// 
+/// The batch doesn't change the blend state, so blending should be enabled to draw transparent quads.
+pub struct QuadBatch {
+    program: ShaderProgram,
+    layout: VertexLayout,
+    vertex_buffer: VertexBuffer,
+    index_buffer: IndexBuffer,
+    // Colored quads are drawn with this 1x1 white texture, so that they can share draw calls with textured quads
+    white_texture: WebGlTexture,
+    // The distinct textures used by the current quads. The first one is always the white texture.
+    textures: Vec<WebGlTexture>,
+    quads: Vec<Quad>
+}
+
+impl QuadBatch {
+
+    pub fn new(gl: &GL) -> Result<QuadBatch, RendererError> {
+        let program = ShaderProgram::compile(gl, VERTEX_SHADER, FRAGMENT_SHADER)?;
+        let location = |name| program.attribute_location(name).expect("The quad shader uses all its attributes");
+        let layout = VertexLayout::new().attribute(location("a_position"), 3).attribute(location("a_tex_coords"), 2).attribute(location("a_color"), 4);
+
+        // When a step fails, the resources of the previous steps are deleted
+        let mut index_buffer = match IndexBuffer::new(gl, BufferUsage::Static) {
+            Ok(index_buffer) => index_buffer,
+            Err(error) => {
+                program.delete(gl);
+                return Err(error.into());
+            }
+        };
+        index_buffer.upload_u16(gl, &quad_indices(MAX_QUADS_PER_DRAW));
+
+        let vertex_buffer = match VertexBuffer::new(gl, BufferUsage::Stream) {
+            Ok(vertex_buffer) => vertex_buffer,
+            Err(error) => {
+                program.delete(gl);
+                index_buffer.delete(gl);
+                return Err(error.into());
+            }
+        };
+
+        let white_texture = match create_white_texture(gl) {
+            Ok(white_texture) => white_texture,
+            Err(error) => {
+                program.delete(gl);
+                index_buffer.delete(gl);
+                vertex_buffer.delete(gl);
+                return Err(error.into());
+            }
+        };
+
+        Ok(QuadBatch {
+            program,
+            layout,
+            vertex_buffer,
+            index_buffer,
+            white_texture: white_texture.clone(),
+            textures: vec![white_texture],
+            quads: Vec::new()
+        })
+    }
+
+    /// Adds a quad that fills the region with a solid color.
+    pub fn push_color(&mut self, region: Region, color: Color, z: i32) {
+        self.quads.push(Quad {
+            region,
+            uv: FULL_UV,
+            color,
+            texture_id: 0,
+            z
+        });
+    }
+
+    /// Adds a quad that draws the given part of the texture onto the region. The uv are (min_u, min_v, max_u, max_v),
+    /// where (0, 0) is the top-left corner of the texture and (1, 1) its bottom-right corner, so use FULL_UV to draw
+    /// the entire texture. The colors of the texture are multiplied with the tint (use Color::WHITE to keep them).
+    pub fn push_texture(&mut self, region: Region, texture: &Texture, uv: [f32; 4], tint: Color, z: i32) {
+        let gl_texture = texture.get_texture();
+        let texture_id = match self.textures.iter().position(|candidate| candidate == gl_texture) {
+            Some(texture_id) => texture_id,
+            None => {
+                self.textures.push(gl_texture.clone());
+                self.textures.len() - 1
+            }
+        };
+        self.quads.push(Quad {
+            region,
+            uv,
+            color: tint,
+            texture_id,
+            z
+        });
+    }
+
+    /// Gets the number of quads that will be drawn during the next flush.
+    pub fn len(&self) -> usize {
+        self.quads.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.quads.is_empty()
+    }
+
+    /// Draws all quads that were pushed since the previous flush, and clears the batch. Returns the number of draw
+    /// calls that were needed.
+    pub fn flush(&mut self, gl: &GL) -> usize {
+        if self.quads.is_empty() {
+            return 0;
+        }
+
+        let ranges = sort_and_plan(&mut self.quads);
+        let mut vertices = Vec::with_capacity(self.quads.len() * 4 * FLOATS_PER_VERTEX);
+        for quad in &self.quads {
+            push_vertices(&mut vertices, quad);
+        }
+        self.vertex_buffer.upload(gl, &vertices);
+
+        self.program.use_program(gl);
+        gl.uniform1i(self.program.uniform_location("u_texture"), 0);
+        gl.active_texture(GL::TEXTURE0);
+        self.layout.apply(gl, &self.vertex_buffer);
+        self.index_buffer.bind(gl);
+
+        for range in &ranges {
+            gl.bind_texture(GL::TEXTURE_2D, Some(&self.textures[range.texture_id]));
+            // The indices always start at vertex 0, so the attributes need to be offset for each range
+            let stride = self.layout.get_stride() as i32;
+            for attribute in self.layout.get_attributes() {
+                let offset = (range.first_quad * 4) as i32 * stride + attribute.offset as i32;
+                gl.vertex_attrib_pointer_with_i32(attribute.location, attribute.components as i32, GL::FLOAT, false, stride, offset);
+            }
+            gl.draw_elements_with_i32(GL::TRIANGLES, (range.num_quads * 6) as i32, GL::UNSIGNED_SHORT, 0);
+        }
+
+        self.quads.clear();
+        self.textures.truncate(1);
+        ranges.len()
+    }
+
+    /// Deletes the GL resources of this batch (but not the textures that were pushed). This struct must not be used
+    /// anymore after calling this.
+    pub fn delete(self, gl: &GL) {
+        self.program.delete(gl);
+        self.vertex_buffer.delete(gl);
+        self.index_buffer.delete(gl);
+        gl.delete_texture(Some(&self.white_texture));
+    }
+}
+
+/// Sorts the quads by z (stable, so quads keep their order within each z and texture), grouping quads with the same z
+/// by texture, and returns the ranges that can be drawn with a single draw call each.
+fn sort_and_plan(quads: &mut [Quad]) -> Vec<DrawRange> {
+    quads.sort_by_key(|quad| (quad.z, quad.texture_id));
+
+    let mut ranges: Vec<DrawRange> = Vec::new();
+    for (index, quad) in quads.iter().enumerate() {
+        match ranges.last_mut() {
+            Some(range) if range.texture_id == quad.texture_id && range.num_quads < MAX_QUADS_PER_DRAW => range.num_quads += 1,
+            _ => ranges.push(DrawRange {
+                texture_id: quad.texture_id,
+                first_quad: index,
+                num_quads: 1
+            })
+        }
+    }
+    ranges
+}
+
+fn push_vertices(vertices: &mut Vec<f32>, quad: &Quad) {
+    let region = quad.region;
+    let [min_u, min_v, max_u, max_v] = quad.uv;
+    let color = quad.color.to_float_array();
+    let depth = Layer::from_z_index(quad.z).depth();
+
+    // The v-axis of the texture points downwards, whereas the y-axis of the region points upwards
+    let corners = [
+        (region.get_float_min_x(), region.get_float_min_y(), min_u, max_v),
+        (region.get_float_max_x(), region.get_float_min_y(), max_u, max_v),
+        (region.get_float_max_x(), region.get_float_max_y(), max_u, min_v),
+        (region.get_float_min_x(), region.get_float_max_y(), min_u, min_v)
+    ];
+    for (x, y, u, v) in corners.iter() {
+        vertices.extend_from_slice(&[*x, *y, depth, *u, *v]);
+        vertices.extend_from_slice(&color);
+    }
+}
+
+fn create_white_texture(gl: &GL) -> Result<WebGlTexture, TextureError> {
+    let white_texture = gl.create_texture().ok_or(TextureError::CreationFailed)?;
+    gl.bind_texture(GL::TEXTURE_2D, Some(&white_texture));
+    if let Err(reason) = gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+        GL::TEXTURE_2D, 0, GL::RGBA as i32, 1, 1, 0, GL::RGBA, GL::UNSIGNED_BYTE, Some(&[255, 255, 255, 255])
+    ) {
+        gl.delete_texture(Some(&white_texture));
+        return Err(TextureError::UploadFailed(reason));
+    }
+    gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MIN_FILTER, GL::NEAREST as i32);
+    gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MAG_FILTER, GL::NEAREST as i32);
+    Ok(white_texture)
+}
+
+/// Generates the indices of num_quads quads, where each quad consists of 4 consecutive vertices.
+pub(crate) fn quad_indices(num_quads: usize) -> Vec<u16> {
+    let mut indices = Vec::with_capacity(num_quads * 6);
+    for quad in 0..num_quads {
+        let first = (quad * 4) as u16;
+        indices.extend_from_slice(&[first, first + 1, first + 2, first + 2, first + 3, first]);
+    }
+    indices
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn quad(texture_id: usize, z: i32) -> Quad {
+        Quad {
+            region: Region::entire_viewport(),
+            uv: FULL_UV,
+            color: Color::WHITE,
+            texture_id,
+            z
+        }
+    }
+
+    #[test]
+    fn test_sort_and_plan() {
+        let mut quads = vec![quad(1, 0), quad(0, 0), quad(1, 0), quad(2, 1), quad(0, -1)];
+        let ranges = sort_and_plan(&mut quads);
+
+        assert_eq!(vec![-1, 0, 0, 0, 1], quads.iter().map(|quad| quad.z).collect::<Vec<_>>());
+        assert_eq!(vec![
+            DrawRange { texture_id: 0, first_quad: 0, num_quads: 2 },
+            DrawRange { texture_id: 1, first_quad: 2, num_quads: 2 },
+            DrawRange { texture_id: 2, first_quad: 4, num_quads: 1 }
+        ], ranges);
+    }
+
+    #[test]
+    fn test_split_large_ranges() {
+        let mut quads = vec![quad(0, 0); MAX_QUADS_PER_DRAW + 1];
+        let ranges = sort_and_plan(&mut quads);
+        assert_eq!(2, ranges.len());
+        assert_eq!(MAX_QUADS_PER_DRAW, ranges[1].first_quad);
+    }
+
+    #[test]
+    fn test_push_vertices() {
+        let mut vertices = Vec::new();
+        let quad = Quad {
+            region: Region::new(-10_000, 0, 5_000, 10_000),
+            uv: [0.0, 0.25, 0.5, 1.0],
+            color: Color::RED,
+            texture_id: 0,
+            z: 0
+        };
+        push_vertices(&mut vertices, &quad);
+        assert_eq!(4 * FLOATS_PER_VERTEX, vertices.len());
+        let depth = Layer::new(0).depth();
+        assert_eq!(&[-1.0, 0.0, depth, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0], &vertices[0..9]);
+        assert_eq!(&[0.5, 1.0, depth, 0.5, 0.25], &vertices[18..23]);
+    }
+
+    #[test]
+    fn test_quad_indices() {
+        assert_eq!(vec![0, 1, 2, 2, 3, 0, 4, 5, 6, 6, 7, 4], quad_indices(2));
+    }
+}
@@ -0,0 +1,77 @@
+use web_sys::WebGlRenderingContext as GL;
+
+use crate::{
+    PixelRect,
+    Region
+};
+
+/// Converts the region to a pixel rectangle in GL window coordinates (with the origin at the bottom-left corner of the
+/// canvas and the y-axis pointing upwards), as expected by scissor and viewport. Adjacent regions are converted to
+/// adjacent rectangles, just like Region::to_pixel_rect.
+pub fn gl_pixel_rect(region: Region, canvas_size: (u32, u32)) -> PixelRect {
+    let (canvas_width, canvas_height) = canvas_size;
+    let dom_rect = region.to_pixel_rect(canvas_width, canvas_height);
+    PixelRect {
+        x: dom_rect.x,
+        y: canvas_height as i32 - dom_rect.y - dom_rect.height,
+        width: dom_rect.width.max(0),
+        height: dom_rect.height.max(0)
+    }
+}
+
+/// Sets the scissor box of the context to the given region of a canvas with the given size (in pixels). Note that this
+/// doesn't enable the scissor test.
+pub fn gl_scissor_region(gl: &GL, region: Region, canvas_size: (u32, u32)) {
+    let rect = gl_pixel_rect(region, canvas_size);
+    gl.scissor(rect.x, rect.y, rect.width, rect.height);
+}
+
+/// Sets the viewport of the context to the given region of a canvas with the given size (in pixels), so that the
+/// entire GL clip space ([-1, 1] in both directions) is mapped onto the region.
+pub fn gl_viewport_region(gl: &GL, region: Region, canvas_size: (u32, u32)) {
+    let rect = gl_pixel_rect(region, canvas_size);
+    gl.viewport(rect.x, rect.y, rect.width, rect.height);
+}
+
+/// Converts a number of pixels of a canvas with canvas_pixels pixels (in the same dimension) to a length in Region
+/// coordinates, rounding up so that the result covers at least the given number of pixels. The length is 0 when the
+/// canvas has no pixels (for instance when it is hidden).
+pub(crate) fn pixels_to_region(pixels: u32, canvas_pixels: u32) -> i32 {
+    if canvas_pixels == 0 {
+        return 0;
+    }
+    ((pixels as i64 * 20_001 + canvas_pixels as i64 - 1) / canvas_pixels as i64) as i32
+}
+
+/// Converts a size (width, height) in pixels to a size in Region coordinates, see pixels_to_region.
+pub(crate) fn pixel_size_to_region(width: u32, height: u32, canvas_width: u32, canvas_height: u32) -> (i32, i32) {
+    (pixels_to_region(width, canvas_width), pixels_to_region(height, canvas_height))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_gl_pixel_rect() {
+        assert_eq!(PixelRect { x: 0, y: 0, width: 800, height: 600 }, gl_pixel_rect(Region::entire_viewport(), (800, 600)));
+
+        // The top half of the canvas starts halfway in GL coordinates, unlike in DOM coordinates
+        let top_half = Region::new(-10_000, 1, 10_000, 10_000);
+        assert_eq!(PixelRect { x: 0, y: 300, width: 800, height: 300 }, gl_pixel_rect(top_half, (800, 600)));
+        let bottom_half = Region::new(-10_000, -10_000, 10_000, 0);
+        assert_eq!(PixelRect { x: 0, y: 0, width: 800, height: 300 }, gl_pixel_rect(bottom_half, (800, 600)));
+
+        let right_quarter = Region::new(5_001, -10_000, 10_000, 10_000);
+        assert_eq!(PixelRect { x: 600, y: 0, width: 200, height: 600 }, gl_pixel_rect(right_quarter, (800, 600)));
+    }
+
+    #[test]
+    fn test_pixel_size_to_region() {
+        assert_eq!((20_001, 20_001), pixel_size_to_region(800, 600, 800, 600));
+        assert_eq!((10_001, 3_334), pixel_size_to_region(400, 100, 800, 600));
+        assert_eq!((0, 0), pixel_size_to_region(400, 100, 0, 0));
+        assert_eq!((10_001, 0), pixel_size_to_region(400, 100, 800, 0));
+    }
+}
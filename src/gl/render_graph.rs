@@ -0,0 +1,396 @@
+use web_sys::WebGlRenderingContext as GL;
+
+use crate::{
+    Color,
+    RenderTarget,
+    RenderTargetError,
+    Texture
+};
+
+/// The reasons why a RenderGraph can't be executed.
+#[derive(Debug)]
+pub enum RenderGraphError {
+    /// The pass reads a target that no pass writes to
+    MissingTarget {
+        pass: String,
+        target: String
+    },
+    /// The passes depend on each other in a cycle (for instance because a pass reads the target it writes to), so
+    /// they can't be ordered. This contains the names of the passes that are part of (or depend on) the cycle.
+    Cycle(Vec<String>),
+    /// One of the intermediate render targets could not be created or resized
+    RenderTarget(RenderTargetError)
+}
+
+impl std::fmt::Display for RenderGraphError {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            RenderGraphError::MissingTarget { pass, target } => write!(f, "Pass '{}' reads target '{}', but no pass writes to it", pass, target),
+            RenderGraphError::Cycle(passes) => write!(f, "The passes {:?} depend on each other in a cycle", passes),
+            RenderGraphError::RenderTarget(error) => write!(f, "Failed to allocate an intermediate target: {}", error)
+        }
+    }
+}
+
+impl std::error::Error for RenderGraphError {}
+
+impl From<RenderTargetError> for RenderGraphError {
+
+    fn from(error: RenderTargetError) -> Self {
+        RenderGraphError::RenderTarget(error)
+    }
+}
+
+/// Where a RenderPass draws to.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub enum PassOutput {
+
+    /// The canvas itself
+    Canvas,
+    /// The intermediate target with the given name, which other passes can read
+    Target(String)
+}
+
+/// The textures of the targets that a pass reads, see RenderPass::read.
+pub struct PassInputs<'a> {
+    inputs: Vec<(&'a str, &'a Texture)>
+}
+
+impl PassInputs<'_> {
+
+    /// Gets the texture of the target with the given name, or None if the pass didn't declare that it reads it.
+    pub fn get(&self, target: &str) -> Option<&Texture> {
+        self.inputs.iter().find(|(name, _)| *name == target).map(|(_, texture)| *texture)
+    }
+}
+
+type PassFunction = Box<dyn FnMut(&GL, &PassInputs)>;
+
+/// A step of a RenderGraph, which reads any number of targets and writes one.
+pub struct RenderPass {
+    name: String,
+    reads: Vec<String>,
+    output: PassOutput,
+    execute: PassFunction
+}
+
+impl RenderPass {
+
+    /// Creates a pass that draws to the output with the execute function. When the function is called, the output is
+    /// already bound (with a viewport that covers it entirely).
+    pub fn new(name: &str, output: PassOutput, execute: impl FnMut(&GL, &PassInputs) + 'static) -> RenderPass {
+        RenderPass {
+            name: name.to_string(),
+            reads: Vec::new(),
+            output,
+            execute: Box::new(execute)
+        }
+    }
+
+    /// Declares that this pass reads the texture of the target with the given name, so it will be executed after all
+    /// passes that write to that target.
+    pub fn read(mut self, target: &str) -> RenderPass {
+        self.reads.push(target.to_string());
+        self
+    }
+}
+
+/// A pass of the planned frame, with the intermediate targets replaced by the physical targets (slots) they use.
+#[derive(Clone,Debug,PartialEq,Eq)]
+struct PlannedPass {
+    pass: usize,
+    // None for the canvas
+    output_slot: Option<usize>,
+    clear: bool,
+    input_slots: Vec<usize>
+}
+
+#[derive(Clone,Debug,PartialEq,Eq)]
+struct Plan {
+    steps: Vec<PlannedPass>,
+    slot_count: usize
+}
+
+/// Orders the passes (keeping the order in which they were added when possible), and assigns physical targets to the
+/// intermediate targets such that targets whose lifetimes don't overlap share the same physical target.
+fn plan_passes(passes: &[RenderPass]) -> Result<Plan, RenderGraphError> {
+    let writers = |target: &PassOutput| -> Vec<usize> {
+        passes.iter().enumerate().filter(|(_, pass)| pass.output == *target).map(|(index, _)| index).collect()
+    };
+
+    // Each pass depends on all writers of the targets it reads, and on the earlier writers of its own output
+    let mut dependencies = Vec::with_capacity(passes.len());
+    for (index, pass) in passes.iter().enumerate() {
+        let mut pass_dependencies: Vec<usize> = writers(&pass.output).into_iter().filter(|writer| *writer < index).collect();
+        for target in &pass.reads {
+            let target_writers = writers(&PassOutput::Target(target.clone()));
+            if target_writers.is_empty() {
+                return Err(RenderGraphError::MissingTarget {
+                    pass: pass.name.clone(),
+                    target: target.clone()
+                });
+            }
+            pass_dependencies.extend(target_writers);
+        }
+        dependencies.push(pass_dependencies);
+    }
+
+    let mut order = Vec::with_capacity(passes.len());
+    let mut done = vec![false; passes.len()];
+    while order.len() < passes.len() {
+        let next = (0..passes.len()).find(|index| !done[*index] && dependencies[*index].iter().all(|dependency| done[*dependency]));
+        match next {
+            Some(index) => {
+                done[index] = true;
+                order.push(index);
+            },
+            None => return Err(RenderGraphError::Cycle(
+                (0..passes.len()).filter(|index| !done[*index]).map(|index| passes[index].name.clone()).collect()
+            ))
+        }
+    }
+
+    // The lifetime (first and last step) of each intermediate target
+    let mut lifetimes: Vec<(&str, usize, usize)> = Vec::new();
+    for (step, index) in order.iter().enumerate() {
+        let pass = &passes[*index];
+        let used = pass.reads.iter().map(|target| target.as_str()).chain(match &pass.output {
+            PassOutput::Target(target) => Some(target.as_str()),
+            PassOutput::Canvas => None
+        });
+        for target in used {
+            match lifetimes.iter_mut().find(|(name, _, _)| *name == target) {
+                Some(lifetime) => lifetime.2 = step,
+                None => lifetimes.push((target, step, step))
+            }
+        }
+    }
+
+    let mut slots: Vec<Option<usize>> = vec![None; lifetimes.len()];
+    let mut free_slots = Vec::new();
+    let mut slot_count = 0;
+    let mut steps = Vec::with_capacity(order.len());
+    let mut canvas_cleared = false;
+    for (step, index) in order.iter().enumerate() {
+        for (target_index, (_, first, _)) in lifetimes.iter().enumerate() {
+            if *first == step {
+                slots[target_index] = Some(free_slots.pop().unwrap_or_else(|| {
+                    slot_count += 1;
+                    slot_count - 1
+                }));
+            }
+        }
+
+        let pass = &passes[*index];
+        let slot_of = |target: &str| {
+            let target_index = lifetimes.iter().position(|(name, _, _)| *name == target).expect("All targets have a lifetime");
+            (target_index, slots[target_index].expect("Targets get a slot at the start of their lifetime"))
+        };
+        let (output_slot, clear) = match &pass.output {
+            PassOutput::Canvas => {
+                let clear = !canvas_cleared;
+                canvas_cleared = true;
+                (None, clear)
+            },
+            PassOutput::Target(target) => {
+                let (target_index, slot) = slot_of(target);
+                (Some(slot), lifetimes[target_index].1 == step)
+            }
+        };
+        steps.push(PlannedPass {
+            pass: *index,
+            output_slot,
+            clear,
+            input_slots: pass.reads.iter().map(|target| slot_of(target).1).collect()
+        });
+
+        for (target_index, (_, _, last)) in lifetimes.iter().enumerate() {
+            if *last == step {
+                free_slots.push(slots[target_index].expect("Targets keep their slot until the end of their lifetime"));
+            }
+        }
+    }
+
+    Ok(Plan {
+        steps,
+        slot_count
+    })
+}
+
+/// Executes render passes that read the results of other passes, for instance a blur pass that reads the scene and a
+/// composite pass that combines the blurred scene with the user interface. The graph orders the passes based on the
+/// targets they read and write, creates the intermediate targets (and reuses them when their contents are no longer
+/// needed), and clears each target before the first pass that writes to it.
+///
+/// All intermediate targets have the size of the canvas.
+pub struct RenderGraph {
+
+    passes: Vec<RenderPass>,
+    plan: Option<Plan>,
+    targets: Vec<RenderTarget>,
+    canvas_clear_color: Option<Color>
+}
+
+impl RenderGraph {
+
+    /// Creates an empty graph that clears the canvas to transparent before the first pass that draws to it.
+    pub fn new() -> RenderGraph {
+        RenderGraph {
+            passes: Vec::new(),
+            plan: None,
+            targets: Vec::new(),
+            canvas_clear_color: Some(Color::TRANSPARENT)
+        }
+    }
+
+    pub fn add_pass(&mut self, pass: RenderPass) {
+        self.passes.push(pass);
+        self.plan = None;
+    }
+
+    /// Sets the color to which the canvas is cleared, or None to keep the content of the canvas (for instance when
+    /// only the dirty regions are redrawn). Intermediate targets are always cleared to transparent.
+    pub fn set_canvas_clear_color(&mut self, color: Option<Color>) {
+        self.canvas_clear_color = color;
+    }
+
+    /// Executes all passes for a canvas of the given size (in pixels). The passes are only ordered again when passes
+    /// were added since the previous call. The canvas is the current render target afterwards.
+    pub fn execute(&mut self, gl: &GL, canvas_size: (u32, u32)) -> Result<(), RenderGraphError> {
+        if self.plan.is_none() {
+            self.plan = Some(plan_passes(&self.passes)?);
+        }
+        let plan = self.plan.as_ref().expect("The plan was just created");
+
+        let (width, height) = canvas_size;
+        for target in &mut self.targets {
+            target.resize(gl, width, height)?;
+        }
+        while self.targets.len() < plan.slot_count {
+            self.targets.push(RenderTarget::new(gl, width, height)?);
+        }
+
+        for step in &plan.steps {
+            let clear_color = match step.output_slot {
+                Some(slot) => {
+                    self.targets[slot].bind(gl);
+                    Some(Color::TRANSPARENT)
+                },
+                None => {
+                    RenderTarget::unbind(gl);
+                    gl.viewport(0, 0, width as i32, height as i32);
+                    self.canvas_clear_color
+                }
+            };
+            if let (true, Some(color)) = (step.clear, clear_color) {
+                gl.clear_color(color.get_red_float(), color.get_green_float(), color.get_blue_float(), color.get_alpha_float());
+                gl.clear(GL::COLOR_BUFFER_BIT);
+            }
+
+            let targets = &self.targets;
+            let pass = &mut self.passes[step.pass];
+            let inputs = PassInputs {
+                inputs: pass.reads.iter().zip(&step.input_slots)
+                    .map(|(name, slot)| (name.as_str(), targets[*slot].as_texture())).collect()
+            };
+            (pass.execute)(gl, &inputs);
+        }
+
+        RenderTarget::unbind(gl);
+        gl.viewport(0, 0, width as i32, height as i32);
+        Ok(())
+    }
+
+    /// Deletes the intermediate targets. This struct must not be used anymore after calling this.
+    pub fn delete(self, gl: &GL) {
+        for target in self.targets {
+            target.delete(gl);
+        }
+    }
+}
+
+impl Default for RenderGraph {
+
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn pass(name: &str, output: Option<&str>, reads: &[&str]) -> RenderPass {
+        let output = match output {
+            Some(target) => PassOutput::Target(target.to_string()),
+            None => PassOutput::Canvas
+        };
+        reads.iter().fold(RenderPass::new(name, output, |_, _| {}), |pass, target| pass.read(target))
+    }
+
+    fn step(pass: usize, output_slot: Option<usize>, clear: bool, input_slots: Vec<usize>) -> PlannedPass {
+        PlannedPass {
+            pass,
+            output_slot,
+            clear,
+            input_slots
+        }
+    }
+
+    #[test]
+    fn test_plan_order_and_clears() {
+        // The composite pass is added first, but needs to wait for the scene and blur passes
+        let passes = [
+            pass("composite", None, &["blurred", "scene"]),
+            pass("scene", Some("scene"), &[]),
+            pass("blur", Some("blurred"), &["scene"]),
+            pass("overlay", None, &[])
+        ];
+        let plan = plan_passes(&passes).unwrap();
+        assert_eq!(vec![
+            step(1, Some(0), true, vec![]),
+            step(2, Some(1), true, vec![0]),
+            step(0, None, true, vec![1, 0]),
+            step(3, None, false, vec![])
+        ], plan.steps);
+        assert_eq!(2, plan.slot_count);
+    }
+
+    #[test]
+    fn test_plan_reuses_targets() {
+        let passes = [
+            pass("a", Some("a"), &[]),
+            pass("b", Some("b"), &["a"]),
+            pass("c", Some("c"), &["b"]),
+            pass("c2", Some("c"), &[]),
+            pass("final", None, &["c"])
+        ];
+        let plan = plan_passes(&passes).unwrap();
+
+        // Target a is no longer needed when c is written, so c can use the same physical target
+        assert_eq!(vec![
+            step(0, Some(0), true, vec![]),
+            step(1, Some(1), true, vec![0]),
+            step(2, Some(0), true, vec![1]),
+            step(3, Some(0), false, vec![]),
+            step(4, None, true, vec![0])
+        ], plan.steps);
+        assert_eq!(2, plan.slot_count);
+    }
+
+    #[test]
+    fn test_plan_errors() {
+        match plan_passes(&[pass("a", None, &["missing"])]) {
+            Err(RenderGraphError::MissingTarget { pass, target }) => assert_eq!(("a", "missing"), (pass.as_str(), target.as_str())),
+            _ => panic!("Expected MissingTarget")
+        }
+
+        match plan_passes(&[pass("a", Some("a"), &["b"]), pass("b", Some("b"), &["a"]), pass("c", None, &[])]) {
+            Err(RenderGraphError::Cycle(passes)) => assert_eq!(vec!["a", "b"], passes),
+            _ => panic!("Expected Cycle")
+        }
+        assert!(matches!(plan_passes(&[pass("a", Some("a"), &["a"])]), Err(RenderGraphError::Cycle(_))));
+    }
+}
@@ -0,0 +1,132 @@
+use web_sys::{
+    WebGlFramebuffer,
+    WebGlRenderingContext as GL
+};
+
+use crate::{
+    GlCanvas,
+    Texture,
+    TextureError
+};
+
+/// The reasons why creating or resizing a RenderTarget can fail.
+#[derive(Debug)]
+pub enum RenderTargetError {
+    /// The framebuffer or texture could not be created, which normally means that the context is lost
+    CreationFailed,
+    /// The backing texture could not be (re)allocated
+    Texture(TextureError),
+    /// The framebuffer is not complete. The status is the result of checkFramebufferStatus.
+    Incomplete(u32)
+}
+
+impl std::fmt::Display for RenderTargetError {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            RenderTargetError::CreationFailed => write!(f, "Failed to create the framebuffer (is the context lost?)"),
+            RenderTargetError::Texture(error) => write!(f, "Failed to allocate the texture of the render target: {}", error),
+            RenderTargetError::Incomplete(status) => write!(f, "The framebuffer is incomplete (status {:#x})", status)
+        }
+    }
+}
+
+impl std::error::Error for RenderTargetError {}
+
+impl From<TextureError> for RenderTargetError {
+
+    fn from(error: TextureError) -> Self {
+        match error {
+            TextureError::CreationFailed => RenderTargetError::CreationFailed,
+            other => RenderTargetError::Texture(other)
+        }
+    }
+}
+
+/// An offscreen framebuffer with a color texture attached to it. Components can render to it (for instance to cache
+/// expensive widgets, or for post-processing), and then draw the texture onto the canvas.
+pub struct RenderTarget {
+    framebuffer: WebGlFramebuffer,
+    texture: Texture
+}
+
+impl RenderTarget {
+
+    /// Creates a render target with a texture of the given size (in pixels).
+    pub fn new(gl: &GL, width: u32, height: u32) -> Result<RenderTarget, RenderTargetError> {
+        let texture = Texture::create_empty(gl, width, height)?;
+        let framebuffer = match gl.create_framebuffer() {
+            Some(framebuffer) => framebuffer,
+            None => {
+                texture.delete(gl);
+                return Err(RenderTargetError::CreationFailed);
+            }
+        };
+
+        gl.bind_framebuffer(GL::FRAMEBUFFER, Some(&framebuffer));
+        gl.framebuffer_texture_2d(GL::FRAMEBUFFER, GL::COLOR_ATTACHMENT0, GL::TEXTURE_2D, Some(texture.get_texture()), 0);
+        let status = gl.check_framebuffer_status(GL::FRAMEBUFFER);
+        gl.bind_framebuffer(GL::FRAMEBUFFER, None);
+
+        if status != GL::FRAMEBUFFER_COMPLETE {
+            gl.delete_framebuffer(Some(&framebuffer));
+            texture.delete(gl);
+            return Err(RenderTargetError::Incomplete(status));
+        }
+
+        Ok(RenderTarget {
+            framebuffer,
+            texture
+        })
+    }
+
+    /// Makes this the current render target, and sets the viewport to cover the entire texture.
+    pub fn bind(&self, gl: &GL) {
+        gl.bind_framebuffer(GL::FRAMEBUFFER, Some(&self.framebuffer));
+        gl.viewport(0, 0, self.texture.get_width() as i32, self.texture.get_height() as i32);
+    }
+
+    pub(crate) fn get_framebuffer(&self) -> &WebGlFramebuffer {
+        &self.framebuffer
+    }
+
+    /// Makes the canvas the current render target again. Note that this doesn't restore the viewport.
+    pub fn unbind(gl: &GL) {
+        gl.bind_framebuffer(GL::FRAMEBUFFER, None);
+    }
+
+    /// Makes the canvas the current render target again, and sets the viewport to cover the entire canvas. The canvas
+    /// can be an HtmlCanvasElement or an OffscreenCanvas.
+    pub fn unbind_to_canvas<C: GlCanvas>(gl: &GL, canvas: &C) {
+        Self::unbind(gl);
+        gl.viewport(0, 0, canvas.get_width() as i32, canvas.get_height() as i32);
+    }
+
+    /// Changes the size of the texture of this render target. This discards its content, and does nothing if the size
+    /// doesn't change.
+    pub fn resize(&mut self, gl: &GL, width: u32, height: u32) -> Result<(), RenderTargetError> {
+        if width != self.texture.get_width() || height != self.texture.get_height() {
+            self.texture.resize_storage(gl, width, height)?;
+        }
+        Ok(())
+    }
+
+    /// Gets the texture that is rendered to, so that it can be drawn onto another target.
+    pub fn as_texture(&self) -> &Texture {
+        &self.texture
+    }
+
+    pub fn get_width(&self) -> u32 {
+        self.texture.get_width()
+    }
+
+    pub fn get_height(&self) -> u32 {
+        self.texture.get_height()
+    }
+
+    /// Deletes the framebuffer and its texture. This struct must not be used anymore after calling this.
+    pub fn delete(self, gl: &GL) {
+        gl.delete_framebuffer(Some(&self.framebuffer));
+        self.texture.delete(gl);
+    }
+}
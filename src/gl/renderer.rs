@@ -0,0 +1,323 @@
+use wasm_bindgen::JsCast;
+
+use web_sys::{
+    CanvasRenderingContext2d,
+    HtmlCanvasElement,
+    HtmlImageElement,
+    WebGlRenderingContext as GL
+};
+
+use crate::{
+    Arena,
+    BlendMode,
+    Color,
+    FULL_UV,
+    Handle,
+    QuadBatch,
+    Region,
+    RenderedText,
+    RendererError,
+    TextColors,
+    TextRenderer,
+    Texture,
+    gl_scissor_region,
+    measure_text,
+    paint_text,
+    try_get_gl
+};
+
+/// A texture (or image) that was created by a Renderer, and can only be drawn by that same renderer.
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+pub struct RendererTexture(Handle);
+
+/// The basic drawing operations of a user interface, implemented with WebGL (WebGlRenderer) and with the 2d canvas
+/// context (Canvas2dRenderer). The 2d implementation is meant as fallback for browsers and devices where WebGL is not
+/// available, so apps that only use these operations still work there. Use create_renderer to pick the best one.
+///
+/// Everything is drawn in the order of the calls, but the drawing may be postponed until the next flush.
+pub trait Renderer {
+
+    /// Gets the size of the canvas, in pixels.
+    fn get_canvas_size(&self) -> (u32, u32);
+
+    /// Fills the entire canvas (or only the clip region, if there is one) with the given color.
+    fn clear(&mut self, color: Color);
+
+    /// Fills the region with a solid color, which will be blended with the background if it is transparent.
+    fn fill_region(&mut self, region: Region, color: Color);
+
+    /// Creates a texture from an image that was already loaded (and decoded).
+    fn create_texture(&mut self, image: &HtmlImageElement) -> Result<RendererTexture, RendererError>;
+
+    /// Deletes the texture. This does nothing if the texture was deleted already.
+    fn delete_texture(&mut self, texture: RendererTexture);
+
+    /// Draws the entire texture stretched onto the region.
+    fn draw_texture(&mut self, region: Region, texture: RendererTexture) -> Result<(), RendererError>;
+
+    /// Draws the text with the given CSS font (for instance "16px sans-serif") and colors at its natural size, with
+    /// its top-left corner at the top-left corner of the region. The text is not clipped to the region.
+    fn draw_text(&mut self, region: Region, text: &str, font: &str, colors: TextColors) -> Result<(), RendererError>;
+
+    /// Restricts all following drawing operations to the given region, or removes the restriction if it is None.
+    fn set_clip(&mut self, clip: Option<Region>);
+
+    /// Draws everything that was postponed.
+    fn flush(&mut self);
+
+    /// Deletes all resources of this renderer (including its textures). The renderer must not be used anymore after
+    /// calling this.
+    fn delete(self: Box<Self>);
+}
+
+/// Creates a WebGlRenderer for the canvas if it supports WebGL, and a Canvas2dRenderer otherwise. Note that the fallback
+/// only covers the case where no WebGL context can be acquired: if the WebGL context is acquired, but creating the
+/// WebGlRenderer fails (for instance because a shader doesn't compile on a buggy driver), that error is returned.
+/// A canvas can't get a 2d context once it has a WebGL context, so the caller would need a new canvas in that case.
+pub fn create_renderer(canvas: &HtmlCanvasElement) -> Result<Box<dyn Renderer>, RendererError> {
+    if let Ok(gl) = try_get_gl(canvas) {
+        return Ok(Box::new(WebGlRenderer::new(gl)?));
+    }
+    match canvas.get_context("2d").map_err(RendererError::Canvas2d)? {
+        Some(context) => Ok(Box::new(Canvas2dRenderer::new(context.dyn_into::<CanvasRenderingContext2d>()
+            .expect("The 2d context should be an instance of CanvasRenderingContext2d")))),
+        None => Err(RendererError::NoContext)
+    }
+}
+
+/// Gets the region of a text of the given size (in Region coordinates), with its top-left corner at the top-left
+/// corner of the target region
+pub(crate) fn text_region(target: Region, size: (i32, i32)) -> Region {
+    let (width, height) = size;
+    Region::new(target.get_min_x(), target.get_max_y() - height + 1, target.get_min_x() + width - 1, target.get_max_y())
+}
+
+/// The Renderer implementation that uses WebGL, with a QuadBatch for the regions, textures and texts. It uses alpha
+/// blending (BlendMode::Alpha) and the scissor test for the clip region.
+pub struct WebGlRenderer {
+    gl: GL,
+    batch: QuadBatch,
+    text_renderer: TextRenderer,
+    textures: Arena<Texture>,
+    // The texts in the batch, which are deleted after the next flush
+    pending_texts: Vec<RenderedText>,
+    // Every quad gets a higher z than the previous one, so that the batch keeps them in the order of the calls
+    next_z: i32
+}
+
+impl WebGlRenderer {
+
+    pub fn new(gl: GL) -> Result<WebGlRenderer, RendererError> {
+        Ok(WebGlRenderer {
+            batch: QuadBatch::new(&gl)?,
+            gl,
+            text_renderer: TextRenderer::new(),
+            textures: Arena::new(),
+            pending_texts: Vec::new(),
+            next_z: 0
+        })
+    }
+
+    pub fn gl(&self) -> &GL {
+        &self.gl
+    }
+
+    fn next_z(&mut self) -> i32 {
+        self.next_z += 1;
+        self.next_z
+    }
+}
+
+impl Renderer for WebGlRenderer {
+
+    fn get_canvas_size(&self) -> (u32, u32) {
+        (self.gl.drawing_buffer_width() as u32, self.gl.drawing_buffer_height() as u32)
+    }
+
+    fn clear(&mut self, color: Color) {
+        self.flush();
+        self.gl.clear_color(color.get_red_float(), color.get_green_float(), color.get_blue_float(), color.get_alpha_float());
+        self.gl.clear(GL::COLOR_BUFFER_BIT);
+    }
+
+    fn fill_region(&mut self, region: Region, color: Color) {
+        let z = self.next_z();
+        self.batch.push_color(region, color, z);
+    }
+
+    fn create_texture(&mut self, image: &HtmlImageElement) -> Result<RendererTexture, RendererError> {
+        let texture = Texture::from_image(&self.gl, image)?;
+        Ok(RendererTexture(self.textures.insert(texture)))
+    }
+
+    fn delete_texture(&mut self, texture: RendererTexture) {
+        // The batch might still refer to the texture
+        self.flush();
+        if let Some(texture) = self.textures.remove(texture.0) {
+            texture.delete(&self.gl);
+        }
+    }
+
+    fn draw_texture(&mut self, region: Region, texture: RendererTexture) -> Result<(), RendererError> {
+        let z = self.next_z();
+        if let Some(texture) = self.textures.get(texture.0) {
+            self.batch.push_texture(region, texture, FULL_UV, Color::WHITE, z);
+        }
+        Ok(())
+    }
+
+    fn draw_text(&mut self, region: Region, text: &str, font: &str, colors: TextColors) -> Result<(), RendererError> {
+        let rendered = self.text_renderer.render(&self.gl, text, font, colors)?;
+        let (canvas_width, canvas_height) = self.get_canvas_size();
+        let region = text_region(region, rendered.size_in_region(canvas_width, canvas_height));
+        let z = self.next_z();
+        self.batch.push_texture(region, &rendered.texture, FULL_UV, Color::WHITE, z);
+        self.pending_texts.push(rendered);
+        Ok(())
+    }
+
+    fn set_clip(&mut self, clip: Option<Region>) {
+        self.flush();
+        match clip {
+            Some(region) => {
+                gl_scissor_region(&self.gl, region, self.get_canvas_size());
+                self.gl.enable(GL::SCISSOR_TEST);
+            },
+            None => self.gl.disable(GL::SCISSOR_TEST)
+        }
+    }
+
+    fn flush(&mut self) {
+        if !self.batch.is_empty() {
+            BlendMode::Alpha.apply(&self.gl);
+            self.batch.flush(&self.gl);
+        }
+        for text in self.pending_texts.drain(..) {
+            text.texture.delete(&self.gl);
+        }
+        self.next_z = 0;
+    }
+
+    fn delete(mut self: Box<Self>) {
+        self.flush();
+        let handles: Vec<Handle> = self.textures.iter().map(|(handle, _)| handle).collect();
+        for handle in handles {
+            if let Some(texture) = self.textures.remove(handle) {
+                texture.delete(&self.gl);
+            }
+        }
+        self.batch.delete(&self.gl);
+    }
+}
+
+/// The Renderer implementation that uses the 2d context of the canvas. Everything is drawn immediately, so flush does
+/// nothing.
+pub struct Canvas2dRenderer {
+    context: CanvasRenderingContext2d,
+    images: Arena<HtmlImageElement>,
+    // Whether the state of the context was saved before clipping, so it can be restored to remove the clip
+    clipped: bool
+}
+
+impl Canvas2dRenderer {
+
+    pub fn new(context: CanvasRenderingContext2d) -> Canvas2dRenderer {
+        Canvas2dRenderer {
+            context,
+            images: Arena::new(),
+            clipped: false
+        }
+    }
+
+    pub fn get_context(&self) -> &CanvasRenderingContext2d {
+        &self.context
+    }
+
+    fn fill_rect(&self, region: Region, color: Color) {
+        let (canvas_width, canvas_height) = self.get_canvas_size();
+        let rect = region.to_pixel_rect(canvas_width, canvas_height);
+        self.context.set_fill_style_str(&color.to_string());
+        self.context.fill_rect(rect.x as f64, rect.y as f64, rect.width as f64, rect.height as f64);
+    }
+}
+
+impl Renderer for Canvas2dRenderer {
+
+    fn get_canvas_size(&self) -> (u32, u32) {
+        let canvas = self.context.canvas().expect("The 2d context should belong to an HtmlCanvasElement");
+        (canvas.width(), canvas.height())
+    }
+
+    fn clear(&mut self, color: Color) {
+        let (canvas_width, canvas_height) = self.get_canvas_size();
+        self.context.clear_rect(0.0, 0.0, canvas_width as f64, canvas_height as f64);
+        self.fill_rect(Region::entire_viewport(), color);
+    }
+
+    fn fill_region(&mut self, region: Region, color: Color) {
+        self.fill_rect(region, color);
+    }
+
+    fn create_texture(&mut self, image: &HtmlImageElement) -> Result<RendererTexture, RendererError> {
+        Ok(RendererTexture(self.images.insert(image.clone())))
+    }
+
+    fn delete_texture(&mut self, texture: RendererTexture) {
+        self.images.remove(texture.0);
+    }
+
+    fn draw_texture(&mut self, region: Region, texture: RendererTexture) -> Result<(), RendererError> {
+        if let Some(image) = self.images.get(texture.0) {
+            let (canvas_width, canvas_height) = self.get_canvas_size();
+            let rect = region.to_pixel_rect(canvas_width, canvas_height);
+            self.context.draw_image_with_html_image_element_and_dw_and_dh(
+                image, rect.x as f64, rect.y as f64, rect.width as f64, rect.height as f64
+            ).map_err(RendererError::Canvas2d)?;
+        }
+        Ok(())
+    }
+
+    fn draw_text(&mut self, region: Region, text: &str, font: &str, colors: TextColors) -> Result<(), RendererError> {
+        let (canvas_width, canvas_height) = self.get_canvas_size();
+        let rect = region.to_pixel_rect(canvas_width, canvas_height);
+        let size = measure_text(&self.context, text, font).map_err(RendererError::Canvas2d)?;
+        paint_text(&self.context, text, font, colors, (rect.x as f64, rect.y as f64), size).map_err(RendererError::Canvas2d)
+    }
+
+    fn set_clip(&mut self, clip: Option<Region>) {
+        if self.clipped {
+            self.context.restore();
+            self.clipped = false;
+        }
+        if let Some(region) = clip {
+            let (canvas_width, canvas_height) = self.get_canvas_size();
+            let rect = region.to_pixel_rect(canvas_width, canvas_height);
+            self.context.save();
+            self.context.begin_path();
+            self.context.rect(rect.x as f64, rect.y as f64, rect.width as f64, rect.height as f64);
+            self.context.clip();
+            self.clipped = true;
+        }
+    }
+
+    fn flush(&mut self) {}
+
+    fn delete(mut self: Box<Self>) {
+        self.set_clip(None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_text_region() {
+        let target = Region::new(-5_000, -2_000, 5_000, 3_000);
+        assert_eq!(Region::new(-5_000, 2_001, -4_001, 3_000), text_region(target, (1_000, 1_000)));
+
+        // Texts that are larger than the target region stick out at the right and bottom
+        assert_eq!(Region::new(-5_000, -7_999, 15_999, 3_000), text_region(target, (21_000, 11_000)));
+    }
+}
@@ -0,0 +1,55 @@
+use wasm_bindgen::JsValue;
+
+use crate::{
+    BufferError,
+    ShaderError,
+    TextureError
+};
+
+/// The reasons why creating (or using) one of the built-in renderers (like QuadBatch) can fail.
+#[derive(Debug)]
+pub enum RendererError {
+    Shader(ShaderError),
+    Buffer(BufferError),
+    Texture(TextureError),
+    /// The canvas supports neither WebGL nor the 2d context
+    NoContext,
+    /// A call to the 2d context of the canvas failed
+    Canvas2d(JsValue)
+}
+
+impl std::fmt::Display for RendererError {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            RendererError::Shader(error) => write!(f, "Failed to create the shader of the renderer: {}", error),
+            RendererError::Buffer(error) => write!(f, "Failed to create the buffers of the renderer: {}", error),
+            RendererError::Texture(error) => write!(f, "Failed to create the texture of the renderer: {}", error),
+            RendererError::NoContext => write!(f, "The canvas supports neither WebGL nor the 2d context"),
+            RendererError::Canvas2d(reason) => write!(f, "A call to the 2d context failed: {:?}", reason)
+        }
+    }
+}
+
+impl std::error::Error for RendererError {}
+
+impl From<ShaderError> for RendererError {
+
+    fn from(error: ShaderError) -> Self {
+        RendererError::Shader(error)
+    }
+}
+
+impl From<BufferError> for RendererError {
+
+    fn from(error: BufferError) -> Self {
+        RendererError::Buffer(error)
+    }
+}
+
+impl From<TextureError> for RendererError {
+
+    fn from(error: TextureError) -> Self {
+        RendererError::Texture(error)
+    }
+}
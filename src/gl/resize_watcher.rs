@@ -0,0 +1,160 @@
+use std::cell::RefCell;
+use std::rc::{
+    Rc,
+    Weak
+};
+
+use js_sys::{
+    Array,
+    Reflect
+};
+
+use wasm_bindgen::{
+    JsCast,
+    JsValue
+};
+use wasm_bindgen::closure::Closure;
+
+use web_sys::{
+    HtmlCanvasElement,
+    ResizeObserver,
+    ResizeObserverEntry,
+    ResizeObserverSize
+};
+
+use crate::{
+    Region,
+    SafeAreaInsets,
+    WeakVec,
+    backing_store_size,
+    device_pixel_ratio,
+    publish_orientation_change,
+    safe_area_insets
+};
+
+/// The new size of a canvas, as reported by a CanvasResizeWatcher.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct CanvasSize {
+
+    /// The number of device pixels the canvas covers horizontally. This is the width its drawing buffer should get.
+    pub pixel_width: u32,
+    /// The number of device pixels the canvas covers vertically. This is the height its drawing buffer should get.
+    pub pixel_height: u32,
+
+    /// The width of the canvas, in CSS pixels
+    pub css_width: f64,
+    /// The height of the canvas, in CSS pixels
+    pub css_height: f64,
+
+    /// The Region of the canvas that is not obscured by notches, rounded corners or home bars. This is only exact if
+    /// the canvas covers the entire window, which is the common case for applications using this crate.
+    pub root_region: Region
+}
+
+type ResizeListeners = Rc<RefCell<WeakVec<dyn FnMut(CanvasSize)>>>;
+
+/// Watches the layout size of a canvas using a ResizeObserver, and notifies its listeners whenever it changes. This
+/// also catches size changes that are not caused by a resize of the window (like a sidebar that is collapsed), and
+/// it doesn't need to poll.
+///
+/// The observer is disconnected when this watcher is dropped.
+pub struct CanvasResizeWatcher {
+    observer: ResizeObserver,
+    listeners: ResizeListeners,
+    _on_resize: Closure<dyn FnMut(Array)>
+}
+
+impl CanvasResizeWatcher {
+
+    pub fn new(canvas: &HtmlCanvasElement) -> CanvasResizeWatcher {
+        let listeners: ResizeListeners = Rc::new(RefCell::new(WeakVec::new()));
+
+        let listeners_clone = Rc::clone(&listeners);
+        let on_resize = Closure::wrap(Box::new(move |entries: Array| {
+            // Only the last entry matters when the canvas was resized multiple times before the observer fired
+            if let Some(entry) = entries.iter().last() {
+                let size = measure_entry(entry.unchecked_ref());
+                publish_orientation_change();
+                WeakVec::for_each_mut_shared(&listeners_clone, |listener| listener(size));
+            }
+        }) as Box<dyn FnMut(Array)>);
+
+        let observer = ResizeObserver::new(on_resize.as_ref().unchecked_ref()).expect("Should be able to create a ResizeObserver");
+        observer.observe(canvas);
+
+        CanvasResizeWatcher {
+            observer,
+            listeners,
+            _on_resize: on_resize
+        }
+    }
+
+    /// Adds a listener that will be called each time the size of the canvas changes (and once shortly after the
+    /// watcher is created), until the last Rc to the listener is dropped.
+    pub fn add_listener(&self, listener: Weak<RefCell<dyn FnMut(CanvasSize)>>) {
+        self.listeners.borrow_mut().push(listener);
+    }
+}
+
+impl Drop for CanvasResizeWatcher {
+
+    fn drop(&mut self) {
+        self.observer.disconnect();
+    }
+}
+
+fn measure_entry(entry: &ResizeObserverEntry) -> CanvasSize {
+    let rect = entry.content_rect();
+
+    // devicePixelContentBoxSize gives the exact number of device pixels, but not all browsers support it yet
+    let device_size = if Reflect::has(entry, &JsValue::from_str("devicePixelContentBoxSize")).unwrap_or(false) {
+        let sizes = entry.device_pixel_content_box_size();
+        if sizes.length() > 0 {
+            let size: ResizeObserverSize = sizes.get(0).unchecked_into();
+            Some((size.inline_size(), size.block_size()))
+        } else {
+            None
+        }
+    } else {
+        None
+    };
+
+    canvas_size(rect.width(), rect.height(), device_size, device_pixel_ratio(), safe_area_insets())
+}
+
+fn canvas_size(
+    css_width: f64, css_height: f64, device_size: Option<(f64, f64)>, pixel_ratio: f64, insets: SafeAreaInsets
+) -> CanvasSize {
+    let (pixel_width, pixel_height) = match device_size {
+        Some((width, height)) => (width.round().max(1.0) as u32, height.round().max(1.0) as u32),
+        None => backing_store_size(css_width, css_height, pixel_ratio)
+    };
+
+    CanvasSize {
+        pixel_width,
+        pixel_height,
+        css_width,
+        css_height,
+        root_region: insets.to_region(css_width as f32, css_height as f32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_canvas_size_prefers_device_pixels() {
+        let size = canvas_size(333.3, 200.0, Some((500.0, 300.0)), 1.5, SafeAreaInsets::NONE);
+        assert_eq!((500, 300), (size.pixel_width, size.pixel_height));
+        assert_eq!(Region::entire_viewport(), size.root_region);
+    }
+
+    #[test]
+    fn test_canvas_size_falls_back_to_pixel_ratio() {
+        let size = canvas_size(800.0, 600.0, None, 2.0, SafeAreaInsets::NONE);
+        assert_eq!((1600, 1200), (size.pixel_width, size.pixel_height));
+        assert_eq!((800.0, 600.0), (size.css_width, size.css_height));
+    }
+}
@@ -0,0 +1,44 @@
+use web_sys::{
+    HtmlCanvasElement,
+    WebGlRenderingContext as GL
+};
+
+/// Gets the devicePixelRatio of the window, which is the number of device pixels per CSS pixel (for instance 2.0 on
+/// most HiDPI displays). Returns 1.0 if there is no window.
+pub fn device_pixel_ratio() -> f64 {
+    web_sys::window().map(|window| window.device_pixel_ratio()).filter(|ratio| *ratio > 0.0).unwrap_or(1.0)
+}
+
+pub(crate) fn backing_store_size(css_width: f64, css_height: f64, pixel_ratio: f64) -> (u32, u32) {
+    ((css_width * pixel_ratio).round().max(1.0) as u32, (css_height * pixel_ratio).round().max(1.0) as u32)
+}
+
+/// Makes the canvas css_width by css_height CSS pixels large, and gives it a drawing buffer with one pixel per device
+/// pixel, so that text and edges are crisp on HiDPI displays. The viewport of the context is updated to cover the
+/// entire drawing buffer. Returns the size of the drawing buffer, in pixels.
+pub fn configure_canvas_resolution(canvas: &HtmlCanvasElement, gl: &GL, css_width: f64, css_height: f64) -> (u32, u32) {
+    let (width, height) = backing_store_size(css_width, css_height, device_pixel_ratio());
+    canvas.set_width(width);
+    canvas.set_height(height);
+
+    let style = canvas.style();
+    style.set_property("width", &format!("{}px", css_width)).expect("Should be able to set the canvas width");
+    style.set_property("height", &format!("{}px", css_height)).expect("Should be able to set the canvas height");
+
+    gl.viewport(0, 0, width as i32, height as i32);
+    (width, height)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_backing_store_size() {
+        assert_eq!((800, 600), backing_store_size(800.0, 600.0, 1.0));
+        assert_eq!((1600, 1200), backing_store_size(800.0, 600.0, 2.0));
+        assert_eq!((1001, 751), backing_store_size(667.5, 500.5, 1.5));
+        assert_eq!((1, 1), backing_store_size(0.0, 0.1, 2.0));
+    }
+}
@@ -0,0 +1,104 @@
+use js_sys::Promise;
+
+use wasm_bindgen::{
+    Clamped,
+    JsCast,
+    JsValue
+};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen_futures::JsFuture;
+
+use web_sys::{
+    Blob,
+    CanvasRenderingContext2d,
+    HtmlCanvasElement,
+    ImageData,
+    WebGlRenderingContext as GL
+};
+
+use crate::{
+    Region,
+    gl_pixel_rect
+};
+
+/// Reads the RGBA pixels of the given region of the canvas that is currently being rendered to. The result contains
+/// the rows from top to bottom (like images do, rather than bottom to top like GL does), and its size is given by
+/// gl_pixel_rect(region, canvas_size).
+///
+/// Note that the drawing buffer is cleared after each frame unless preserveDrawingBuffer is enabled (which is the
+/// default for contexts acquired by this crate), so this should otherwise be called before returning to the browser.
+pub fn read_region_pixels(gl: &GL, region: Region, canvas_size: (u32, u32)) -> Vec<u8> {
+    let rect = gl_pixel_rect(region, canvas_size);
+    let mut pixels = vec![0; 4 * rect.width as usize * rect.height as usize];
+    if !pixels.is_empty() {
+        gl.read_pixels_with_opt_u8_array(rect.x, rect.y, rect.width, rect.height, GL::RGBA, GL::UNSIGNED_BYTE, Some(&mut pixels))
+            .expect("readPixels should not fail");
+        flip_rows(&mut pixels, rect.width as usize);
+    }
+    pixels
+}
+
+/// Encodes the RGBA pixels (with the rows from top to bottom, as returned by read_region_pixels) as PNG image and
+/// returns it as data URL, which can for instance be used as href of a download link.
+pub fn pixels_to_png_data_url(pixels: &[u8], width: u32, height: u32) -> Result<String, JsValue> {
+    pixels_to_canvas(pixels, width, height)?.to_data_url_with_type("image/png")
+}
+
+/// Encodes the RGBA pixels (with the rows from top to bottom, as returned by read_region_pixels) as PNG image. Unlike
+/// pixels_to_png_data_url, the encoding doesn't block the main thread.
+pub async fn pixels_to_png_blob(pixels: &[u8], width: u32, height: u32) -> Result<Blob, JsValue> {
+    let canvas = pixels_to_canvas(pixels, width, height)?;
+    let promise = Promise::new(&mut |resolve, reject| {
+        let reject_clone = reject.clone();
+        let on_blob = Closure::once_into_js(move |blob: Option<Blob>| {
+            match blob {
+                Some(blob) => resolve.call1(&JsValue::NULL, &blob),
+                None => reject_clone.call1(&JsValue::NULL, &JsValue::from_str("The image could not be encoded"))
+            }.expect("Resolving a promise should not fail");
+        });
+        if let Err(reason) = canvas.to_blob_with_type(on_blob.unchecked_ref(), "image/png") {
+            reject.call1(&JsValue::NULL, &reason).expect("Rejecting a promise should not fail");
+        }
+    });
+    Ok(JsFuture::from(promise).await?.unchecked_into())
+}
+
+fn pixels_to_canvas(pixels: &[u8], width: u32, height: u32) -> Result<HtmlCanvasElement, JsValue> {
+    let document = web_sys::window().expect("There should be a window").document().expect("The window should have a document");
+    let canvas = document.create_element("canvas")?.dyn_into::<HtmlCanvasElement>()
+        .expect("A canvas element should be an HtmlCanvasElement");
+    canvas.set_width(width);
+    canvas.set_height(height);
+
+    let context = canvas.get_context("2d")?.expect("get_context('2d') should not fail (2)")
+        .dyn_into::<CanvasRenderingContext2d>().expect("The 2d context should be an instance of CanvasRenderingContext2d");
+    let image_data = ImageData::new_with_u8_clamped_array_and_sh(Clamped(pixels), width, height)?;
+    context.put_image_data(&image_data, 0.0, 0.0)?;
+    Ok(canvas)
+}
+
+fn flip_rows(pixels: &mut [u8], width: usize) {
+    let row_length = 4 * width;
+    let height = pixels.len() / row_length;
+    for row in 0..height / 2 {
+        let (top, bottom) = pixels.split_at_mut((height - row - 1) * row_length);
+        top[row * row_length..(row + 1) * row_length].swap_with_slice(&mut bottom[..row_length]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_flip_rows() {
+        let mut pixels = vec![1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3];
+        flip_rows(&mut pixels, 1);
+        assert_eq!(vec![3, 3, 3, 3, 2, 2, 2, 2, 1, 1, 1, 1], pixels);
+
+        let mut pixels = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
+        flip_rows(&mut pixels, 2);
+        assert_eq!(vec![9, 10, 11, 12, 13, 14, 15, 16, 1, 2, 3, 4, 5, 6, 7, 8], pixels);
+    }
+}
@@ -0,0 +1,417 @@
+use std::collections::HashMap;
+
+use web_sys::WebGlRenderingContext as GL;
+
+use crate::{
+    BufferUsage,
+    Color,
+    Region,
+    RendererError,
+    ShaderProgram,
+    Texture,
+    TextureError,
+    UniformLocations,
+    VertexBuffer,
+    VertexLayout,
+    create_hidden_canvas,
+    uniform_block
+};
+
+const VERTEX_SHADER: &str = "
+attribute vec2 a_position;
+attribute vec2 a_tex_coords;
+
+varying vec2 v_tex_coords;
+
+void main() {
+    gl_Position = vec4(a_position, 0.0, 1.0);
+    v_tex_coords = a_tex_coords;
+}
+";
+
+const FRAGMENT_SHADER: &str = "
+precision mediump float;
+
+uniform sampler2D u_atlas;
+uniform vec4 u_color;
+uniform float u_smoothing;
+
+varying vec2 v_tex_coords;
+
+void main() {
+    float distance = texture2D(u_atlas, v_tex_coords).a;
+    float coverage = smoothstep(0.5 - u_smoothing, 0.5 + u_smoothing, distance);
+    gl_FragColor = vec4(u_color.rgb, u_color.a * coverage);
+}
+";
+
+/// All printable ASCII characters, which is a reasonable default character set for SdfFont::new
+pub const PRINTABLE_ASCII: &str = " !\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`abcdefghijklmnopqrstuvwxyz{|}~";
+
+/// The width of the atlas texture in pixels, unless a glyph is wider than this
+const MIN_ATLAS_WIDTH: u32 = 512;
+
+/// 2 for the position and 2 for the texture coordinates
+const FLOATS_PER_VERTEX: usize = 4;
+
+/// The place and metrics of a glyph in the atlas of an SdfFont. All sizes are in atlas pixels.
+#[derive(Clone,Copy,Debug,PartialEq)]
+struct SdfGlyph {
+    /// (min_u, min_v, max_u, max_v) of the box of the glyph, where v = 0 is the top of the atlas
+    uv: [f32; 4],
+    /// The size of the box of the glyph, including the spread around it
+    width: u32,
+    height: u32,
+    /// The distance from the start of this glyph to the start of the next one
+    advance: f32
+}
+
+/// A font whose glyphs are stored as signed distance fields in an atlas texture. Unlike the textures of a
+/// TextRenderer, these glyphs stay sharp at any size, so texts don't need to be rasterized again when the region they
+/// are drawn in is animated or scaled. Use SdfTextRenderer to draw texts with it.
+pub struct SdfFont {
+    atlas: Texture,
+    glyphs: HashMap<char, SdfGlyph>,
+    /// The height of a line (without spread), in atlas pixels
+    line_height: f32,
+    /// The distance (in atlas pixels) that is covered by the distance fields at each side of the edges of the glyphs
+    spread: u32
+}
+
+impl SdfFont {
+
+    /// Rasterizes the given characters in the given CSS font family (for instance "sans-serif") and stores their
+    /// distance fields in a new atlas. The glyphs are rasterized at the given size (in pixels): larger sizes give
+    /// sharper corners at large text sizes, but need a larger atlas. A size around 32 works well for user interfaces.
+    /// This fails with InvalidSettings if there are no characters.
+    pub fn new(gl: &GL, font_family: &str, glyph_size: u32, characters: &str) -> Result<SdfFont, TextureError> {
+        if characters.is_empty() {
+            return Err(TextureError::InvalidSettings("An SdfFont needs at least 1 character"));
+        }
+        let (canvas, context) = create_hidden_canvas();
+        let font = format!("{}px {}", glyph_size, font_family);
+        let spread = (glyph_size / 8).max(2);
+
+        let mut rasterized = Vec::new();
+        let mut line_height = 0.0;
+        for character in characters.chars() {
+            let text = character.to_string();
+            context.set_font(&font);
+            let metrics = context.measure_text(&text).map_err(TextureError::LoadFailed)?;
+            let ascent = metrics.font_bounding_box_ascent();
+            let glyph_line_height = ascent + metrics.font_bounding_box_descent();
+            line_height = glyph_line_height;
+
+            let width = metrics.width().ceil() as u32 + 2 * spread;
+            let height = glyph_line_height.ceil() as u32 + 2 * spread;
+
+            // Resizing the canvas clears it and resets the state of the context
+            canvas.set_width(width);
+            canvas.set_height(height);
+            context.set_font(&font);
+            context.set_fill_style_str("white");
+            context.fill_text(&text, spread as f64, spread as f64 + ascent).map_err(TextureError::LoadFailed)?;
+            let image = context.get_image_data(0.0, 0.0, width as f64, height as f64).map_err(TextureError::LoadFailed)?;
+            let coverage: Vec<u8> = image.data().0.chunks(4).map(|pixel| pixel[3]).collect();
+
+            rasterized.push((character, width, height, metrics.width() as f32, signed_distance_field(&coverage, width, height, spread)));
+        }
+
+        let sizes: Vec<(u32, u32)> = rasterized.iter().map(|(_, width, height, _, _)| (*width, *height)).collect();
+        let (positions, atlas_width, atlas_height) = pack_glyphs(&sizes, MIN_ATLAS_WIDTH);
+        if line_height <= 0.0 {
+            return Err(TextureError::InvalidSettings("The font has no height"));
+        }
+
+        let mut pixels = vec![255; 4 * atlas_width as usize * atlas_height as usize];
+        let mut glyphs = HashMap::new();
+        for ((character, width, height, advance, distances), (x, y)) in rasterized.into_iter().zip(positions) {
+            for row in 0..height {
+                for column in 0..width {
+                    let atlas_index = 4 * ((y + row) * atlas_width + x + column) as usize;
+                    pixels[atlas_index + 3] = distances[(row * width + column) as usize];
+                }
+            }
+            glyphs.insert(character, SdfGlyph {
+                uv: [
+                    x as f32 / atlas_width as f32, y as f32 / atlas_height as f32,
+                    (x + width) as f32 / atlas_width as f32, (y + height) as f32 / atlas_height as f32
+                ],
+                width,
+                height,
+                advance
+            });
+        }
+
+        Ok(SdfFont {
+            atlas: Texture::from_rgba(gl, atlas_width, atlas_height, &pixels)?,
+            glyphs,
+            line_height: line_height as f32,
+            spread
+        })
+    }
+
+    pub fn get_atlas(&self) -> &Texture {
+        &self.atlas
+    }
+
+    /// Deletes the atlas texture. This struct must not be used anymore after calling this.
+    pub fn delete(self, gl: &GL) {
+        self.atlas.delete(gl);
+    }
+}
+
+/// Computes the signed distance field of a glyph from its coverage (alpha values). The result has the same size, and
+/// encodes the distance to the edge of the glyph such that 128 is on the edge, 255 is spread pixels (or more) inside
+/// the glyph, and 0 is spread pixels (or more) outside it.
+fn signed_distance_field(coverage: &[u8], width: u32, height: u32, spread: u32) -> Vec<u8> {
+    let inside: Vec<bool> = coverage.iter().map(|alpha| *alpha >= 128).collect();
+    let outside: Vec<bool> = inside.iter().map(|is_inside| !is_inside).collect();
+    let distance_to_inside = distance_transform(&inside, width, height);
+    let distance_to_outside = distance_transform(&outside, width, height);
+
+    inside.iter().zip(distance_to_inside).zip(distance_to_outside).map(|((is_inside, to_inside), to_outside)| {
+        // The edge lies halfway between the centers of an inside pixel and an outside pixel
+        let signed_distance = if *is_inside { to_outside - 0.5 } else { 0.5 - to_inside };
+        (128.0 + signed_distance * 127.0 / spread as f32).round().clamp(0.0, 255.0) as u8
+    }).collect()
+}
+
+/// Computes the distance from every pixel to the nearest pixel for which target is true, using the 8SSEDT algorithm
+/// (which propagates the offset to the nearest target pixel in two passes over the image).
+fn distance_transform(target: &[bool], width: u32, height: u32) -> Vec<f32> {
+    const FAR: (i32, i32) = (100_000, 100_000);
+    let (width, height) = (width as i32, height as i32);
+    let mut offsets: Vec<(i32, i32)> = target.iter().map(|is_target| if *is_target { (0, 0) } else { FAR }).collect();
+
+    let length_squared = |offset: (i32, i32)| offset.0 as i64 * offset.0 as i64 + offset.1 as i64 * offset.1 as i64;
+    let compare = |offsets: &mut [(i32, i32)], x: i32, y: i32, dx: i32, dy: i32| {
+        let (other_x, other_y) = (x + dx, y + dy);
+        if other_x < 0 || other_y < 0 || other_x >= width || other_y >= height {
+            return;
+        }
+        let other = offsets[(other_y * width + other_x) as usize];
+        let candidate = (other.0 + dx, other.1 + dy);
+        let index = (y * width + x) as usize;
+        if length_squared(candidate) < length_squared(offsets[index]) {
+            offsets[index] = candidate;
+        }
+    };
+
+    for y in 0..height {
+        for x in 0..width {
+            for (dx, dy) in [(-1, 0), (0, -1), (-1, -1), (1, -1)].iter() {
+                compare(&mut offsets, x, y, *dx, *dy);
+            }
+        }
+        for x in (0..width).rev() {
+            compare(&mut offsets, x, y, 1, 0);
+        }
+    }
+    for y in (0..height).rev() {
+        for x in (0..width).rev() {
+            for (dx, dy) in [(1, 0), (0, 1), (-1, 1), (1, 1)].iter() {
+                compare(&mut offsets, x, y, *dx, *dy);
+            }
+        }
+        for x in 0..width {
+            compare(&mut offsets, x, y, -1, 0);
+        }
+    }
+
+    offsets.into_iter().map(|offset| (length_squared(offset) as f64).sqrt() as f32).collect()
+}
+
+/// Places the glyphs (with the given sizes) in rows from left to right and top to bottom, and returns their positions
+/// and the width and height of the atlas. The atlas is made wider than min_atlas_width if a glyph doesn't fit in it.
+fn pack_glyphs(sizes: &[(u32, u32)], min_atlas_width: u32) -> (Vec<(u32, u32)>, u32, u32) {
+    let atlas_width = sizes.iter().map(|(width, _)| *width).fold(min_atlas_width, u32::max);
+    let mut positions = Vec::with_capacity(sizes.len());
+    let (mut x, mut y, mut row_height) = (0, 0, 0);
+    for (width, height) in sizes {
+        if x + width > atlas_width && x > 0 {
+            x = 0;
+            y += row_height;
+            row_height = 0;
+        }
+        positions.push((x, y));
+        x += width;
+        row_height = row_height.max(*height);
+    }
+    (positions, atlas_width, (y + row_height).max(1))
+}
+
+/// Generates 2 triangles per glyph for the text, scaled such that a line fills the height of the region. The text
+/// starts at the left of the region, and characters that are not in the font are skipped.
+fn text_vertices(glyphs: &HashMap<char, SdfGlyph>, line_height: f32, spread: u32, text: &str, region: Region, canvas_size: (u32, u32)) -> Vec<f32> {
+    let (canvas_width, canvas_height) = canvas_size;
+    // The size of an atlas pixel in GL coordinates, keeping the aspect ratio of the glyphs on the canvas
+    let scale_y = region.get_float_height() / line_height;
+    let scale_x = scale_y * canvas_height as f32 / canvas_width as f32;
+
+    let mut vertices = Vec::with_capacity(text.len() * 6 * FLOATS_PER_VERTEX);
+    let mut x = region.get_float_min_x() - spread as f32 * scale_x;
+    let top = region.get_float_max_y() + spread as f32 * scale_y;
+    for character in text.chars() {
+        let glyph = match glyphs.get(&character) {
+            Some(glyph) => glyph,
+            None => continue
+        };
+        let [min_u, min_v, max_u, max_v] = glyph.uv;
+        let (min_x, max_x) = (x, x + glyph.width as f32 * scale_x);
+        let (min_y, max_y) = (top - glyph.height as f32 * scale_y, top);
+        let corners = [
+            (min_x, min_y, min_u, max_v), (max_x, min_y, max_u, max_v), (max_x, max_y, max_u, min_v),
+            (max_x, max_y, max_u, min_v), (min_x, max_y, min_u, min_v), (min_x, min_y, min_u, max_v)
+        ];
+        for (x, y, u, v) in corners.iter() {
+            vertices.extend_from_slice(&[*x, *y, *u, *v]);
+        }
+        x += glyph.advance * scale_x;
+    }
+    vertices
+}
+
+struct SdfUniforms {
+    atlas: i32,
+    color: Color,
+    smoothing: f32
+}
+
+uniform_block!(SdfUniforms {
+    atlas => "u_atlas",
+    color => "u_color",
+    smoothing => "u_smoothing"
+});
+
+/// Draws texts with an SdfFont. The blend state is not changed, so blending should be enabled, because the edges of
+/// the glyphs are partially transparent.
+pub struct SdfTextRenderer {
+    program: ShaderProgram,
+    uniforms: UniformLocations<SdfUniforms>,
+    layout: VertexLayout,
+    vertex_buffer: VertexBuffer
+}
+
+impl SdfTextRenderer {
+
+    pub fn new(gl: &GL) -> Result<SdfTextRenderer, RendererError> {
+        let program = ShaderProgram::compile(gl, VERTEX_SHADER, FRAGMENT_SHADER)?;
+        let location = |name| program.attribute_location(name).expect("The SDF text shader uses all its attributes");
+        let layout = VertexLayout::new().attribute(location("a_position"), 2).attribute(location("a_tex_coords"), 2);
+        let vertex_buffer = match VertexBuffer::new(gl, BufferUsage::Stream) {
+            Ok(vertex_buffer) => vertex_buffer,
+            Err(error) => {
+                program.delete(gl);
+                return Err(error.into());
+            }
+        };
+        Ok(SdfTextRenderer {
+            uniforms: UniformLocations::new(&program),
+            program,
+            layout,
+            vertex_buffer
+        })
+    }
+
+    /// Draws the text in the given color, scaled such that a line of text fills the height of the region, starting at
+    /// the left of the region. The text is not clipped to the region. The canvas size (in pixels) is needed to keep
+    /// the aspect ratio of the glyphs and to compute the anti-aliasing width.
+    pub fn draw_text(&mut self, gl: &GL, font: &SdfFont, text: &str, region: Region, color: Color, canvas_size: (u32, u32)) {
+        let vertices = text_vertices(&font.glyphs, font.line_height, font.spread, text, region, canvas_size);
+        if vertices.is_empty() {
+            return;
+        }
+
+        // The edge is smoothed over 1 screen pixel. Each atlas pixel changes the encoded distance by 127 / 255 / spread.
+        let pixels_per_texel = region.to_pixel_rect(canvas_size.0, canvas_size.1).height.max(1) as f32 / font.line_height;
+        let smoothing = 0.5 * 127.0 / (255.0 * font.spread as f32 * pixels_per_texel);
+
+        self.vertex_buffer.upload(gl, &vertices);
+        self.program.use_program(gl);
+        font.atlas.bind(gl, 0);
+        self.uniforms.upload(gl, &SdfUniforms {
+            atlas: 0,
+            color,
+            smoothing
+        });
+        self.layout.apply(gl, &self.vertex_buffer);
+        gl.draw_arrays(GL::TRIANGLES, 0, (vertices.len() / FLOATS_PER_VERTEX) as i32);
+    }
+
+    /// Deletes the GL resources of this renderer (but not the fonts). This struct must not be used anymore after
+    /// calling this.
+    pub fn delete(self, gl: &GL) {
+        self.program.delete(gl);
+        self.vertex_buffer.delete(gl);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_signed_distance_field() {
+        // A 4x4 square in the middle of a 12x12 image
+        let mut coverage = vec![0; 144];
+        for y in 4..8 {
+            for x in 4..8 {
+                coverage[y * 12 + x] = 255;
+            }
+        }
+        let field = signed_distance_field(&coverage, 12, 12, 4);
+        assert_eq!(0, field[0]);
+        assert_eq!(176, field[5 * 12 + 5]);
+        assert_eq!(144, field[4 * 12 + 4]);
+        assert_eq!(112, field[3 * 12 + 4]);
+        assert_eq!(field[5 * 12 + 5], field[6 * 12 + 6]);
+    }
+
+    #[test]
+    fn test_distance_transform() {
+        let mut target = vec![false; 25];
+        target[12] = true;
+        let distances = distance_transform(&target, 5, 5);
+        assert_eq!(0.0, distances[12]);
+        assert_eq!(1.0, distances[13]);
+        assert_eq!(2.0, distances[10]);
+        assert!((distances[0] - 8f32.sqrt()).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_pack_glyphs() {
+        let (positions, width, height) = pack_glyphs(&[(200, 30), (200, 40), (200, 30), (500, 10)], 512);
+        assert_eq!(vec![(0, 0), (200, 0), (0, 40), (0, 70)], positions);
+        assert_eq!((512, 80), (width, height));
+
+        // The atlas grows when a glyph is wider than it
+        let (positions, width, height) = pack_glyphs(&[(200, 30), (600, 10), (300, 20)], 512);
+        assert_eq!(vec![(0, 0), (0, 30), (0, 40)], positions);
+        assert_eq!((600, 60), (width, height));
+    }
+
+    #[test]
+    fn test_text_vertices() {
+        let mut glyphs = HashMap::new();
+        glyphs.insert('a', SdfGlyph {
+            uv: [0.0, 0.0, 0.5, 1.0],
+            width: 14,
+            height: 24,
+            advance: 10.0
+        });
+        let region = Region::new(-10_000, -10_000, 9_999, 9_999);
+        let vertices = text_vertices(&glyphs, 20.0, 2, "a?a", region, (100, 100));
+        assert_eq!(2 * 6 * FLOATS_PER_VERTEX, vertices.len());
+
+        // A line of 20 texels fills the height of 2.0, so every texel is 0.1
+        let first = &vertices[0..4];
+        assert!((first[0] - (-1.2)).abs() < 0.001);
+        assert!((first[1] - (1.2 - 2.4)).abs() < 0.001);
+        assert_eq!(&[0.0, 1.0], &first[2..4]);
+
+        // The second glyph starts 10 texels further, since the unknown character is skipped
+        assert!((vertices[24] - (-0.2)).abs() < 0.001);
+    }
+}
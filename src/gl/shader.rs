@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+
+use web_sys::{
+    WebGlProgram,
+    WebGlRenderingContext as GL,
+    WebGlShader,
+    WebGlUniformLocation
+};
+
+/// The stage of a shader, used to tell which shader failed to compile.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum ShaderStage {
+
+    Vertex,
+    Fragment
+}
+
+/// The reasons why ShaderProgram::compile can fail.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub enum ShaderError {
+    /// createShader or createProgram returned null, which normally means that the context is lost
+    CreationFailed,
+    /// A shader failed to compile. The log contains the info log of the shader, where each error is followed by the
+    /// source line it refers to.
+    Compile {
+        stage: ShaderStage,
+        log: String
+    },
+    /// The program failed to link, for instance because the vertex shader and fragment shader don't match
+    Link {
+        log: String
+    }
+}
+
+impl std::fmt::Display for ShaderError {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ShaderError::CreationFailed => write!(f, "Failed to create the shader or program (is the context lost?)"),
+            ShaderError::Compile { stage, log } => write!(f, "The {:?} shader failed to compile:\n{}", stage, log),
+            ShaderError::Link { log } => write!(f, "The shader program failed to link:\n{}", log)
+        }
+    }
+}
+
+impl std::error::Error for ShaderError {}
+
+/// A compiled and linked shader program, together with the locations of all its active attributes and uniforms.
+pub struct ShaderProgram {
+    program: WebGlProgram,
+    attributes: HashMap<String, u32>,
+    uniforms: HashMap<String, WebGlUniformLocation>
+}
+
+impl ShaderProgram {
+
+    /// Compiles the vertex shader and fragment shader, and links them into a program. The locations of all active
+    /// attributes and uniforms are looked up immediately, so that they don't need to be queried during rendering.
+    pub fn compile(gl: &GL, vertex_source: &str, fragment_source: &str) -> Result<ShaderProgram, ShaderError> {
+        let vertex_shader = compile_shader(gl, ShaderStage::Vertex, vertex_source)?;
+        let fragment_shader = match compile_shader(gl, ShaderStage::Fragment, fragment_source) {
+            Ok(shader) => shader,
+            Err(error) => {
+                gl.delete_shader(Some(&vertex_shader));
+                return Err(error);
+            }
+        };
+
+        let program = gl.create_program();
+        if let Some(program) = &program {
+            gl.attach_shader(program, &vertex_shader);
+            gl.attach_shader(program, &fragment_shader);
+            gl.link_program(program);
+        }
+
+        // The shaders are no longer needed once the program has been linked
+        gl.delete_shader(Some(&vertex_shader));
+        gl.delete_shader(Some(&fragment_shader));
+
+        let program = program.ok_or(ShaderError::CreationFailed)?;
+        if !gl.get_program_parameter(&program, GL::LINK_STATUS).as_bool().unwrap_or(false) {
+            let log = gl.get_program_info_log(&program).unwrap_or_default();
+            gl.delete_program(Some(&program));
+            return Err(ShaderError::Link { log });
+        }
+
+        let mut attributes = HashMap::new();
+        let num_attributes = gl.get_program_parameter(&program, GL::ACTIVE_ATTRIBUTES).as_f64().unwrap_or(0.0) as u32;
+        for index in 0..num_attributes {
+            if let Some(info) = gl.get_active_attrib(&program, index) {
+                let location = gl.get_attrib_location(&program, &info.name());
+                if location >= 0 {
+                    attributes.insert(info.name(), location as u32);
+                }
+            }
+        }
+
+        let mut uniforms = HashMap::new();
+        let num_uniforms = gl.get_program_parameter(&program, GL::ACTIVE_UNIFORMS).as_f64().unwrap_or(0.0) as u32;
+        for index in 0..num_uniforms {
+            if let Some(info) = gl.get_active_uniform(&program, index) {
+                if let Some(location) = gl.get_uniform_location(&program, &info.name()) {
+                    // Arrays are reported as "name[0]", but are usually referred to as just "name"
+                    let name = info.name();
+                    if let Some(array_name) = name.strip_suffix("[0]") {
+                        uniforms.insert(array_name.to_string(), location.clone());
+                    }
+                    uniforms.insert(name, location);
+                }
+            }
+        }
+
+        Ok(ShaderProgram {
+            program,
+            attributes,
+            uniforms
+        })
+    }
+
+    pub fn get_program(&self) -> &WebGlProgram {
+        &self.program
+    }
+
+    /// Makes this the current program of the given context.
+    pub fn use_program(&self, gl: &GL) {
+        gl.use_program(Some(&self.program));
+    }
+
+    /// Gets the location of the active attribute with the given name, or None if the program doesn't have such an
+    /// attribute (note that the compiler removes attributes that are not used).
+    pub fn attribute_location(&self, name: &str) -> Option<u32> {
+        self.attributes.get(name).copied()
+    }
+
+    /// Gets the location of the active uniform with the given name, or None if the program doesn't have such a
+    /// uniform (note that the compiler removes uniforms that are not used).
+    pub fn uniform_location(&self, name: &str) -> Option<&WebGlUniformLocation> {
+        self.uniforms.get(name)
+    }
+
+    /// Deletes the program. This struct must not be used anymore after calling this.
+    pub fn delete(self, gl: &GL) {
+        gl.delete_program(Some(&self.program));
+    }
+}
+
+fn compile_shader(gl: &GL, stage: ShaderStage, source: &str) -> Result<WebGlShader, ShaderError> {
+    let shader_type = match stage {
+        ShaderStage::Vertex => GL::VERTEX_SHADER,
+        ShaderStage::Fragment => GL::FRAGMENT_SHADER
+    };
+    let shader = gl.create_shader(shader_type).ok_or(ShaderError::CreationFailed)?;
+    gl.shader_source(&shader, source);
+    gl.compile_shader(&shader);
+
+    if gl.get_shader_parameter(&shader, GL::COMPILE_STATUS).as_bool().unwrap_or(false) {
+        Ok(shader)
+    } else {
+        let log = gl.get_shader_info_log(&shader).unwrap_or_default();
+        gl.delete_shader(Some(&shader));
+        Err(ShaderError::Compile {
+            stage,
+            log: annotate_log(&log, source)
+        })
+    }
+}
+
+/// Shader info logs refer to source lines like "ERROR: 0:12: 'foo' : undeclared identifier". This function adds the
+/// referenced source line below each such log line, so that the error can be understood without counting lines.
+fn annotate_log(log: &str, source: &str) -> String {
+    let source_lines: Vec<&str> = source.lines().collect();
+    let mut result = String::new();
+    for log_line in log.lines() {
+        result.push_str(log_line);
+        result.push('\n');
+        if let Some(line_number) = parse_line_number(log_line) {
+            if line_number >= 1 && line_number <= source_lines.len() {
+                result.push_str(&format!("{:>5} | {}\n", line_number, source_lines[line_number - 1]));
+            }
+        }
+    }
+    result
+}
+
+fn parse_line_number(log_line: &str) -> Option<usize> {
+    // The format is "<severity>: <source string>:<line>: <message>"
+    let mut parts = log_line.splitn(4, ':');
+    parts.next()?;
+    parts.next()?;
+    parts.next()?.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_annotate_log() {
+        let source = "void main() {\n    gl_FragColor = colour;\n}";
+        let log = "ERROR: 0:2: 'colour' : undeclared identifier\nERROR: 1 compilation errors";
+        assert_eq!(
+            "ERROR: 0:2: 'colour' : undeclared identifier\n    2 |     gl_FragColor = colour;\nERROR: 1 compilation errors\n",
+            annotate_log(log, source)
+        );
+    }
+
+    #[test]
+    fn test_parse_line_number() {
+        assert_eq!(Some(12), parse_line_number("ERROR: 0:12: 'foo' : undeclared identifier"));
+        assert_eq!(Some(3), parse_line_number("WARNING: 0:3: extension not supported"));
+        assert_eq!(None, parse_line_number("ERROR: 1 compilation errors"));
+        assert_eq!(None, parse_line_number(""));
+    }
+}
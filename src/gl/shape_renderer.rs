@@ -0,0 +1,294 @@
+use web_sys::WebGlRenderingContext as GL;
+
+use crate::{
+    BufferUsage,
+    Color,
+    IndexBuffer,
+    Region,
+    RendererError,
+    ShaderProgram,
+    VertexBuffer,
+    VertexLayout,
+    quad_indices
+};
+
+const VERTEX_SHADER: &str = "
+attribute vec2 a_position;
+attribute vec2 a_local;
+attribute vec2 a_half_size;
+attribute vec2 a_shape;
+attribute vec4 a_color;
+
+varying vec2 v_local;
+varying vec2 v_half_size;
+varying vec2 v_shape;
+varying vec4 v_color;
+
+void main() {
+    gl_Position = vec4(a_position, 0.0, 1.0);
+    v_local = a_local;
+    v_half_size = a_half_size;
+    v_shape = a_shape;
+    v_color = a_color;
+}
+";
+
+const FRAGMENT_SHADER: &str = "
+precision mediump float;
+
+varying vec2 v_local;
+varying vec2 v_half_size;
+varying vec2 v_shape;
+varying vec4 v_color;
+
+float rounded_box_distance(vec2 point, vec2 half_size, float radius) {
+    vec2 q = abs(point) - half_size + radius;
+    return length(max(q, 0.0)) + min(max(q.x, q.y), 0.0) - radius;
+}
+
+float ellipse_distance(vec2 point, vec2 radii) {
+    // An approximation that is exact for circles, and good enough for anti-aliasing the edges of ellipses
+    return (length(point / radii) - 1.0) * min(radii.x, radii.y);
+}
+
+void main() {
+    float distance = v_shape.y > 0.5 ? ellipse_distance(v_local, v_half_size) : rounded_box_distance(v_local, v_half_size, v_shape.x);
+    float coverage = clamp(0.5 - distance, 0.0, 1.0);
+    gl_FragColor = vec4(v_color.rgb, v_color.a * coverage);
+}
+";
+
+/// 2 for the position, 2 for the local position, 2 for the half size, 2 for the shape (radius, is_ellipse) and 4 for
+/// the color
+const FLOATS_PER_VERTEX: usize = 12;
+
+const MAX_SHAPES_PER_DRAW: usize = 65536 / 4;
+
+/// The shapes are drawn slightly larger than they are, so that there is room for their anti-aliased edges
+const EDGE_MARGIN: f32 = 1.0;
+
+/// Generates the vertices of the shapes, without needing a GL context.
+struct ShapeVertices {
+    canvas_size: (u32, u32),
+    vertices: Vec<f32>
+}
+
+impl ShapeVertices {
+
+    /// Converts a Region coordinate to a (fractional) pixel coordinate, in the same way as Region::to_pixel_rect.
+    fn to_pixel(coordinate: i32, canvas_size: u32) -> f32 {
+        ((coordinate as f64 + 10_000.0) * canvas_size as f64 / 20_001.0) as f32
+    }
+
+    fn to_pixel_point(&self, point: (i32, i32)) -> (f32, f32) {
+        (Self::to_pixel(point.0, self.canvas_size.0), Self::to_pixel(point.1, self.canvas_size.1))
+    }
+
+    /// Gets the center and the half size (in pixels) of the region.
+    fn region_in_pixels(&self, region: Region) -> ((f32, f32), (f32, f32)) {
+        let (min_x, min_y) = self.to_pixel_point((region.get_min_x(), region.get_min_y()));
+        let (max_x, max_y) = self.to_pixel_point((region.get_max_x() + 1, region.get_max_y() + 1));
+        (((min_x + max_x) / 2.0, (min_y + max_y) / 2.0), ((max_x - min_x) / 2.0, (max_y - min_y) / 2.0))
+    }
+
+    /// Adds a shape with the given center (in pixels) and half size (in pixels, along the axis and perpendicular to
+    /// it). The axis must have length 1.
+    fn push_shape(&mut self, center: (f32, f32), axis: (f32, f32), half_size: (f32, f32), radius: f32, is_ellipse: bool, color: Color) {
+        let normal = (-axis.1, axis.0);
+        let extent = (half_size.0 + EDGE_MARGIN, half_size.1 + EDGE_MARGIN);
+        let color = color.to_float_array();
+        for (sign_x, sign_y) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)].iter() {
+            let local = (sign_x * extent.0, sign_y * extent.1);
+            let pixel_x = center.0 + axis.0 * local.0 + normal.0 * local.1;
+            let pixel_y = center.1 + axis.1 * local.0 + normal.1 * local.1;
+            self.vertices.extend_from_slice(&[
+                pixel_x / self.canvas_size.0 as f32 * 2.0 - 1.0, pixel_y / self.canvas_size.1 as f32 * 2.0 - 1.0,
+                local.0, local.1,
+                half_size.0, half_size.1,
+                radius, if is_ellipse { 1.0 } else { 0.0 }
+            ]);
+            self.vertices.extend_from_slice(&color);
+        }
+    }
+
+    fn push_rounded_rect(&mut self, region: Region, radius: f32, color: Color) {
+        let (center, half_size) = self.region_in_pixels(region);
+        let radius = radius.clamp(0.0, half_size.0.min(half_size.1));
+        self.push_shape(center, (1.0, 0.0), half_size, radius, false, color);
+    }
+
+    fn push_ellipse(&mut self, region: Region, color: Color) {
+        let (center, half_size) = self.region_in_pixels(region);
+        self.push_shape(center, (1.0, 0.0), half_size, 0.0, true, color);
+    }
+
+    fn push_line(&mut self, from: (i32, i32), to: (i32, i32), thickness: f32, color: Color) {
+        let from = self.to_pixel_point(from);
+        let to = self.to_pixel_point(to);
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let length = (dx * dx + dy * dy).sqrt();
+        let axis = if length > 0.0 { (dx / length, dy / length) } else { (1.0, 0.0) };
+        let center = ((from.0 + to.0) / 2.0, (from.1 + to.1) / 2.0);
+
+        // The line is drawn as a capsule, so it gets round caps
+        let radius = thickness / 2.0;
+        self.push_shape(center, axis, (length / 2.0 + radius, radius), radius, false, color);
+    }
+
+    fn num_shapes(&self) -> usize {
+        self.vertices.len() / (4 * FLOATS_PER_VERTEX)
+    }
+}
+
+/// Draws anti-aliased rectangles, rounded rectangles, ellipses and lines, using signed distance functions in the
+/// fragment shader (so no textures are needed). The shapes are collected and drawn in the order in which they were
+/// added during the next flush. Blending should be enabled, because the anti-aliased edges are partially transparent.
+pub struct ShapeRenderer {
+    program: ShaderProgram,
+    layout: VertexLayout,
+    vertex_buffer: VertexBuffer,
+    index_buffer: IndexBuffer,
+    shapes: ShapeVertices
+}
+
+impl ShapeRenderer {
+
+    /// Creates a shape renderer for a canvas of the given size (in pixels), which is needed to anti-alias the edges.
+    pub fn new(gl: &GL, canvas_width: u32, canvas_height: u32) -> Result<ShapeRenderer, RendererError> {
+        let program = ShaderProgram::compile(gl, VERTEX_SHADER, FRAGMENT_SHADER)?;
+        let location = |name| program.attribute_location(name).expect("The shape shader uses all its attributes");
+        let layout = VertexLayout::new().attribute(location("a_position"), 2).attribute(location("a_local"), 2)
+            .attribute(location("a_half_size"), 2).attribute(location("a_shape"), 2).attribute(location("a_color"), 4);
+
+        // When a step fails, the resources of the previous steps are deleted
+        let mut index_buffer = match IndexBuffer::new(gl, BufferUsage::Static) {
+            Ok(index_buffer) => index_buffer,
+            Err(error) => {
+                program.delete(gl);
+                return Err(error.into());
+            }
+        };
+        index_buffer.upload_u16(gl, &quad_indices(MAX_SHAPES_PER_DRAW));
+
+        let vertex_buffer = match VertexBuffer::new(gl, BufferUsage::Stream) {
+            Ok(vertex_buffer) => vertex_buffer,
+            Err(error) => {
+                program.delete(gl);
+                index_buffer.delete(gl);
+                return Err(error.into());
+            }
+        };
+
+        Ok(ShapeRenderer {
+            program,
+            layout,
+            vertex_buffer,
+            index_buffer,
+            shapes: ShapeVertices {
+                canvas_size: (canvas_width, canvas_height),
+                vertices: Vec::new()
+            }
+        })
+    }
+
+    /// Changes the canvas size (in pixels) that is used for the shapes that are added after this call.
+    pub fn set_canvas_size(&mut self, canvas_width: u32, canvas_height: u32) {
+        self.shapes.canvas_size = (canvas_width, canvas_height);
+    }
+
+    pub fn fill_rect(&mut self, region: Region, color: Color) {
+        self.shapes.push_rounded_rect(region, 0.0, color);
+    }
+
+    /// Fills the region with a rectangle whose corners are rounded with the given radius (in pixels).
+    pub fn fill_rounded_rect(&mut self, region: Region, radius: f32, color: Color) {
+        self.shapes.push_rounded_rect(region, radius, color);
+    }
+
+    /// Fills the ellipse that touches all edges of the region. This is a circle if the region is square in pixels.
+    pub fn fill_ellipse(&mut self, region: Region, color: Color) {
+        self.shapes.push_ellipse(region, color);
+    }
+
+    /// Draws a line with round caps between two points (in Region coordinates) with the given thickness (in pixels).
+    pub fn draw_line(&mut self, from: (i32, i32), to: (i32, i32), thickness: f32, color: Color) {
+        self.shapes.push_line(from, to, thickness, color);
+    }
+
+    /// Draws all shapes that were added since the previous flush.
+    pub fn flush(&mut self, gl: &GL) {
+        let num_shapes = self.shapes.num_shapes();
+        if num_shapes == 0 {
+            return;
+        }
+
+        self.vertex_buffer.upload(gl, &self.shapes.vertices);
+        self.program.use_program(gl);
+        self.vertex_buffer.bind(gl);
+        self.index_buffer.bind(gl);
+
+        let stride = self.layout.get_stride() as i32;
+        let mut first_shape = 0;
+        while first_shape < num_shapes {
+            let chunk_size = (num_shapes - first_shape).min(MAX_SHAPES_PER_DRAW);
+            for attribute in self.layout.get_attributes() {
+                gl.enable_vertex_attrib_array(attribute.location);
+                let offset = (first_shape * 4) as i32 * stride + attribute.offset as i32;
+                gl.vertex_attrib_pointer_with_i32(attribute.location, attribute.components as i32, GL::FLOAT, false, stride, offset);
+            }
+            gl.draw_elements_with_i32(GL::TRIANGLES, (chunk_size * 6) as i32, GL::UNSIGNED_SHORT, 0);
+            first_shape += chunk_size;
+        }
+
+        self.shapes.vertices.clear();
+    }
+
+    /// Deletes the GL resources of this renderer. This struct must not be used anymore after calling this.
+    pub fn delete(self, gl: &GL) {
+        self.program.delete(gl);
+        self.vertex_buffer.delete(gl);
+        self.index_buffer.delete(gl);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn shapes() -> ShapeVertices {
+        ShapeVertices {
+            canvas_size: (20_001, 20_001),
+            vertices: Vec::new()
+        }
+    }
+
+    #[test]
+    fn test_rect_vertices() {
+        let mut shapes = shapes();
+        shapes.push_rounded_rect(Region::new(-10_000, -10_000, -9_981, -9_971), 100.0, Color::RED);
+        assert_eq!(1, shapes.num_shapes());
+
+        let first_vertex = &shapes.vertices[0..FLOATS_PER_VERTEX];
+        // The radius is clamped to the half width
+        assert_eq!(&[-11.0, -16.0, 10.0, 15.0, 10.0, 0.0, 1.0, 0.0, 0.0, 1.0], &first_vertex[2..]);
+
+        // The margin is 1 pixel
+        let third_vertex = &shapes.vertices[2 * FLOATS_PER_VERTEX..3 * FLOATS_PER_VERTEX];
+        assert!((third_vertex[0] - (21.0 / 20_001.0 * 2.0 - 1.0)).abs() < 0.00001);
+    }
+
+    #[test]
+    fn test_line_vertices() {
+        let mut shapes = shapes();
+        shapes.push_line((0, 0), (0, 100), 4.0, Color::BLACK);
+
+        // A vertical line, so the axis points upwards and the first corner is at the bottom-right
+        let first_vertex = &shapes.vertices[0..FLOATS_PER_VERTEX];
+        let expected_x = (10_000.0 + 3.0) / 20_001.0 * 2.0 - 1.0;
+        let expected_y = (10_000.0 - 3.0) / 20_001.0 * 2.0 - 1.0;
+        assert!((first_vertex[0] - expected_x).abs() < 0.00001);
+        assert!((first_vertex[1] - expected_y).abs() < 0.00001);
+        assert_eq!(&[-53.0, -3.0, 52.0, 2.0, 2.0, 0.0], &first_vertex[2..8]);
+    }
+}
@@ -0,0 +1,121 @@
+use wasm_bindgen::{
+    JsCast,
+    JsValue
+};
+
+use web_sys::{
+    CanvasRenderingContext2d,
+    HtmlCanvasElement,
+    WebGlRenderingContext as GL
+};
+
+use crate::{
+    TextColors,
+    Texture,
+    TextureError,
+    pixel_size_to_region
+};
+
+/// The number of pixels that is kept free around the text, so that the stroke isn't cut off
+const PADDING: f64 = 2.0;
+
+/// A text that was rendered by a TextRenderer.
+pub struct RenderedText {
+    /// The texture containing the text, with the background color of the TextColors around it
+    pub texture: Texture
+}
+
+impl RenderedText {
+
+    /// Gets the size (width, height) this text should have in Region coordinates to be drawn without scaling on a
+    /// canvas of the given size (in pixels).
+    pub fn size_in_region(&self, canvas_width: u32, canvas_height: u32) -> (i32, i32) {
+        pixel_size_to_region(self.texture.get_width(), self.texture.get_height(), canvas_width, canvas_height)
+    }
+}
+
+/// Renders text to textures, using a hidden 2d canvas. This is the simplest way to draw text with WebGL: the browser
+/// takes care of fonts, shaping and anti-aliasing.
+pub struct TextRenderer {
+    canvas: HtmlCanvasElement,
+    context: CanvasRenderingContext2d
+}
+
+impl TextRenderer {
+
+    pub fn new() -> TextRenderer {
+        let (canvas, context) = create_hidden_canvas();
+        TextRenderer {
+            canvas,
+            context
+        }
+    }
+
+    /// Renders the text with the given CSS font (for instance "16px sans-serif") and colors to a new texture. The
+    /// texture is exactly large enough to contain the text and a small padding.
+    pub fn render(&self, gl: &GL, text: &str, font: &str, colors: TextColors) -> Result<RenderedText, TextureError> {
+        let size = measure_text(&self.context, text, font).map_err(TextureError::LoadFailed)?;
+
+        // Resizing the canvas resets the state of the context, so the font needs to be set again
+        self.canvas.set_width(size.width);
+        self.canvas.set_height(size.height);
+        self.context.clear_rect(0.0, 0.0, size.width as f64, size.height as f64);
+        paint_text(&self.context, text, font, colors, (0.0, 0.0), size).map_err(TextureError::LoadFailed)?;
+
+        Ok(RenderedText {
+            texture: Texture::from_canvas(gl, &self.canvas)?
+        })
+    }
+}
+
+/// Creates a canvas that is not attached to the document, together with its 2d context.
+pub(crate) fn create_hidden_canvas() -> (HtmlCanvasElement, CanvasRenderingContext2d) {
+    let document = web_sys::window().expect("There should be a window").document().expect("The window should have a document");
+    let canvas = document.create_element("canvas").expect("Should be able to create a canvas").dyn_into::<HtmlCanvasElement>()
+        .expect("A canvas element should be an HtmlCanvasElement");
+    let context = canvas.get_context("2d").expect("get_context('2d') should not fail (1)").expect("get_context('2d') should not fail (2)")
+        .dyn_into::<CanvasRenderingContext2d>().expect("The 2d context should be an instance of CanvasRenderingContext2d");
+    (canvas, context)
+}
+
+/// The size (in pixels) of a text including its padding, and the distance from its top to its baseline.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub(crate) struct TextSize {
+    pub width: u32,
+    pub height: u32,
+    pub baseline: f64
+}
+
+/// Measures the space that paint_text will need to draw the text with the given CSS font.
+pub(crate) fn measure_text(context: &CanvasRenderingContext2d, text: &str, font: &str) -> Result<TextSize, JsValue> {
+    context.set_font(font);
+    let metrics = context.measure_text(text)?;
+    let ascent = metrics.font_bounding_box_ascent();
+    Ok(TextSize {
+        width: (metrics.width() + 2.0 * PADDING).ceil().max(1.0) as u32,
+        height: (ascent + metrics.font_bounding_box_descent() + 2.0 * PADDING).ceil().max(1.0) as u32,
+        baseline: PADDING + ascent
+    })
+}
+
+/// Draws the background, fill and stroke of the text with its top-left corner at the given position (in pixels).
+pub(crate) fn paint_text(
+    context: &CanvasRenderingContext2d, text: &str, font: &str, colors: TextColors, position: (f64, f64), size: TextSize
+) -> Result<(), JsValue> {
+    let (x, y) = position;
+    context.set_font(font);
+    context.set_fill_style_str(&colors.background_color.to_string());
+    context.fill_rect(x, y, size.width as f64, size.height as f64);
+
+    context.set_fill_style_str(&colors.fill_color.to_string());
+    context.fill_text(text, x + PADDING, y + size.baseline)?;
+    context.set_stroke_style_str(&colors.stroke_color.to_string());
+    context.stroke_text(text, x + PADDING, y + size.baseline)
+}
+
+impl Default for TextRenderer {
+
+    fn default() -> Self {
+        Self::new()
+    }
+}
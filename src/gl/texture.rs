@@ -0,0 +1,326 @@
+use wasm_bindgen::{
+    JsCast,
+    JsValue
+};
+use wasm_bindgen_futures::JsFuture;
+
+use web_sys::{
+    CanvasRenderingContext2d,
+    HtmlCanvasElement,
+    HtmlImageElement,
+    WebGlRenderingContext as GL,
+    WebGlTexture
+};
+
+use crate::{
+    GlCanvas,
+    TextureSettings,
+    is_webgl2
+};
+
+/// The reasons why creating or loading a Texture can fail.
+#[derive(Debug)]
+pub enum TextureError {
+    /// createTexture returned null, which normally means that the context is lost
+    CreationFailed,
+    /// The image could not be fetched or decoded
+    LoadFailed(JsValue),
+    /// The image was loaded, but could not be uploaded to the texture (for instance because it is cross-origin)
+    UploadFailed(JsValue),
+    /// The TextureSettings can't be used for a texture of this size (see TextureSettings::validate)
+    InvalidSettings(&'static str),
+    /// The number of bytes of the pixel data doesn't match the size of the texture
+    InvalidDataLength {
+        expected: u64,
+        actual: usize
+    }
+}
+
+impl std::fmt::Display for TextureError {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TextureError::CreationFailed => write!(f, "Failed to create the texture (is the context lost?)"),
+            TextureError::LoadFailed(reason) => write!(f, "Failed to load the image: {:?}", reason),
+            TextureError::UploadFailed(reason) => write!(f, "Failed to upload the image: {:?}", reason),
+            TextureError::InvalidSettings(reason) => write!(f, "Invalid texture settings: {}", reason),
+            TextureError::InvalidDataLength { expected, actual } => write!(f, "Expected {} bytes of pixel data, but got {}", expected, actual)
+        }
+    }
+}
+
+impl std::error::Error for TextureError {}
+
+/// A 2D GL texture together with its size in pixels.
+pub struct Texture {
+    texture: WebGlTexture,
+    width: u32,
+    height: u32,
+    // The size of the image the texture was created from, which differs from the size of the texture when the image
+    // was resized to a power of 2
+    image_width: u32,
+    image_height: u32
+}
+
+impl Texture {
+
+    /// Fetches and decodes the image at the given url, and uploads it to a new texture. The texture uses linear
+    /// filtering and clamps to the edge, which works for images of any size (WebGL1 doesn't support repeating or
+    /// mipmapping textures whose size is not a power of 2).
+    /// 
+    /// Images from other origins can only be loaded if the server allows it via CORS.
+    pub async fn load(gl: &GL, url: &str) -> Result<Texture, TextureError> {
+        Self::load_with_settings(gl, url, TextureSettings::new()).await
+    }
+
+    /// Like load, but uses the given settings instead of the default ones. This fails with InvalidSettings if the
+    /// settings can't be used for the size of the image.
+    pub async fn load_with_settings(gl: &GL, url: &str, settings: TextureSettings) -> Result<Texture, TextureError> {
+        let image = web_sys::window().expect("There should be a window").document().expect("The window should have a document")
+            .create_element("img").map_err(TextureError::LoadFailed)?
+            .dyn_into::<HtmlImageElement>().expect("An img element should be an HtmlImageElement");
+        image.set_cross_origin(Some("anonymous"));
+        image.set_src(url);
+        JsFuture::from(image.decode()).await.map_err(TextureError::LoadFailed)?;
+
+        let (width, height) = (image.natural_width(), image.natural_height());
+        if settings.get_resize_to_power_of_two() && !is_webgl2(gl) && !is_power_of_two_size(width, height) {
+            let (new_width, new_height) = power_of_two_size(width, height);
+            let canvas = scale_image(&image, new_width, new_height).map_err(TextureError::UploadFailed)?;
+            let mut texture = Self::from_canvas_with_settings(gl, &canvas, settings)?;
+            texture.image_width = width;
+            texture.image_height = height;
+            return Ok(texture);
+        }
+
+        Self::create_with(gl, image.natural_width(), image.natural_height(), settings, |gl| {
+            gl.tex_image_2d_with_u32_and_u32_and_image(GL::TEXTURE_2D, 0, GL::RGBA as i32, GL::RGBA, GL::UNSIGNED_BYTE, &image)
+        })
+    }
+
+    /// Uploads the current content of the canvas (an HtmlCanvasElement or an OffscreenCanvas) to a new texture with
+    /// the default parameters.
+    pub fn from_canvas<C: GlCanvas>(gl: &GL, canvas: &C) -> Result<Texture, TextureError> {
+        Self::from_canvas_with_settings(gl, canvas, TextureSettings::new())
+    }
+
+    /// Like from_canvas, but uses the given settings instead of the default ones.
+    pub fn from_canvas_with_settings<C: GlCanvas>(gl: &GL, canvas: &C, settings: TextureSettings) -> Result<Texture, TextureError> {
+        Self::create_with(gl, canvas.get_width(), canvas.get_height(), settings, |gl| canvas.upload_to_texture(gl))
+    }
+
+    /// Uploads an image that was already loaded (and decoded) to a new texture with the default parameters.
+    pub fn from_image(gl: &GL, image: &HtmlImageElement) -> Result<Texture, TextureError> {
+        Self::create_with(gl, image.natural_width(), image.natural_height(), TextureSettings::new(), |gl| {
+            gl.tex_image_2d_with_u32_and_u32_and_image(GL::TEXTURE_2D, 0, GL::RGBA as i32, GL::RGBA, GL::UNSIGNED_BYTE, image)
+        })
+    }
+
+    /// Uploads raw pixel data to a new texture with the default parameters. The pixels must contain 4 bytes (red,
+    /// green, blue and alpha) per pixel, row by row, so their length must be exactly 4 * width * height. The first row
+    /// ends up at v = 0, unless flip_y is used (see from_rgba_with_settings).
+    pub fn from_rgba(gl: &GL, width: u32, height: u32, pixels: &[u8]) -> Result<Texture, TextureError> {
+        Self::from_rgba_with_settings(gl, width, height, pixels, TextureSettings::new())
+    }
+
+    /// Like from_rgba, but uses the given settings instead of the default ones.
+    pub fn from_rgba_with_settings(gl: &GL, width: u32, height: u32, pixels: &[u8], settings: TextureSettings) -> Result<Texture, TextureError> {
+        validate_rgba_length(width, height, pixels.len())?;
+        Self::create_with(gl, width, height, settings, |gl| {
+            // Rows of RGBA pixels are always aligned to 4 bytes, but the alignment might have been changed elsewhere
+            gl.pixel_storei(GL::UNPACK_ALIGNMENT, 1);
+            let result = gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+                GL::TEXTURE_2D, 0, GL::RGBA as i32, width as i32, height as i32, 0, GL::RGBA, GL::UNSIGNED_BYTE, Some(pixels)
+            );
+            gl.pixel_storei(GL::UNPACK_ALIGNMENT, 4);
+            result
+        })
+    }
+
+    /// Validates the settings, creates a texture, and uses the upload function to fill it.
+    fn create_with(
+        gl: &GL, width: u32, height: u32, settings: TextureSettings, upload: impl FnOnce(&GL) -> Result<(), JsValue>
+    ) -> Result<Texture, TextureError> {
+        settings.validate(width, height, is_webgl2(gl)).map_err(TextureError::InvalidSettings)?;
+
+        let texture = gl.create_texture().ok_or(TextureError::CreationFailed)?;
+        gl.bind_texture(GL::TEXTURE_2D, Some(&texture));
+        settings.before_upload(gl);
+        let result = upload(gl);
+        settings.after_upload(gl);
+        if let Err(reason) = result {
+            gl.delete_texture(Some(&texture));
+            return Err(TextureError::UploadFailed(reason));
+        }
+
+        settings.apply_parameters(gl);
+        if settings.get_min_filter().uses_mipmaps() {
+            gl.generate_mipmap(GL::TEXTURE_2D);
+        }
+
+        Ok(Texture {
+            texture,
+            width,
+            height,
+            image_width: width,
+            image_height: height
+        })
+    }
+
+    /// Generates the mipmaps of this texture, and makes it use trilinear filtering (LinearMipmapLinear) when it is drawn
+    /// smaller than its size. WebGL1 only supports mipmaps for textures whose width and height are powers of 2, so this
+    /// returns InvalidSettings for other textures on WebGL1 (instead of leaving a texture that is silently drawn black).
+    /// Use TextureSettings::resize_to_power_of_two when loading such textures.
+    ///
+    /// This leaves the texture bound to TEXTURE_2D.
+    pub fn generate_mipmaps(&self, gl: &GL) -> Result<(), TextureError> {
+        if !is_webgl2(gl) && !is_power_of_two_size(self.width, self.height) {
+            return Err(TextureError::InvalidSettings("WebGL1 doesn't support mipmaps for textures whose size is not a power of 2"));
+        }
+        gl.bind_texture(GL::TEXTURE_2D, Some(&self.texture));
+        gl.generate_mipmap(GL::TEXTURE_2D);
+        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MIN_FILTER, GL::LINEAR_MIPMAP_LINEAR as i32);
+        Ok(())
+    }
+
+    /// Changes the filtering and wrapping parameters of this texture. The unpack settings (premultiply_alpha and
+    /// flip_y) only affect uploads, so they are ignored. This leaves the texture bound to TEXTURE_2D.
+    pub fn apply_settings(&self, gl: &GL, settings: TextureSettings) -> Result<(), TextureError> {
+        settings.validate(self.width, self.height, is_webgl2(gl)).map_err(TextureError::InvalidSettings)?;
+        gl.bind_texture(GL::TEXTURE_2D, Some(&self.texture));
+        if settings.get_min_filter().uses_mipmaps() {
+            gl.generate_mipmap(GL::TEXTURE_2D);
+        }
+        settings.apply_parameters(gl);
+        Ok(())
+    }
+
+    /// Creates a texture of the given size with undefined content, for instance to render to.
+    pub(crate) fn create_empty(gl: &GL, width: u32, height: u32) -> Result<Texture, TextureError> {
+        let texture = gl.create_texture().ok_or(TextureError::CreationFailed)?;
+        let mut result = Texture {
+            texture,
+            width: 0,
+            height: 0,
+            image_width: 0,
+            image_height: 0
+        };
+        if let Err(error) = result.resize_storage(gl, width, height) {
+            result.delete(gl);
+            return Err(error);
+        }
+        TextureSettings::new().apply_parameters(gl);
+        Ok(result)
+    }
+
+    /// Reallocates the storage of this texture with the given size. This discards the content of the texture, and
+    /// leaves the texture bound to TEXTURE_2D.
+    pub(crate) fn resize_storage(&mut self, gl: &GL, width: u32, height: u32) -> Result<(), TextureError> {
+        gl.bind_texture(GL::TEXTURE_2D, Some(&self.texture));
+        gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+            GL::TEXTURE_2D, 0, GL::RGBA as i32, width as i32, height as i32, 0, GL::RGBA, GL::UNSIGNED_BYTE, None
+        ).map_err(TextureError::UploadFailed)?;
+        self.width = width;
+        self.height = height;
+        self.image_width = width;
+        self.image_height = height;
+        Ok(())
+    }
+
+    /// Binds this texture to the given texture unit (0 for TEXTURE0, 1 for TEXTURE1...).
+    pub fn bind(&self, gl: &GL, unit: u32) {
+        gl.active_texture(GL::TEXTURE0 + unit);
+        gl.bind_texture(GL::TEXTURE_2D, Some(&self.texture));
+    }
+
+    pub fn get_texture(&self) -> &WebGlTexture {
+        &self.texture
+    }
+
+    /// Gets the width of this texture, in pixels. When the image was resized to a power of 2 (see
+    /// TextureSettings::resize_to_power_of_two), this is the resized width rather than the width of the image.
+    pub fn get_width(&self) -> u32 {
+        self.width
+    }
+
+    /// Gets the height of this texture, in pixels. Like get_width, this is the resized height if the image was resized.
+    pub fn get_height(&self) -> u32 {
+        self.height
+    }
+
+    /// Gets the original width of the image this texture was created from, in pixels. This only differs from
+    /// get_width when the image was resized to a power of 2.
+    pub fn get_image_width(&self) -> u32 {
+        self.image_width
+    }
+
+    /// Gets the original height of the image this texture was created from, in pixels.
+    pub fn get_image_height(&self) -> u32 {
+        self.image_height
+    }
+
+    /// Deletes the texture. This struct must not be used anymore after calling this.
+    pub fn delete(self, gl: &GL) {
+        gl.delete_texture(Some(&self.texture));
+    }
+}
+
+fn validate_rgba_length(width: u32, height: u32, length: usize) -> Result<(), TextureError> {
+    let expected = (width as u64 * height as u64).saturating_mul(4);
+    if expected != length as u64 {
+        return Err(TextureError::InvalidDataLength {
+            expected,
+            actual: length
+        });
+    }
+    Ok(())
+}
+
+fn is_power_of_two_size(width: u32, height: u32) -> bool {
+    width.is_power_of_two() && height.is_power_of_two()
+}
+
+/// Gets the smallest size whose width and height are powers of 2 and at least as large as the given size.
+fn power_of_two_size(width: u32, height: u32) -> (u32, u32) {
+    (width.max(1).next_power_of_two(), height.max(1).next_power_of_two())
+}
+
+/// Draws the image onto a new canvas of the given size, stretching it to fill the entire canvas.
+fn scale_image(image: &HtmlImageElement, width: u32, height: u32) -> Result<HtmlCanvasElement, JsValue> {
+    let document = web_sys::window().expect("There should be a window").document().expect("The window should have a document");
+    let canvas = document.create_element("canvas")?.dyn_into::<HtmlCanvasElement>()
+        .expect("A canvas element should be an HtmlCanvasElement");
+    canvas.set_width(width);
+    canvas.set_height(height);
+
+    let context = canvas.get_context("2d")?.expect("get_context('2d') should not fail (2)")
+        .dyn_into::<CanvasRenderingContext2d>().expect("The 2d context should be an instance of CanvasRenderingContext2d");
+    context.draw_image_with_html_image_element_and_dw_and_dh(image, 0.0, 0.0, width as f64, height as f64)?;
+    Ok(canvas)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_power_of_two_size() {
+        assert_eq!((256, 128), power_of_two_size(256, 128));
+        assert_eq!((512, 128), power_of_two_size(300, 100));
+        assert_eq!((1, 1), power_of_two_size(0, 1));
+        assert!(is_power_of_two_size(64, 1));
+        assert!(!is_power_of_two_size(64, 3));
+    }
+
+    #[test]
+    fn test_validate_rgba_length() {
+        assert!(validate_rgba_length(3, 5, 60).is_ok());
+        assert!(validate_rgba_length(0, 5, 0).is_ok());
+        match validate_rgba_length(3, 5, 45) {
+            Err(TextureError::InvalidDataLength { expected, actual }) => assert_eq!((60, 45), (expected, actual)),
+            _ => panic!("Expected InvalidDataLength")
+        }
+        assert!(validate_rgba_length(u32::MAX, u32::MAX, 0).is_err());
+    }
+}
@@ -0,0 +1,271 @@
+use wasm_bindgen::JsCast;
+
+use web_sys::{
+    ExtTextureFilterAnisotropic,
+    WebGl2RenderingContext,
+    WebGlRenderingContext as GL
+};
+
+use crate::get_extension;
+
+/// The ways in which texels can be sampled from a texture. The mipmap variants can only be used as minification filter,
+/// and require the texture to have mipmaps.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum TextureFilter {
+
+    Nearest,
+    Linear,
+    NearestMipmapNearest,
+    LinearMipmapNearest,
+    NearestMipmapLinear,
+    LinearMipmapLinear
+}
+
+impl TextureFilter {
+
+    pub fn uses_mipmaps(self) -> bool {
+        !matches!(self, TextureFilter::Nearest | TextureFilter::Linear)
+    }
+
+    pub fn to_gl(self) -> u32 {
+        match self {
+            TextureFilter::Nearest => GL::NEAREST,
+            TextureFilter::Linear => GL::LINEAR,
+            TextureFilter::NearestMipmapNearest => GL::NEAREST_MIPMAP_NEAREST,
+            TextureFilter::LinearMipmapNearest => GL::LINEAR_MIPMAP_NEAREST,
+            TextureFilter::NearestMipmapLinear => GL::NEAREST_MIPMAP_LINEAR,
+            TextureFilter::LinearMipmapLinear => GL::LINEAR_MIPMAP_LINEAR
+        }
+    }
+}
+
+/// What happens when a texture is sampled outside the [0, 1] range.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum TextureWrap {
+
+    ClampToEdge,
+    Repeat,
+    MirroredRepeat
+}
+
+impl TextureWrap {
+
+    pub fn to_gl(self) -> u32 {
+        match self {
+            TextureWrap::ClampToEdge => GL::CLAMP_TO_EDGE,
+            TextureWrap::Repeat => GL::REPEAT,
+            TextureWrap::MirroredRepeat => GL::MIRRORED_REPEAT
+        }
+    }
+}
+
+/// The parameters that are used when a texture is created: filtering, wrapping and how the image is unpacked. The
+/// default settings (TextureSettings::new) use linear filtering without mipmaps and clamp to the edge, which works for
+/// textures of any size.
+///
+/// All methods are const, so the settings can also be defined as constants.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct TextureSettings {
+    min_filter: TextureFilter,
+    mag_filter: TextureFilter,
+    wrap_s: TextureWrap,
+    wrap_t: TextureWrap,
+    anisotropy: Option<f32>,
+    premultiply_alpha: bool,
+    flip_y: bool,
+    resize_to_power_of_two: bool
+}
+
+impl TextureSettings {
+
+    pub const fn new() -> TextureSettings {
+        TextureSettings {
+            min_filter: TextureFilter::Linear,
+            mag_filter: TextureFilter::Linear,
+            wrap_s: TextureWrap::ClampToEdge,
+            wrap_t: TextureWrap::ClampToEdge,
+            anisotropy: None,
+            premultiply_alpha: false,
+            flip_y: false,
+            resize_to_power_of_two: false
+        }
+    }
+
+    /// The filter that is used when the texture is drawn smaller than its size. If this is a mipmap filter, the
+    /// mipmaps are generated when the texture is created.
+    pub const fn min_filter(self, min_filter: TextureFilter) -> TextureSettings {
+        TextureSettings {
+            min_filter,
+            ..self
+        }
+    }
+
+    /// The filter that is used when the texture is drawn larger than its size. This must not be a mipmap filter.
+    pub const fn mag_filter(self, mag_filter: TextureFilter) -> TextureSettings {
+        TextureSettings {
+            mag_filter,
+            ..self
+        }
+    }
+
+    /// Uses the given filter for both minification and magnification, for instance Nearest for pixel art.
+    pub const fn filter(self, filter: TextureFilter) -> TextureSettings {
+        TextureSettings {
+            min_filter: filter,
+            mag_filter: filter,
+            ..self
+        }
+    }
+
+    /// Uses the given wrap mode in both directions.
+    pub const fn wrap(self, wrap: TextureWrap) -> TextureSettings {
+        TextureSettings {
+            wrap_s: wrap,
+            wrap_t: wrap,
+            ..self
+        }
+    }
+
+    pub const fn wrap_s(self, wrap_s: TextureWrap) -> TextureSettings {
+        TextureSettings {
+            wrap_s,
+            ..self
+        }
+    }
+
+    pub const fn wrap_t(self, wrap_t: TextureWrap) -> TextureSettings {
+        TextureSettings {
+            wrap_t,
+            ..self
+        }
+    }
+
+    /// The level of anisotropic filtering, which keeps textures sharp when they are drawn at an angle. This is
+    /// limited to the maximum supported level, and ignored if the EXT_texture_filter_anisotropic extension is not
+    /// available.
+    pub const fn anisotropy(self, anisotropy: f32) -> TextureSettings {
+        TextureSettings {
+            anisotropy: Some(anisotropy),
+            ..self
+        }
+    }
+
+    /// Whether the colors of the image should be multiplied with their alpha during the upload.
+    pub const fn premultiply_alpha(self, premultiply_alpha: bool) -> TextureSettings {
+        TextureSettings {
+            premultiply_alpha,
+            ..self
+        }
+    }
+
+    /// Whether the rows of the image should be flipped during the upload, so that the first row ends up at v = 0.
+    pub const fn flip_y(self, flip_y: bool) -> TextureSettings {
+        TextureSettings {
+            flip_y,
+            ..self
+        }
+    }
+
+    /// Whether images whose size is not a power of 2 should be scaled up to the next power of 2 before they are
+    /// uploaded to a WebGL1 context, so that they can use mipmaps and repeat. This only affects
+    /// Texture::load_with_settings, and is ignored for WebGL2 contexts (which don't need it). The size of the original
+    /// image is still available via Texture::get_image_width and get_image_height.
+    pub const fn resize_to_power_of_two(self, resize_to_power_of_two: bool) -> TextureSettings {
+        TextureSettings {
+            resize_to_power_of_two,
+            ..self
+        }
+    }
+
+    pub fn get_min_filter(&self) -> TextureFilter {
+        self.min_filter
+    }
+
+    pub fn get_mag_filter(&self) -> TextureFilter {
+        self.mag_filter
+    }
+
+    pub fn get_resize_to_power_of_two(&self) -> bool {
+        self.resize_to_power_of_two
+    }
+
+    /// Checks if these settings can be used for a texture of the given size. WebGL1 doesn't support mipmaps or
+    /// wrap modes other than ClampToEdge for textures whose width or height is not a power of 2 (such textures are
+    /// silently sampled as black), so this returns an error in that case.
+    pub fn validate(&self, width: u32, height: u32, webgl2: bool) -> Result<(), &'static str> {
+        if self.mag_filter.uses_mipmaps() {
+            return Err("The magnification filter can't be a mipmap filter");
+        }
+        let is_power_of_two = width.is_power_of_two() && height.is_power_of_two();
+        if !(webgl2 || is_power_of_two) {
+            if self.min_filter.uses_mipmaps() {
+                return Err("WebGL1 doesn't support mipmaps for textures whose size is not a power of 2");
+            }
+            if self.wrap_s != TextureWrap::ClampToEdge || self.wrap_t != TextureWrap::ClampToEdge {
+                return Err("WebGL1 only supports ClampToEdge for textures whose size is not a power of 2");
+            }
+        }
+        Ok(())
+    }
+
+    /// Sets the pixel store parameters that affect the next upload.
+    pub(crate) fn before_upload(&self, gl: &GL) {
+        gl.pixel_storei(GL::UNPACK_PREMULTIPLY_ALPHA_WEBGL, self.premultiply_alpha as i32);
+        gl.pixel_storei(GL::UNPACK_FLIP_Y_WEBGL, self.flip_y as i32);
+    }
+
+    /// Restores the pixel store parameters, so that they don't affect other uploads.
+    pub(crate) fn after_upload(&self, gl: &GL) {
+        gl.pixel_storei(GL::UNPACK_PREMULTIPLY_ALPHA_WEBGL, 0);
+        gl.pixel_storei(GL::UNPACK_FLIP_Y_WEBGL, 0);
+    }
+
+    /// Sets the parameters of the texture that is currently bound to TEXTURE_2D.
+    pub(crate) fn apply_parameters(&self, gl: &GL) {
+        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MIN_FILTER, self.min_filter.to_gl() as i32);
+        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MAG_FILTER, self.mag_filter.to_gl() as i32);
+        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_S, self.wrap_s.to_gl() as i32);
+        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_T, self.wrap_t.to_gl() as i32);
+
+        if let Some(anisotropy) = self.anisotropy {
+            if get_extension::<ExtTextureFilterAnisotropic>(gl, "EXT_texture_filter_anisotropic").is_some() {
+                let max = gl.get_parameter(ExtTextureFilterAnisotropic::MAX_TEXTURE_MAX_ANISOTROPY_EXT).ok()
+                    .and_then(|max| max.as_f64()).unwrap_or(1.0) as f32;
+                gl.tex_parameterf(GL::TEXTURE_2D, ExtTextureFilterAnisotropic::TEXTURE_MAX_ANISOTROPY_EXT, anisotropy.clamp(1.0, max.max(1.0)));
+            }
+        }
+    }
+}
+
+impl Default for TextureSettings {
+
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Checks whether the context is actually a WebGL2 context (GlContext::gl returns WebGL2 contexts as WebGL1 context).
+pub(crate) fn is_webgl2(gl: &GL) -> bool {
+    gl.is_instance_of::<WebGl2RenderingContext>()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        let mipmaps = TextureSettings::new().min_filter(TextureFilter::LinearMipmapLinear);
+        assert_eq!(Ok(()), mipmaps.validate(256, 128, false));
+        assert!(mipmaps.validate(300, 128, false).is_err());
+        assert_eq!(Ok(()), mipmaps.validate(300, 128, true));
+
+        let repeat = TextureSettings::new().wrap(TextureWrap::Repeat);
+        assert!(repeat.validate(100, 100, false).is_err());
+        assert_eq!(Ok(()), repeat.validate(64, 64, false));
+
+        assert_eq!(Ok(()), TextureSettings::new().validate(100, 100, false));
+        assert!(TextureSettings::new().filter(TextureFilter::NearestMipmapNearest).validate(64, 64, true).is_err());
+    }
+}
@@ -0,0 +1,211 @@
+use std::marker::PhantomData;
+
+use web_sys::{
+    WebGlRenderingContext,
+    WebGlUniformLocation
+};
+
+use crate::{
+    Color,
+    Region,
+    ShaderProgram,
+    Transform2D
+};
+
+/// Extension methods to set uniforms directly from the types of this crate. The locations are optional so that the
+/// result of ShaderProgram::uniform_location can be passed directly (setting a uniform at location None does nothing).
+pub trait Uniforms {
+
+    /// Sets a vec4 uniform to the red, green, blue and alpha components of the color (in the range [0.0, 1.0]).
+    fn set_color(&self, location: Option<&WebGlUniformLocation>, color: Color);
+
+    /// Sets a vec4 uniform to (min_x, min_y, max_x, max_y) of the region, in GL coordinates.
+    fn set_region(&self, location: Option<&WebGlUniformLocation>, region: Region);
+
+    /// Sets a mat3 uniform to the matrix of the transformation.
+    fn set_mat3(&self, location: Option<&WebGlUniformLocation>, transform: &Transform2D);
+}
+
+impl Uniforms for WebGlRenderingContext {
+
+    fn set_color(&self, location: Option<&WebGlUniformLocation>, color: Color) {
+        self.uniform4fv_with_f32_array(location, &color.to_float_array());
+    }
+
+    fn set_region(&self, location: Option<&WebGlUniformLocation>, region: Region) {
+        self.uniform4f(location, region.get_float_min_x(), region.get_float_min_y(), region.get_float_max_x(), region.get_float_max_y());
+    }
+
+    fn set_mat3(&self, location: Option<&WebGlUniformLocation>, transform: &Transform2D) {
+        self.uniform_matrix3fv_with_f32_array(location, false, &transform.to_mat3());
+    }
+}
+
+/// A value that can be uploaded to a uniform of the matching GLSL type.
+pub trait UniformValue {
+
+    fn upload(&self, gl: &WebGlRenderingContext, location: Option<&WebGlUniformLocation>);
+}
+
+impl UniformValue for f32 {
+
+    fn upload(&self, gl: &WebGlRenderingContext, location: Option<&WebGlUniformLocation>) {
+        gl.uniform1f(location, *self);
+    }
+}
+
+/// For int and sampler2D uniforms
+impl UniformValue for i32 {
+
+    fn upload(&self, gl: &WebGlRenderingContext, location: Option<&WebGlUniformLocation>) {
+        gl.uniform1i(location, *self);
+    }
+}
+
+impl UniformValue for bool {
+
+    fn upload(&self, gl: &WebGlRenderingContext, location: Option<&WebGlUniformLocation>) {
+        gl.uniform1i(location, *self as i32);
+    }
+}
+
+impl UniformValue for [f32; 2] {
+
+    fn upload(&self, gl: &WebGlRenderingContext, location: Option<&WebGlUniformLocation>) {
+        gl.uniform2fv_with_f32_array(location, self);
+    }
+}
+
+impl UniformValue for [f32; 3] {
+
+    fn upload(&self, gl: &WebGlRenderingContext, location: Option<&WebGlUniformLocation>) {
+        gl.uniform3fv_with_f32_array(location, self);
+    }
+}
+
+impl UniformValue for [f32; 4] {
+
+    fn upload(&self, gl: &WebGlRenderingContext, location: Option<&WebGlUniformLocation>) {
+        gl.uniform4fv_with_f32_array(location, self);
+    }
+}
+
+/// Uploads a vec4, see Uniforms::set_color
+impl UniformValue for Color {
+
+    fn upload(&self, gl: &WebGlRenderingContext, location: Option<&WebGlUniformLocation>) {
+        gl.set_color(location, *self);
+    }
+}
+
+/// Uploads a vec4, see Uniforms::set_region
+impl UniformValue for Region {
+
+    fn upload(&self, gl: &WebGlRenderingContext, location: Option<&WebGlUniformLocation>) {
+        gl.set_region(location, *self);
+    }
+}
+
+/// Uploads a mat3, see Uniforms::set_mat3
+impl UniformValue for Transform2D {
+
+    fn upload(&self, gl: &WebGlRenderingContext, location: Option<&WebGlUniformLocation>) {
+        gl.set_mat3(location, self);
+    }
+}
+
+/// A struct whose fields are uploaded to the uniforms with the given names, so that all uniforms of a shader can be
+/// set with a single call to UniformLocations::upload. This is normally implemented with the uniform_block macro, for
+/// instance `uniform_block!(BorderUniforms { color => "u_color", width => "u_width" });`
+///
+/// A manual implementation must upload the fields in the same order as their UNIFORM_NAMES.
+pub trait UniformBlock {
+
+    /// The names of the uniforms, in the order in which upload_fields uploads them
+    const UNIFORM_NAMES: &'static [&'static str];
+
+    /// Uploads every field with the uploader, in the order of UNIFORM_NAMES.
+    fn upload_fields(&self, uploader: &mut UniformUploader);
+}
+
+/// Uploads the fields of a UniformBlock to consecutive uniform locations.
+pub struct UniformUploader<'a> {
+    gl: &'a WebGlRenderingContext,
+    locations: std::slice::Iter<'a, Option<WebGlUniformLocation>>
+}
+
+impl UniformUploader<'_> {
+
+    /// Uploads the value to the next uniform. This does nothing if the shader doesn't use that uniform.
+    pub fn upload<V: UniformValue + ?Sized>(&mut self, value: &V) {
+        let location = self.locations.next().expect("A UniformBlock must not upload more fields than it has names");
+        value.upload(self.gl, location.as_ref());
+    }
+}
+
+/// The uniform locations of a UniformBlock in a shader program, looked up once so that uploading doesn't need to look
+/// up any names.
+pub struct UniformLocations<T: UniformBlock> {
+    locations: Vec<Option<WebGlUniformLocation>>,
+    block: PhantomData<T>
+}
+
+impl<T: UniformBlock> UniformLocations<T> {
+
+    /// Looks up the locations of the uniforms of T in the program. Uniforms that the program doesn't use (for instance
+    /// because the shader compiler optimized them away) are skipped during uploads.
+    pub fn new(program: &ShaderProgram) -> UniformLocations<T> {
+        UniformLocations {
+            locations: T::UNIFORM_NAMES.iter().map(|name| program.uniform_location(name).cloned()).collect(),
+            block: PhantomData
+        }
+    }
+
+    /// Uploads all fields of the block. The program must be in use.
+    pub fn upload(&self, gl: &WebGlRenderingContext, block: &T) {
+        block.upload_fields(&mut UniformUploader {
+            gl,
+            locations: self.locations.iter()
+        });
+    }
+}
+
+/// Implements UniformBlock for a struct, by mapping each of its fields to the name of a uniform. See UniformBlock for
+/// an example.
+#[macro_export]
+macro_rules! uniform_block {
+    ($block:ty { $($field:ident => $name:expr),* $(,)? }) => {
+        impl $crate::UniformBlock for $block {
+
+            const UNIFORM_NAMES: &'static [&'static str] = &[$($name),*];
+
+            fn upload_fields(&self, uploader: &mut $crate::UniformUploader) {
+                $(uploader.upload(&self.$field);)*
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[allow(dead_code)]
+    struct TestUniforms {
+        color: Color,
+        transform: Transform2D,
+        enabled: bool
+    }
+
+    uniform_block!(TestUniforms {
+        color => "u_color",
+        transform => "u_transform",
+        enabled => "u_enabled",
+    });
+
+    #[test]
+    fn test_uniform_block_macro() {
+        assert_eq!(&["u_color", "u_transform", "u_enabled"], TestUniforms::UNIFORM_NAMES);
+    }
+}
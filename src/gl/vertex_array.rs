@@ -0,0 +1,112 @@
+use web_sys::{
+    OesVertexArrayObject,
+    WebGlBuffer,
+    WebGlRenderingContext as GL,
+    WebGlVertexArrayObject
+};
+
+use crate::{
+    BufferError,
+    GlContext,
+    IndexBuffer,
+    VertexBuffer,
+    VertexLayout,
+    get_extension
+};
+
+enum VertexArrayKind {
+    /// A WebGL2 vertex array object
+    Native(WebGlVertexArrayObject),
+    /// A vertex array object created with the OES_vertex_array_object extension of WebGL1
+    Extension(OesVertexArrayObject, WebGlVertexArrayObject),
+    /// The extension is not available, so all attributes need to be set up again during each bind
+    Emulated
+}
+
+/// Remembers which buffers and attributes should be used for drawing, so that all of them can be bound with a single
+/// bind call. This uses vertex array objects when they are available (always on WebGL2, and on nearly all WebGL1
+/// implementations via the OES_vertex_array_object extension), and sets up the attributes manually otherwise.
+pub struct VertexArray {
+    kind: VertexArrayKind,
+    layout: VertexLayout,
+    vertex_buffer: WebGlBuffer,
+    index_buffer: Option<WebGlBuffer>
+}
+
+impl VertexArray {
+
+    /// Creates a vertex array that uses the given layout for the given vertex buffer, and the given index buffer (if
+    /// any). Note that the buffers must not be deleted while this vertex array is still used.
+    pub fn new(context: &GlContext, layout: VertexLayout, vertex_buffer: &VertexBuffer, index_buffer: Option<&IndexBuffer>) -> Result<VertexArray, BufferError> {
+        let kind = match context.gl2() {
+            Some(gl2) => VertexArrayKind::Native(gl2.create_vertex_array().ok_or(BufferError::CreationFailed)?),
+            None => match get_extension::<OesVertexArrayObject>(context.gl(), "OES_vertex_array_object") {
+                Some(extension) => {
+                    let vao = extension.create_vertex_array_oes().ok_or(BufferError::CreationFailed)?;
+                    VertexArrayKind::Extension(extension, vao)
+                }, None => VertexArrayKind::Emulated
+            }
+        };
+
+        let vertex_array = VertexArray {
+            kind,
+            layout,
+            vertex_buffer: vertex_buffer.get_buffer().clone(),
+            index_buffer: index_buffer.map(|index_buffer| index_buffer.get_buffer().clone())
+        };
+
+        // Record the state in the vertex array object
+        if !vertex_array.is_emulated() {
+            vertex_array.bind(context);
+            vertex_array.set_up(context.gl());
+            vertex_array.unbind(context);
+        }
+
+        Ok(vertex_array)
+    }
+
+    /// Checks if this vertex array needs to set up all attributes during each bind, because vertex array objects are
+    /// not supported.
+    pub fn is_emulated(&self) -> bool {
+        matches!(self.kind, VertexArrayKind::Emulated)
+    }
+
+    /// Binds the buffers and attributes of this vertex array, so that it can be drawn.
+    pub fn bind(&self, context: &GlContext) {
+        match &self.kind {
+            VertexArrayKind::Native(vao) => context.gl2().expect("Native vertex arrays need WebGL2").bind_vertex_array(Some(vao)),
+            VertexArrayKind::Extension(extension, vao) => extension.bind_vertex_array_oes(Some(vao)),
+            VertexArrayKind::Emulated => self.set_up(context.gl())
+        }
+    }
+
+    /// Unbinds this vertex array, so that subsequent buffer and attribute changes don't affect it.
+    pub fn unbind(&self, context: &GlContext) {
+        match &self.kind {
+            VertexArrayKind::Native(_) => context.gl2().expect("Native vertex arrays need WebGL2").bind_vertex_array(None),
+            VertexArrayKind::Extension(extension, _) => extension.bind_vertex_array_oes(None),
+            VertexArrayKind::Emulated => {
+                for attribute in self.layout.get_attributes() {
+                    context.gl().disable_vertex_attrib_array(attribute.location);
+                }
+            }
+        }
+    }
+
+    /// Deletes the vertex array object (but not the buffers). This struct must not be used anymore after calling this.
+    pub fn delete(self, context: &GlContext) {
+        match &self.kind {
+            VertexArrayKind::Native(vao) => context.gl2().expect("Native vertex arrays need WebGL2").delete_vertex_array(Some(vao)),
+            VertexArrayKind::Extension(extension, vao) => extension.delete_vertex_array_oes(Some(vao)),
+            VertexArrayKind::Emulated => {}
+        }
+    }
+
+    fn set_up(&self, gl: &GL) {
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(&self.vertex_buffer));
+        self.layout.set_pointers(gl);
+        if let Some(index_buffer) = &self.index_buffer {
+            gl.bind_buffer(GL::ELEMENT_ARRAY_BUFFER, Some(index_buffer));
+        }
+    }
+}
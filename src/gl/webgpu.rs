@@ -0,0 +1,352 @@
+use js_sys::{
+    Array,
+    Float32Array,
+    Function,
+    Object,
+    Reflect
+};
+
+use wasm_bindgen::{
+    JsCast,
+    JsValue
+};
+use wasm_bindgen_futures::JsFuture;
+
+use web_sys::HtmlCanvasElement;
+
+use crate::{
+    Color,
+    Region
+};
+
+// The WebGPU bindings of web-sys are still unstable (they need --cfg=web_sys_unstable_apis), so this module talks to
+// the WebGPU API through js_sys::Reflect instead. That keeps the webgpu feature usable on stable builds.
+
+/// GPUBufferUsage.COPY_DST | GPUBufferUsage.VERTEX
+const VERTEX_BUFFER_USAGE: u32 = 0x0008 | 0x0020;
+
+/// The reasons why get_gpu (or one of the WebGPU helpers) can fail.
+#[derive(Debug)]
+pub enum WebGpuError {
+    /// The browser doesn't support WebGPU (navigator.gpu is undefined)
+    Unsupported,
+    /// The browser supports WebGPU, but there is no suitable adapter (GPU)
+    NoAdapter,
+    /// A WebGPU call threw an exception or returned a rejected promise
+    CallFailed(JsValue)
+}
+
+impl std::fmt::Display for WebGpuError {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            WebGpuError::Unsupported => write!(f, "This browser doesn't support WebGPU"),
+            WebGpuError::NoAdapter => write!(f, "No suitable WebGPU adapter is available"),
+            WebGpuError::CallFailed(reason) => write!(f, "A WebGPU call failed: {:?}", reason)
+        }
+    }
+}
+
+impl std::error::Error for WebGpuError {}
+
+impl From<JsValue> for WebGpuError {
+
+    fn from(reason: JsValue) -> Self {
+        WebGpuError::CallFailed(reason)
+    }
+}
+
+fn get(target: &JsValue, property: &str) -> Result<JsValue, JsValue> {
+    Reflect::get(target, &JsValue::from_str(property))
+}
+
+/// Calls the method with the given name on the target
+fn call(target: &JsValue, method: &str, arguments: &[JsValue]) -> Result<JsValue, JsValue> {
+    let function: Function = get(target, method)?.dyn_into()?;
+    function.apply(target, &arguments.iter().collect::<Array>())
+}
+
+async fn call_async(target: &JsValue, method: &str, arguments: &[JsValue]) -> Result<JsValue, JsValue> {
+    JsFuture::from(call(target, method, arguments)?.dyn_into::<js_sys::Promise>()?).await
+}
+
+/// Creates a JavaScript object with the given properties
+fn object(properties: &[(&str, JsValue)]) -> JsValue {
+    let result = Object::new();
+    for (name, value) in properties {
+        Reflect::set(&result, &JsValue::from_str(name), value).expect("Should be able to set properties of a new object");
+    }
+    result.into()
+}
+
+fn color_dict(color: Color) -> JsValue {
+    object(&[
+        ("r", JsValue::from_f64(color.get_red_float() as f64)),
+        ("g", JsValue::from_f64(color.get_green_float() as f64)),
+        ("b", JsValue::from_f64(color.get_blue_float() as f64)),
+        ("a", JsValue::from_f64(color.get_alpha_float() as f64))
+    ])
+}
+
+/// A canvas that is configured for WebGPU rendering, together with the device that renders to it.
+pub struct GpuCanvas {
+    context: JsValue,
+    device: JsValue,
+    format: JsValue
+}
+
+/// Requests a WebGPU adapter and device, and configures the canvas to be rendered to with them (using the preferred
+/// canvas format of the browser and premultiplied alpha). This is experimental: WebGL (get_gl) remains the default.
+pub async fn get_gpu(canvas: &HtmlCanvasElement) -> Result<GpuCanvas, WebGpuError> {
+    let navigator = web_sys::window().expect("There should be a window").navigator();
+    let gpu = get(&navigator, "gpu")?;
+    if gpu.is_undefined() || gpu.is_null() {
+        return Err(WebGpuError::Unsupported);
+    }
+
+    let adapter = call_async(&gpu, "requestAdapter", &[]).await?;
+    if adapter.is_null() {
+        return Err(WebGpuError::NoAdapter);
+    }
+    let device = call_async(&adapter, "requestDevice", &[]).await?;
+
+    let context: JsValue = canvas.get_context("webgpu")?.ok_or(WebGpuError::Unsupported)?.into();
+    let format = call(&gpu, "getPreferredCanvasFormat", &[])?;
+    call(&context, "configure", &[object(&[
+        ("device", device.clone()),
+        ("format", format.clone()),
+        ("alphaMode", JsValue::from_str("premultiplied"))
+    ])])?;
+
+    Ok(GpuCanvas {
+        context,
+        device,
+        format
+    })
+}
+
+impl GpuCanvas {
+
+    /// Gets the GPUCanvasContext
+    pub fn get_context(&self) -> &JsValue {
+        &self.context
+    }
+
+    /// Gets the GPUDevice
+    pub fn get_device(&self) -> &JsValue {
+        &self.device
+    }
+
+    /// Gets the GPUTextureFormat of the canvas
+    pub fn get_format(&self) -> &JsValue {
+        &self.format
+    }
+
+    /// Clears the entire canvas to the given color.
+    pub fn clear(&self, color: Color) -> Result<(), WebGpuError> {
+        let encoder = call(&self.device, "createCommandEncoder", &[])?;
+        let pass = self.begin_render_pass(&encoder, Some(color))?;
+        call(&pass, "end", &[])?;
+        self.submit(&encoder)
+    }
+
+    /// Starts a render pass on the current texture of the canvas, which is cleared to the given color (if any).
+    fn begin_render_pass(&self, encoder: &JsValue, clear_color: Option<Color>) -> Result<JsValue, WebGpuError> {
+        let texture = call(&self.context, "getCurrentTexture", &[])?;
+        let view = call(&texture, "createView", &[])?;
+        let attachment = match clear_color {
+            Some(color) => object(&[
+                ("view", view),
+                ("clearValue", color_dict(color)),
+                ("loadOp", JsValue::from_str("clear")),
+                ("storeOp", JsValue::from_str("store"))
+            ]),
+            None => object(&[
+                ("view", view),
+                ("loadOp", JsValue::from_str("load")),
+                ("storeOp", JsValue::from_str("store"))
+            ])
+        };
+        let attachments: Array = std::iter::once(attachment).collect();
+        Ok(call(encoder, "beginRenderPass", &[object(&[("colorAttachments", attachments.into())])])?)
+    }
+
+    fn submit(&self, encoder: &JsValue) -> Result<(), WebGpuError> {
+        let command_buffer = call(encoder, "finish", &[])?;
+        let queue = get(&self.device, "queue")?;
+        let command_buffers: Array = std::iter::once(command_buffer).collect();
+        call(&queue, "submit", &[command_buffers.into()])?;
+        Ok(())
+    }
+}
+
+const QUAD_SHADER: &str = "
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec4<f32>
+};
+
+@vertex
+fn vertex_main(@location(0) position: vec2<f32>, @location(1) color: vec4<f32>) -> VertexOutput {
+    var output: VertexOutput;
+    output.position = vec4<f32>(position, 0.0, 1.0);
+    output.color = color;
+    return output;
+}
+
+@fragment
+fn fragment_main(@location(0) color: vec4<f32>) -> @location(0) vec4<f32> {
+    return vec4<f32>(color.rgb * color.a, color.a);
+}
+";
+
+/// 2 for the position and 4 for the color
+const FLOATS_PER_VERTEX: usize = 6;
+
+/// The WebGPU version of QuadBatch, which is limited to colored quads for now. The quads are drawn in the order in
+/// which they were pushed, with regular alpha blending.
+pub struct GpuQuadBatch {
+    pipeline: JsValue,
+    vertex_buffer: Option<(JsValue, usize)>,
+    vertices: Vec<f32>
+}
+
+impl GpuQuadBatch {
+
+    pub fn new(canvas: &GpuCanvas) -> Result<GpuQuadBatch, WebGpuError> {
+        let module = call(&canvas.device, "createShaderModule", &[object(&[("code", JsValue::from_str(QUAD_SHADER))])])?;
+
+        let attribute = |location: u32, offset: usize, format: &str| object(&[
+            ("shaderLocation", JsValue::from(location)),
+            ("offset", JsValue::from((offset * 4) as u32)),
+            ("format", JsValue::from_str(format))
+        ]);
+        let attributes: Array = vec![attribute(0, 0, "float32x2"), attribute(1, 2, "float32x4")].into_iter().collect();
+        let buffers: Array = std::iter::once(object(&[
+            ("arrayStride", JsValue::from((FLOATS_PER_VERTEX * 4) as u32)),
+            ("attributes", attributes.into())
+        ])).collect();
+
+        // The shader outputs premultiplied colors, which matches the premultiplied alpha mode of the canvas
+        let blend_component = || object(&[
+            ("srcFactor", JsValue::from_str("one")),
+            ("dstFactor", JsValue::from_str("one-minus-src-alpha")),
+            ("operation", JsValue::from_str("add"))
+        ]);
+        let targets: Array = std::iter::once(object(&[
+            ("format", canvas.format.clone()),
+            ("blend", object(&[("color", blend_component()), ("alpha", blend_component())]))
+        ])).collect();
+
+        let pipeline = call(&canvas.device, "createRenderPipeline", &[object(&[
+            ("layout", JsValue::from_str("auto")),
+            ("vertex", object(&[
+                ("module", module.clone()),
+                ("entryPoint", JsValue::from_str("vertex_main")),
+                ("buffers", buffers.into())
+            ])),
+            ("fragment", object(&[
+                ("module", module),
+                ("entryPoint", JsValue::from_str("fragment_main")),
+                ("targets", targets.into())
+            ])),
+            ("primitive", object(&[("topology", JsValue::from_str("triangle-list"))]))
+        ])])?;
+
+        Ok(GpuQuadBatch {
+            pipeline,
+            vertex_buffer: None,
+            vertices: Vec::new()
+        })
+    }
+
+    /// Adds a quad that fills the region with a solid color.
+    pub fn push_color(&mut self, region: Region, color: Color) {
+        push_quad_vertices(&mut self.vertices, region, color);
+    }
+
+    /// Gets the number of quads that will be drawn during the next flush.
+    pub fn len(&self) -> usize {
+        self.vertices.len() / (6 * FLOATS_PER_VERTEX)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    /// Draws all quads that were pushed since the previous flush onto the canvas, and clears the batch. If clear_color
+    /// is given, the canvas is cleared to it first.
+    pub fn flush(&mut self, canvas: &GpuCanvas, clear_color: Option<Color>) -> Result<(), WebGpuError> {
+        let encoder = call(&canvas.device, "createCommandEncoder", &[])?;
+        let pass = canvas.begin_render_pass(&encoder, clear_color)?;
+
+        if !self.vertices.is_empty() {
+            let buffer = self.ensure_vertex_buffer(canvas)?;
+            let queue = get(&canvas.device, "queue")?;
+            let data = Float32Array::from(&self.vertices[..]);
+            call(&queue, "writeBuffer", &[buffer.clone(), JsValue::from(0), data.into()])?;
+
+            call(&pass, "setPipeline", std::slice::from_ref(&self.pipeline))?;
+            call(&pass, "setVertexBuffer", &[JsValue::from(0), buffer])?;
+            call(&pass, "draw", &[JsValue::from((self.vertices.len() / FLOATS_PER_VERTEX) as u32)])?;
+        }
+
+        call(&pass, "end", &[])?;
+        canvas.submit(&encoder)?;
+        self.vertices.clear();
+        Ok(())
+    }
+
+    /// Gets a vertex buffer that is large enough for the current vertices, creating a larger one if needed
+    fn ensure_vertex_buffer(&mut self, canvas: &GpuCanvas) -> Result<JsValue, WebGpuError> {
+        let needed_size = self.vertices.len() * 4;
+        if let Some((buffer, size)) = &self.vertex_buffer {
+            if *size >= needed_size {
+                return Ok(buffer.clone());
+            }
+            call(buffer, "destroy", &[])?;
+        }
+
+        let size = needed_size.next_power_of_two();
+        let buffer = call(&canvas.device, "createBuffer", &[object(&[
+            ("size", JsValue::from(size as u32)),
+            ("usage", JsValue::from(VERTEX_BUFFER_USAGE))
+        ])])?;
+        self.vertex_buffer = Some((buffer.clone(), size));
+        Ok(buffer)
+    }
+
+    /// Destroys the vertex buffer of this batch. This struct must not be used anymore after calling this.
+    pub fn delete(self) {
+        if let Some((buffer, _)) = self.vertex_buffer {
+            let _ = call(&buffer, "destroy", &[]);
+        }
+    }
+}
+
+/// Adds the 2 triangles (6 vertices) of a colored quad
+fn push_quad_vertices(vertices: &mut Vec<f32>, region: Region, color: Color) {
+    let (min_x, min_y) = (region.get_float_min_x(), region.get_float_min_y());
+    let (max_x, max_y) = (region.get_float_max_x(), region.get_float_max_y());
+    let color = color.to_float_array();
+    for (x, y) in [(min_x, min_y), (max_x, min_y), (max_x, max_y), (max_x, max_y), (min_x, max_y), (min_x, min_y)].iter() {
+        vertices.extend_from_slice(&[*x, *y]);
+        vertices.extend_from_slice(&color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_push_quad_vertices() {
+        let mut vertices = Vec::new();
+        push_quad_vertices(&mut vertices, Region::new(-10_000, 0, 5_000, 10_000), Color::RED);
+        assert_eq!(6 * FLOATS_PER_VERTEX, vertices.len());
+        assert_eq!(&[-1.0, 0.0, 1.0, 0.0, 0.0, 1.0], &vertices[0..6]);
+        assert_eq!(&[0.5, 1.0], &vertices[12..14]);
+        assert_eq!(&vertices[0..6], &vertices[30..36]);
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{
+    Region,
+    SafeAreaInsets
+};
+
+/// A rectangle in (CSS or device) pixel coordinates, with the origin at the top-left corner of the viewport and the
+/// y-axis pointing downwards, like the coordinates of the DOM.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub struct PixelRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32
+}
+
+impl Region {
+
+    /// Converts this region to pixel coordinates for a viewport of the given size (in pixels). Adjacent regions will
+    /// be converted to adjacent pixel rectangles (without gaps or overlap) as long as the viewport is smaller than
+    /// 10_000 pixels.
+    pub fn to_pixel_rect(&self, viewport_width: u32, viewport_height: u32) -> PixelRect {
+        let left = to_pixel(self.get_min_x(), viewport_width);
+        let right = to_pixel(self.get_max_x() + 1, viewport_width);
+        let bottom = to_pixel(self.get_min_y(), viewport_height);
+        let top = to_pixel(self.get_max_y() + 1, viewport_height);
+        PixelRect {
+            x: left,
+            y: viewport_height as i32 - top,
+            width: right - left,
+            height: top - bottom
+        }
+    }
+}
+
+/// Converts a Region coordinate to a pixel coordinate using only integer math, so that the result is exact.
+fn to_pixel(coordinate: i32, viewport_size: u32) -> i32 {
+    let numerator = (coordinate as i64 + 10_000) * viewport_size as i64;
+    ((numerator + 10_000) / 20_001) as i32
+}
+
+/// Everything that is needed to compute a LayoutSnapshot. None of this requires a canvas or a GL context, so layouts
+/// can be computed on servers, in command line tools and in plain unit tests.
+#[derive(Clone,Debug)]
+pub struct LayoutInputs {
+    /// The width of the viewport, in CSS pixels
+    pub viewport_width: u32,
+    /// The height of the viewport, in CSS pixels
+    pub viewport_height: u32,
+    /// The safe area insets of the (simulated) device, or SafeAreaInsets::NONE
+    pub safe_area: SafeAreaInsets,
+    /// The regions of the components, in viewport coordinates
+    pub components: Vec<Region>
+}
+
+/// The result of compute_layout.
+#[derive(Clone,Debug,PartialEq)]
+pub struct LayoutSnapshot {
+    /// The region that layout roots should use, see safe_viewport()
+    pub root: Region,
+    /// The pixel rectangles of the components, in the same order as LayoutInputs::components
+    pub component_pixels: Vec<PixelRect>,
+    /// For each component, whether it is (partially) visible inside the root region
+    pub component_visible: Vec<bool>,
+    /// The parts of the root region that are not covered by any component, for instance to determine which parts
+    /// of the background need to be drawn
+    pub uncovered: Vec<Region>
+}
+
+/// Computes the layout for the given inputs, without needing a canvas, a GL context or even a browser.
+pub fn compute_layout(inputs: &LayoutInputs) -> LayoutSnapshot {
+    let root = inputs.safe_area.to_region(inputs.viewport_width as f32, inputs.viewport_height as f32);
+    LayoutSnapshot {
+        root,
+        component_pixels: inputs.components.iter().map(|component| component.to_pixel_rect(inputs.viewport_width, inputs.viewport_height)).collect(),
+        component_visible: inputs.components.iter().map(|component| component.intersects_with(root)).collect(),
+        uncovered: root.get_uncovered_regions(&inputs.components)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_to_pixel_rect(){
+        assert_eq!(PixelRect { x: 0, y: 0, width: 800, height: 600 }, Region::entire_viewport().to_pixel_rect(800, 600));
+        assert_eq!(PixelRect { x: 0, y: 300, width: 400, height: 300 }, Region::new(-10_000, -10_000, 0, 0).to_pixel_rect(800, 600));
+        assert_eq!(PixelRect { x: 400, y: 0, width: 400, height: 300 }, Region::new(1, 1, 10_000, 10_000).to_pixel_rect(800, 600));
+
+        // Adjacent regions should remain adjacent
+        let left = Region::new(-10_000, -10_000, 1234, 10_000).to_pixel_rect(1920, 1080);
+        let right = Region::new(1235, -10_000, 10_000, 10_000).to_pixel_rect(1920, 1080);
+        assert_eq!(left.x + left.width, right.x);
+        assert_eq!(1920, left.width + right.width);
+    }
+
+    #[test]
+    fn test_compute_layout(){
+        let inputs = LayoutInputs {
+            viewport_width: 400,
+            viewport_height: 800,
+            safe_area: SafeAreaInsets {
+                top: 40.0,
+                right: 0.0,
+                bottom: 0.0,
+                left: 0.0
+            },
+            components: vec![Region::new(-10_000, -10_000, 10_000, 0), Region::new(-10_000, 9_500, 10_000, 10_000)]
+        };
+        let snapshot = compute_layout(&inputs);
+
+        assert_eq!(Region::new(-10_000, -10_000, 10_000, 9_000), snapshot.root);
+        assert_eq!(vec![PixelRect { x: 0, y: 400, width: 400, height: 400 }, PixelRect { x: 0, y: 0, width: 400, height: 20 }], snapshot.component_pixels);
+        assert_eq!(vec![true, false], snapshot.component_visible);
+        assert_eq!(vec![Region::new(-10_000, 1, 10_000, 9_000)], snapshot.uncovered);
+    }
+}
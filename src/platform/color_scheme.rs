@@ -0,0 +1,89 @@
+use std::cell::RefCell;
+use std::rc::Weak;
+
+use wasm_bindgen::JsCast;
+use wasm_bindgen::closure::Closure;
+
+use web_sys::{
+    MediaQueryList,
+    MediaQueryListEvent
+};
+
+use crate::{
+    Theme,
+    WeakVec
+};
+
+/// The color scheme the user prefers, as configured in the settings of their OS or browser.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum ColorScheme {
+    Light,
+    Dark
+}
+
+impl ColorScheme {
+
+    fn from_dark(is_dark: bool) -> ColorScheme {
+        if is_dark {
+            ColorScheme::Dark
+        } else {
+            ColorScheme::Light
+        }
+    }
+
+    /// Gets the default Theme for this color scheme.
+    pub fn get_theme(&self) -> Theme {
+        match self {
+            ColorScheme::Light => Theme::light(),
+            ColorScheme::Dark => Theme::dark()
+        }
+    }
+}
+
+const DARK_QUERY: &str = "(prefers-color-scheme: dark)";
+
+thread_local! {
+    static LISTENERS: RefCell<WeakVec<dyn FnMut(ColorScheme)>> = RefCell::new(WeakVec::new());
+    // Some browsers stop notifying listeners of a MediaQueryList once it has been garbage collected
+    static QUERY: RefCell<Option<MediaQueryList>> = const { RefCell::new(None) };
+}
+
+/// Gets the color scheme preferred by the user. If the browser doesn't support the prefers-color-scheme media
+/// query, ColorScheme::Light is returned.
+pub fn preferred_color_scheme() -> ColorScheme {
+    match get_dark_query() {
+        Some(query) => ColorScheme::from_dark(query.matches()),
+        None => ColorScheme::Light
+    }
+}
+
+/// Picks the default Theme that matches the color scheme preferred by the user.
+pub fn default_theme() -> Theme {
+    preferred_color_scheme().get_theme()
+}
+
+/// Registers a listener that will be called with the new color scheme whenever the user changes their preferred
+/// color scheme. Applications that use default_theme() should use this to switch themes while they are running.
+pub fn add_color_scheme_listener(listener: Weak<RefCell<dyn FnMut(ColorScheme)>>) {
+    let is_listening = QUERY.with(|query| query.borrow().is_some());
+    if !is_listening {
+        if let Some(query) = get_dark_query() {
+            let on_change = Closure::wrap(Box::new(|event: MediaQueryListEvent| {
+                let scheme = ColorScheme::from_dark(event.matches());
+                LISTENERS.with(|listeners| WeakVec::for_each_mut_shared(listeners, |listener| listener(scheme)));
+            }) as Box<dyn FnMut(MediaQueryListEvent)>);
+            query.add_event_listener_with_callback("change", on_change.as_ref().unchecked_ref())
+                .expect("Should be able to listen for color scheme changes");
+
+            // The media query is shared by all color scheme listeners, so its change handler is never removed
+            on_change.forget();
+            QUERY.with(|stored| *stored.borrow_mut() = Some(query));
+        }
+    }
+
+    LISTENERS.with(|listeners| listeners.borrow_mut().push(listener));
+}
+
+fn get_dark_query() -> Option<MediaQueryList> {
+    web_sys::window()?.match_media(DARK_QUERY).ok()?
+}
@@ -0,0 +1,77 @@
+use std::cell::Cell;
+
+use wasm_bindgen::JsValue;
+
+/// Determines whether calls to vibrate will actually make the device vibrate.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum HapticsPolicy {
+    /// Vibrate whenever the device supports it
+    Enabled,
+    /// Never vibrate, for instance because the user turned haptic feedback off in the settings of the application
+    Disabled,
+    /// Vibrate unless the user indicated that they prefer reduced motion in their OS settings. This is the default.
+    RespectReducedMotion
+}
+
+thread_local! {
+    static POLICY: Cell<HapticsPolicy> = const { Cell::new(HapticsPolicy::RespectReducedMotion) };
+}
+
+/// Changes the policy that will be used by all subsequent calls to vibrate.
+pub fn set_haptics_policy(policy: HapticsPolicy) {
+    POLICY.with(|current| current.set(policy));
+}
+
+pub fn get_haptics_policy() -> HapticsPolicy {
+    POLICY.with(|current| current.get())
+}
+
+/// Checks if the browser supports the Vibration API. Note that this returns true on many desktop browsers as well,
+/// even though those devices can't vibrate.
+pub fn supports_vibration() -> bool {
+    match web_sys::window() {
+        Some(window) => js_sys::Reflect::has(&window.navigator(), &JsValue::from_str("vibrate")).unwrap_or(false),
+        None => false
+    }
+}
+
+/// Lets the device vibrate with the given pattern, which alternates between vibration durations and pause durations
+/// (in milliseconds). So a pattern of [50] will vibrate once for 50ms, and a pattern of [30, 100, 30] will vibrate
+/// twice for 30ms with a pause of 100ms in between.
+/// 
+/// Returns true if the vibration was requested, and false if it was suppressed by the haptics policy, the device
+/// doesn't support vibration, or the browser refused it (browsers typically refuse vibrations before the first
+/// user interaction with the page).
+pub fn vibrate(pattern: &[u32]) -> bool {
+    if !is_allowed_by_policy() || !supports_vibration() {
+        return false;
+    }
+
+    let window = web_sys::window().expect("There should be a window");
+    let js_pattern = pattern.iter().map(|duration| JsValue::from(*duration)).collect::<js_sys::Array>();
+    window.navigator().vibrate_with_pattern(&js_pattern)
+}
+
+/// Stops any ongoing vibration that was started by vibrate. This is not affected by the haptics policy.
+pub fn cancel_vibration() {
+    if supports_vibration() {
+        let window = web_sys::window().expect("There should be a window");
+        window.navigator().vibrate_with_duration(0);
+    }
+}
+
+fn is_allowed_by_policy() -> bool {
+    match get_haptics_policy() {
+        HapticsPolicy::Enabled => true,
+        HapticsPolicy::Disabled => false,
+        HapticsPolicy::RespectReducedMotion => !prefers_reduced_motion()
+    }
+}
+
+fn prefers_reduced_motion() -> bool {
+    let window = web_sys::window().expect("There should be a window");
+    match window.match_media("(prefers-reduced-motion: reduce)") {
+        Ok(Some(query)) => query.matches(),
+        _ => false
+    }
+}
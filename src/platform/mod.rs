@@ -0,0 +1,16 @@
+mod color_scheme;
+mod haptics;
+mod notification;
+mod orientation;
+mod permissions;
+mod safe_area;
+mod share;
+mod wake_lock;
+pub use color_scheme::*;
+pub use haptics::*;
+pub use notification::*;
+pub use orientation::*;
+pub use permissions::*;
+pub use safe_area::*;
+pub use share::*;
+pub use wake_lock::*;
@@ -0,0 +1,104 @@
+use wasm_bindgen::JsCast;
+use wasm_bindgen::JsValue;
+use wasm_bindgen::closure::Closure;
+
+use web_sys::NotificationOptions;
+
+use super::{
+    Permission,
+    PermissionError,
+    PermissionState,
+    request_permission
+};
+
+/// The reasons why notify can fail.
+#[derive(Debug)]
+pub enum NotificationError {
+    /// The browser doesn't support the Notification API
+    Unsupported,
+    /// The user denied (or dismissed) the notification permission
+    PermissionDenied,
+    /// Showing the notification failed for another reason
+    Failed(JsValue)
+}
+
+impl std::fmt::Display for NotificationError {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            NotificationError::Unsupported => write!(f, "The Notification API is not supported"),
+            NotificationError::PermissionDenied => write!(f, "The notification permission was not granted"),
+            NotificationError::Failed(reason) => write!(f, "Showing the notification failed: {:?}", reason)
+        }
+    }
+}
+
+impl std::error::Error for NotificationError {}
+
+impl From<PermissionError> for NotificationError {
+
+    fn from(error: PermissionError) -> NotificationError {
+        match error {
+            PermissionError::Unsupported | PermissionError::UnknownPermission(_) => NotificationError::Unsupported,
+            PermissionError::Failed(reason) => NotificationError::Failed(reason)
+        }
+    }
+}
+
+/// A notification that was shown by notify.
+pub struct AppNotification {
+    notification: web_sys::Notification
+}
+
+impl AppNotification {
+
+    /// Sets the callback that will be called when the user clicks on this notification. Clicking on the notification
+    /// will also bring the tab of the application to the front and close the notification.
+    /// 
+    /// This handle doesn't need to be kept alive for the callback to be called.
+    pub fn set_on_click<F: FnMut() + 'static>(&self, mut callback: F) {
+        let notification = self.notification.clone();
+        let on_click = Closure::wrap(Box::new(move || {
+            if let Some(window) = web_sys::window() {
+                let _ = window.focus();
+            }
+            notification.close();
+            callback();
+        }) as Box<dyn FnMut()>);
+
+        // Let the garbage collector of JavaScript take care of the closure, since the notification can outlive this handle
+        self.notification.set_onclick(Some(on_click.into_js_value().unchecked_ref()));
+    }
+
+    /// Closes this notification, if it is still shown.
+    pub fn close(&self) {
+        self.notification.close();
+    }
+}
+
+/// Shows a system notification with the given title, body and (optionally) the URL of an icon. This is mostly useful
+/// to alert users when the application is running in a background tab.
+/// 
+/// If the user hasn't granted (or denied) the notification permission yet, it will be requested first. Browsers only
+/// show that prompt during a user interaction, so the first call should happen from an event handler.
+pub async fn notify(title: &str, body: &str, icon: Option<&str>) -> Result<AppNotification, NotificationError> {
+    let window = web_sys::window().expect("There should be a window");
+    if !js_sys::Reflect::has(&window, &JsValue::from_str("Notification")).unwrap_or(false) {
+        return Err(NotificationError::Unsupported);
+    }
+
+    if request_permission(Permission::Notifications).await? != PermissionState::Granted {
+        return Err(NotificationError::PermissionDenied);
+    }
+
+    let options = NotificationOptions::new();
+    options.set_body(body);
+    if let Some(icon) = icon {
+        options.set_icon(icon);
+    }
+
+    let notification = web_sys::Notification::new_with_options(title, &options).map_err(NotificationError::Failed)?;
+    Ok(AppNotification {
+        notification
+    })
+}
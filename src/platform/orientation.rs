@@ -0,0 +1,208 @@
+use std::cell::Cell;
+
+use wasm_bindgen::JsCast;
+use wasm_bindgen::JsValue;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen_futures::JsFuture;
+
+use web_sys::{
+    OrientationLockType,
+    OrientationType,
+    ScreenOrientation,
+    Window
+};
+
+use crate::{
+    Subscription,
+    publish_message,
+    subscribe_message
+};
+
+/// The orientation of the screen, as reported by `screen.orientation.type`.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum Orientation {
+    PortraitPrimary,
+    PortraitSecondary,
+    LandscapePrimary,
+    LandscapeSecondary
+}
+
+impl Orientation {
+
+    pub fn is_portrait(&self) -> bool {
+        matches!(self, Orientation::PortraitPrimary | Orientation::PortraitSecondary)
+    }
+
+    pub fn is_landscape(&self) -> bool {
+        !self.is_portrait()
+    }
+
+    fn from_type(orientation_type: OrientationType) -> Option<Orientation> {
+        match orientation_type {
+            OrientationType::PortraitPrimary => Some(Orientation::PortraitPrimary),
+            OrientationType::PortraitSecondary => Some(Orientation::PortraitSecondary),
+            OrientationType::LandscapePrimary => Some(Orientation::LandscapePrimary),
+            OrientationType::LandscapeSecondary => Some(Orientation::LandscapeSecondary),
+            _ => None
+        }
+    }
+
+    /// Guesses the orientation from the size of the viewport. This is used on browsers that don't support
+    /// the Screen Orientation API.
+    fn from_size(width: f64, height: f64) -> Orientation {
+        if width > height {
+            Orientation::LandscapePrimary
+        } else {
+            Orientation::PortraitPrimary
+        }
+    }
+}
+
+/// The orientations the screen can be locked to, see lock_orientation.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum OrientationLock {
+    Any,
+    Natural,
+    Landscape,
+    Portrait,
+    PortraitPrimary,
+    PortraitSecondary,
+    LandscapePrimary,
+    LandscapeSecondary
+}
+
+impl OrientationLock {
+
+    fn to_lock_type(self) -> OrientationLockType {
+        match self {
+            OrientationLock::Any => OrientationLockType::Any,
+            OrientationLock::Natural => OrientationLockType::Natural,
+            OrientationLock::Landscape => OrientationLockType::Landscape,
+            OrientationLock::Portrait => OrientationLockType::Portrait,
+            OrientationLock::PortraitPrimary => OrientationLockType::PortraitPrimary,
+            OrientationLock::PortraitSecondary => OrientationLockType::PortraitSecondary,
+            OrientationLock::LandscapePrimary => OrientationLockType::LandscapePrimary,
+            OrientationLock::LandscapeSecondary => OrientationLockType::LandscapeSecondary
+        }
+    }
+}
+
+/// The reasons why lock_orientation can fail.
+#[derive(Debug)]
+pub enum OrientationLockError {
+    /// The browser doesn't support the Screen Orientation API at all
+    Unsupported,
+    /// The browser rejected the lock request. Most browsers only allow locking the orientation while the page is
+    /// in fullscreen mode, and desktop browsers typically never allow it.
+    Rejected(JsValue)
+}
+
+impl std::fmt::Display for OrientationLockError {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            OrientationLockError::Unsupported => write!(f, "The Screen Orientation API is not supported"),
+            OrientationLockError::Rejected(reason) => write!(f, "The orientation lock was rejected: {:?}", reason)
+        }
+    }
+}
+
+impl std::error::Error for OrientationLockError {}
+
+/// The message that is published on the global message bus (see subscribe_message) when the orientation of the screen
+/// changes, once watch_orientation was called.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub struct OrientationChange {
+    pub orientation: Orientation
+}
+
+thread_local! {
+    static LAST_ORIENTATION: Cell<Option<Orientation>> = const { Cell::new(None) };
+}
+
+/// Gets the current orientation of the screen. If the browser doesn't support the Screen Orientation API,
+/// the orientation will be derived from the size of the viewport.
+pub fn current_orientation() -> Orientation {
+    let window = web_sys::window().expect("There should be a window");
+    current_orientation_of(&window)
+}
+
+/// Starts publishing an OrientationChange message on the global message bus whenever the orientation of the screen
+/// changes. Calling this more than once has no effect.
+/// 
+/// Changes are detected through `orientationchange` events, and through the canvas resizes that are reported by every
+/// CanvasResizeWatcher. The latter also covers browsers that only resize the viewport when the device is rotated, and
+/// ensures that the message is published before the resize listeners of the watcher are called. The message is only
+/// published when the orientation actually changed.
+pub fn watch_orientation() {
+    let window = web_sys::window().expect("There should be a window");
+    if LAST_ORIENTATION.with(|last| last.get()).is_none() {
+        LAST_ORIENTATION.with(|last| last.set(Some(current_orientation_of(&window))));
+
+        let on_change = Closure::wrap(Box::new(publish_orientation_change) as Box<dyn FnMut()>);
+        window.add_event_listener_with_callback("orientationchange", on_change.as_ref().unchecked_ref())
+            .expect("Should be able to listen for orientationchange");
+        on_change.forget();
+    }
+}
+
+/// Calls watch_orientation and subscribes the callback to the OrientationChange messages, until the returned
+/// Subscription is dropped.
+pub fn subscribe_orientation_changes<F: FnMut(&OrientationChange) + 'static>(callback: F) -> Subscription {
+    watch_orientation();
+    subscribe_message(callback)
+}
+
+/// Attempts to lock the orientation of the screen to the given kind. This is only supported by some (mostly mobile)
+/// browsers, and usually only while the page is in fullscreen mode.
+pub async fn lock_orientation(kind: OrientationLock) -> Result<(), OrientationLockError> {
+    let screen_orientation = get_screen_orientation().ok_or(OrientationLockError::Unsupported)?;
+    let promise = screen_orientation.lock(kind.to_lock_type()).map_err(OrientationLockError::Rejected)?;
+    JsFuture::from(promise).await.map_err(OrientationLockError::Rejected)?;
+    Ok(())
+}
+
+/// Releases an orientation lock that was previously obtained with lock_orientation. This does nothing if the screen
+/// orientation wasn't locked or if the browser doesn't support the Screen Orientation API.
+pub fn unlock_orientation() {
+    if let Some(screen_orientation) = get_screen_orientation() {
+        let _ = screen_orientation.unlock();
+    }
+}
+
+/// Publishes an OrientationChange message if the orientation changed since the last check. This does nothing until
+/// watch_orientation is called.
+pub(crate) fn publish_orientation_change() {
+    if LAST_ORIENTATION.with(|last| last.get()).is_none() {
+        return;
+    }
+    let orientation = current_orientation();
+    let previous = LAST_ORIENTATION.with(|last| last.replace(Some(orientation)));
+    if previous != Some(orientation) {
+        publish_message(&OrientationChange { orientation });
+    }
+}
+
+fn current_orientation_of(window: &Window) -> Orientation {
+    let from_api = get_screen_orientation()
+        .and_then(|screen_orientation| screen_orientation.type_().ok())
+        .and_then(Orientation::from_type);
+    match from_api {
+        Some(orientation) => orientation,
+        None => {
+            let width = window.inner_width().ok().and_then(|width| width.as_f64()).unwrap_or(0.0);
+            let height = window.inner_height().ok().and_then(|height| height.as_f64()).unwrap_or(0.0);
+            Orientation::from_size(width, height)
+        }
+    }
+}
+
+fn get_screen_orientation() -> Option<ScreenOrientation> {
+    let screen = web_sys::window()?.screen().ok()?;
+    let orientation = js_sys::Reflect::get(&screen, &JsValue::from_str("orientation")).ok()?;
+    if orientation.is_undefined() || orientation.is_null() {
+        None
+    } else {
+        Some(orientation.unchecked_into())
+    }
+}
@@ -0,0 +1,194 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Weak;
+
+use wasm_bindgen::JsCast;
+use wasm_bindgen::JsValue;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen_futures::JsFuture;
+
+use web_sys::{
+    Notification,
+    NotificationPermission,
+    PermissionStatus
+};
+
+use crate::WeakVec;
+
+/// The permissions that can be queried with query_permission.
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+pub enum Permission {
+    ClipboardRead,
+    ClipboardWrite,
+    Notifications,
+    ScreenWakeLock,
+    Geolocation,
+    /// Any other permission, by the name used by the Permissions API
+    Other(&'static str)
+}
+
+impl Permission {
+
+    /// Gets the name of this permission, as used by the Permissions API.
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Permission::ClipboardRead => "clipboard-read",
+            Permission::ClipboardWrite => "clipboard-write",
+            Permission::Notifications => "notifications",
+            Permission::ScreenWakeLock => "screen-wake-lock",
+            Permission::Geolocation => "geolocation",
+            Permission::Other(name) => name
+        }
+    }
+}
+
+/// The state of a permission.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum PermissionState {
+    /// The permission has been granted, so the corresponding API can be used without prompting the user
+    Granted,
+    /// The permission has been denied, so the corresponding API will fail (often silently)
+    Denied,
+    /// The user will be prompted when the corresponding API is used (or when the permission is requested)
+    Prompt
+}
+
+impl PermissionState {
+
+    fn from_web(state: web_sys::PermissionState) -> PermissionState {
+        match state {
+            web_sys::PermissionState::Granted => PermissionState::Granted,
+            web_sys::PermissionState::Denied => PermissionState::Denied,
+            _ => PermissionState::Prompt
+        }
+    }
+
+    fn from_notification(permission: NotificationPermission) -> PermissionState {
+        match permission {
+            NotificationPermission::Granted => PermissionState::Granted,
+            NotificationPermission::Denied => PermissionState::Denied,
+            _ => PermissionState::Prompt
+        }
+    }
+}
+
+/// The reasons why querying or requesting a permission can fail.
+#[derive(Debug)]
+pub enum PermissionError {
+    /// The browser doesn't support the Permissions API (or the API needed to request the permission)
+    Unsupported,
+    /// The browser doesn't know this permission, which usually means that it doesn't support the corresponding API
+    UnknownPermission(Permission),
+    /// The query or request failed for another reason
+    Failed(JsValue)
+}
+
+impl std::fmt::Display for PermissionError {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PermissionError::Unsupported => write!(f, "The Permissions API is not supported"),
+            PermissionError::UnknownPermission(permission) => write!(f, "The permission {} is not known by this browser", permission.get_name()),
+            PermissionError::Failed(reason) => write!(f, "The permission query failed: {:?}", reason)
+        }
+    }
+}
+
+impl std::error::Error for PermissionError {}
+
+type PermissionListeners = WeakVec<dyn FnMut(PermissionState)>;
+
+thread_local! {
+    static WATCHERS: RefCell<HashMap<Permission, PermissionListeners>> = RefCell::new(HashMap::new());
+}
+
+/// Queries the current state of the given permission, without prompting the user.
+pub async fn query_permission(permission: Permission) -> Result<PermissionState, PermissionError> {
+    let status = query_status(permission).await?;
+    Ok(PermissionState::from_web(status.state()))
+}
+
+/// Requests the given permission and returns its new state.
+/// 
+/// Only the notifications permission can be requested explicitly; the user will be prompted for the other
+/// permissions when the corresponding API is used. For those permissions, this function just returns the current
+/// state. Like most permission prompts, this should be called during a user interaction.
+pub async fn request_permission(permission: Permission) -> Result<PermissionState, PermissionError> {
+    if permission == Permission::Notifications {
+        let window = web_sys::window().expect("There should be a window");
+        if !js_sys::Reflect::has(&window, &JsValue::from_str("Notification")).unwrap_or(false) {
+            return Err(PermissionError::Unsupported);
+        }
+
+        let promise = Notification::request_permission().map_err(PermissionError::Failed)?;
+        let result = JsFuture::from(promise).await.map_err(PermissionError::Failed)?;
+        let state = match result.as_string().as_deref() {
+            Some("granted") => PermissionState::Granted,
+            Some("denied") => PermissionState::Denied,
+            _ => PermissionState::from_notification(Notification::permission())
+        };
+        Ok(state)
+    } else {
+        query_permission(permission).await
+    }
+}
+
+/// Registers a listener that will be called with the new state whenever the state of the given permission changes
+/// (for instance because the user changed it in the site settings of the browser).
+pub async fn watch_permission(permission: Permission, listener: Weak<RefCell<dyn FnMut(PermissionState)>>) -> Result<(), PermissionError> {
+    let is_watched = WATCHERS.with(|watchers| watchers.borrow().contains_key(&permission));
+    if !is_watched {
+        let status = query_status(permission).await?;
+
+        // Another call may have started watching while we were waiting for the query
+        let is_watched = WATCHERS.with(|watchers| watchers.borrow().contains_key(&permission));
+        if !is_watched {
+            let status_clone = status.clone();
+            let on_change = Closure::wrap(Box::new(move || {
+                let state = PermissionState::from_web(status_clone.state());
+                WATCHERS.with(|watchers| {
+                    if let Some(listeners) = watchers.borrow_mut().get_mut(&permission) {
+                        listeners.for_each_mut(|listener| listener(state));
+                    }
+                });
+            }) as Box<dyn FnMut()>);
+            status.add_event_listener_with_callback("change", on_change.as_ref().unchecked_ref())
+                .expect("Should be able to listen for permission changes");
+
+            // Each permission is only watched once, so the status must keep reporting changes to later listeners
+            on_change.forget();
+            WATCHERS.with(|watchers| watchers.borrow_mut().insert(permission, WeakVec::new()));
+        }
+    }
+
+    WATCHERS.with(|watchers| {
+        watchers.borrow_mut().get_mut(&permission).expect("The permission should be watched by now").push(listener);
+    });
+    Ok(())
+}
+
+async fn query_status(permission: Permission) -> Result<PermissionStatus, PermissionError> {
+    let navigator = web_sys::window().expect("There should be a window").navigator();
+    if !js_sys::Reflect::has(&navigator, &JsValue::from_str("permissions")).unwrap_or(false) {
+        return Err(PermissionError::Unsupported);
+    }
+    let permissions = navigator.permissions().map_err(PermissionError::Failed)?;
+
+    // Build the descriptor by hand because web_sys::PermissionName doesn't know all permissions
+    let descriptor = js_sys::Object::new();
+    js_sys::Reflect::set(&descriptor, &JsValue::from_str("name"), &JsValue::from_str(permission.get_name()))
+        .expect("Should be able to set the name of the descriptor");
+
+    // Browsers throw a TypeError for permission names they don't know
+    let promise = permissions.query(&descriptor).map_err(|_| PermissionError::UnknownPermission(permission))?;
+    match JsFuture::from(promise).await {
+        Ok(status) => Ok(status.unchecked_into()),
+        Err(error) => {
+            if error.is_instance_of::<js_sys::TypeError>() {
+                Err(PermissionError::UnknownPermission(permission))
+            } else {
+                Err(PermissionError::Failed(error))
+            }
+        }
+    }
+}
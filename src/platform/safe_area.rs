@@ -0,0 +1,178 @@
+use std::cell::{
+    Cell,
+    RefCell
+};
+
+use wasm_bindgen::JsCast;
+use wasm_bindgen::closure::Closure;
+
+use web_sys::{
+    HtmlElement,
+    Window
+};
+
+use crate::Region;
+
+/// The sizes (in CSS pixels) of the edges of the viewport that are obscured by notches, rounded display corners or
+/// home bars. These are the values of the CSS `env(safe-area-inset-*)` variables.
+/// 
+/// Note that browsers will only report non-zero insets when the page has `viewport-fit=cover` in its viewport meta tag.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct SafeAreaInsets {
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32
+}
+
+impl SafeAreaInsets {
+
+    pub const NONE: SafeAreaInsets = SafeAreaInsets {
+        top: 0.0,
+        right: 0.0,
+        bottom: 0.0,
+        left: 0.0
+    };
+
+    /// Converts these insets to the Region of the viewport that is not obscured by them. The viewport_width and
+    /// viewport_height must be given in CSS pixels, just like the insets themselves.
+    /// 
+    /// If the viewport has no size (or the insets would cover the entire viewport), Region::entire_viewport() is returned.
+    pub fn to_region(&self, viewport_width: f32, viewport_height: f32) -> Region {
+        if viewport_width <= 0.0 || viewport_height <= 0.0 
+            || self.left + self.right >= viewport_width || self.top + self.bottom >= viewport_height {
+            return Region::entire_viewport();
+        }
+
+        // Note that the y-axis of the Region coordinates points upwards, so the top inset affects max_y
+        Region::from_floats(
+            -1.0 + 2.0 * self.left / viewport_width,
+            -1.0 + 2.0 * self.bottom / viewport_height,
+            1.0 - 2.0 * self.right / viewport_width,
+            1.0 - 2.0 * self.top / viewport_height
+        )
+    }
+}
+
+thread_local! {
+    static CACHED_INSETS: RefCell<Option<SafeAreaInsets>> = const { RefCell::new(None) };
+    static LISTENING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Gets the current safe area insets of the viewport. The result is cached until the next orientation change or
+/// resize of the window, so this function is cheap enough to call every frame.
+pub fn safe_area_insets() -> SafeAreaInsets {
+    if let Some(insets) = CACHED_INSETS.with(|cache| *cache.borrow()) {
+        return insets;
+    }
+
+    let window = web_sys::window().expect("There should be a window");
+    listen_for_invalidation(&window);
+
+    let insets = measure_insets(&window);
+    CACHED_INSETS.with(|cache| *cache.borrow_mut() = Some(insets));
+    insets
+}
+
+/// Constructs a Region spanning the part of the viewport that is not obscured by notches, rounded corners or home bars.
+/// Layout roots should use this instead of Region::entire_viewport() if their content should remain visible on phones.
+/// 
+/// On devices without such obstructions, this returns the same Region as Region::entire_viewport().
+pub fn safe_viewport() -> Region {
+    let window = web_sys::window().expect("There should be a window");
+    let width = window.inner_width().expect("Should be able to get innerWidth").as_f64().unwrap_or(0.0);
+    let height = window.inner_height().expect("Should be able to get innerHeight").as_f64().unwrap_or(0.0);
+    safe_area_insets().to_region(width as f32, height as f32)
+}
+
+fn listen_for_invalidation(window: &Window) {
+    if LISTENING.with(|listening| listening.replace(true)) {
+        return;
+    }
+
+    let invalidate = Closure::wrap(Box::new(|| {
+        CACHED_INSETS.with(|cache| *cache.borrow_mut() = None);
+    }) as Box<dyn FnMut()>);
+    window.add_event_listener_with_callback("orientationchange", invalidate.as_ref().unchecked_ref())
+        .expect("Should be able to listen for orientationchange");
+    window.add_event_listener_with_callback("resize", invalidate.as_ref().unchecked_ref())
+        .expect("Should be able to listen for resize");
+
+    // The cached insets must be invalidated after every orientation change or resize, not just the first one
+    invalidate.forget();
+}
+
+fn measure_insets(window: &Window) -> SafeAreaInsets {
+    let document = window.document().expect("The window should have a document");
+    let body = match document.body() {
+        Some(body) => body,
+        None => return SafeAreaInsets::NONE
+    };
+
+    // The env() values can't be queried directly, so we let the browser apply them to a hidden probe element
+    let probe = document.create_element("div").expect("Should be able to create a div")
+        .dyn_into::<HtmlElement>().expect("A div should be an HtmlElement");
+    probe.style().set_property("position", "fixed").expect("Should be able to set position");
+    probe.style().set_property("visibility", "hidden").expect("Should be able to set visibility");
+    probe.style().set_property("pointer-events", "none").expect("Should be able to set pointer-events");
+    for side in &["top", "right", "bottom", "left"] {
+        probe.style().set_property(&format!("padding-{}", side), &format!("env(safe-area-inset-{})", side))
+            .expect("Should be able to set padding");
+    }
+
+    body.append_child(&probe).expect("Should be able to append the probe to the body");
+    let insets = match window.get_computed_style(&probe) {
+        Ok(Some(style)) => {
+            let read = |side: &str| parse_pixels(&style.get_property_value(&format!("padding-{}", side)).unwrap_or_default());
+            SafeAreaInsets {
+                top: read("top"),
+                right: read("right"),
+                bottom: read("bottom"),
+                left: read("left")
+            }
+        }, _ => SafeAreaInsets::NONE
+    };
+    body.remove_child(&probe).expect("Should be able to remove the probe from the body");
+
+    insets
+}
+
+fn parse_pixels(value: &str) -> f32 {
+    value.trim().trim_end_matches("px").parse().unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_parse_pixels(){
+        assert_eq!(0.0, parse_pixels("0px"));
+        assert_eq!(44.0, parse_pixels("44px"));
+        assert_eq!(20.5, parse_pixels(" 20.5px "));
+        assert_eq!(0.0, parse_pixels(""));
+    }
+
+    #[test]
+    fn test_to_region(){
+        assert_eq!(Region::entire_viewport(), SafeAreaInsets::NONE.to_region(400.0, 800.0));
+        assert_eq!(Region::entire_viewport(), SafeAreaInsets::NONE.to_region(0.0, 0.0));
+
+        let notch = SafeAreaInsets {
+            top: 40.0,
+            right: 0.0,
+            bottom: 20.0,
+            left: 100.0
+        };
+        assert_eq!(Region::new(-5_000, -9_500, 10_000, 9_000), notch.to_region(400.0, 800.0));
+
+        let too_big = SafeAreaInsets {
+            top: 500.0,
+            right: 0.0,
+            bottom: 500.0,
+            left: 0.0
+        };
+        assert_eq!(Region::entire_viewport(), too_big.to_region(400.0, 800.0));
+    }
+}
@@ -0,0 +1,99 @@
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::JsFuture;
+
+use web_sys::{
+    File,
+    ShareData
+};
+
+/// The reasons why share can fail.
+#[derive(Debug)]
+pub enum ShareError {
+    /// The browser doesn't support the Web Share API (this is the case for most desktop browsers)
+    Unsupported,
+    /// The browser supports the Web Share API, but can't share this data. This typically happens when a file is
+    /// shared on a browser that can only share text and URLs, or when the file type is not allowed.
+    UnsupportedData,
+    /// The user dismissed the share dialog
+    Cancelled,
+    /// The browser refused to show the share dialog, typically because share wasn't called during a user interaction
+    NotAllowed,
+    /// Sharing failed for another reason
+    Failed(JsValue)
+}
+
+impl std::fmt::Display for ShareError {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ShareError::Unsupported => write!(f, "The Web Share API is not supported"),
+            ShareError::UnsupportedData => write!(f, "The browser can't share this data"),
+            ShareError::Cancelled => write!(f, "The user cancelled sharing"),
+            ShareError::NotAllowed => write!(f, "Sharing is not allowed right now"),
+            ShareError::Failed(reason) => write!(f, "Sharing failed: {:?}", reason)
+        }
+    }
+}
+
+impl std::error::Error for ShareError {}
+
+/// Checks if the browser supports the Web Share API.
+pub fn supports_sharing() -> bool {
+    match web_sys::window() {
+        Some(window) => js_sys::Reflect::has(&window.navigator(), &JsValue::from_str("share")).unwrap_or(false),
+        None => false
+    }
+}
+
+/// Opens the share dialog of the platform to share the given title, text, url and (optionally) file. Empty titles
+/// and texts will be left out.
+/// 
+/// Browsers only allow this during a user interaction (like a click event handler), so this should be called
+/// directly from such an event handler. The returned future completes when the user has picked a share target
+/// or dismissed the dialog.
+pub async fn share(title: &str, text: &str, url: Option<&str>, file: Option<&File>) -> Result<(), ShareError> {
+    if !supports_sharing() {
+        return Err(ShareError::Unsupported);
+    }
+
+    let data = ShareData::new();
+    if !title.is_empty() {
+        data.set_title(title);
+    }
+    if !text.is_empty() {
+        data.set_text(text);
+    }
+    if let Some(url) = url {
+        data.set_url(url);
+    }
+    if let Some(file) = file {
+        data.set_files(&js_sys::Array::of1(file));
+    }
+
+    let navigator = web_sys::window().expect("There should be a window").navigator();
+
+    // Older browsers support share, but not canShare. They can't share files either.
+    let has_can_share = js_sys::Reflect::has(&navigator, &JsValue::from_str("canShare")).unwrap_or(false);
+    if has_can_share {
+        if !navigator.can_share_with_data(&data) {
+            return Err(ShareError::UnsupportedData);
+        }
+    } else if file.is_some() {
+        return Err(ShareError::UnsupportedData);
+    }
+
+    match JsFuture::from(navigator.share_with_data(&data)).await {
+        Ok(_) => Ok(()),
+        Err(error) => Err(to_share_error(error))
+    }
+}
+
+fn to_share_error(error: JsValue) -> ShareError {
+    let name = js_sys::Reflect::get(&error, &JsValue::from_str("name")).ok().and_then(|name| name.as_string());
+    match name.as_deref() {
+        Some("AbortError") => ShareError::Cancelled,
+        Some("NotAllowedError") => ShareError::NotAllowed,
+        Some("DataError") | Some("TypeError") => ShareError::UnsupportedData,
+        _ => ShareError::Failed(error)
+    }
+}
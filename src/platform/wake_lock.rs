@@ -0,0 +1,160 @@
+use std::cell::RefCell;
+use std::rc::{
+    Rc,
+    Weak
+};
+
+use wasm_bindgen::JsCast;
+use wasm_bindgen::JsValue;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen_futures::{
+    JsFuture,
+    spawn_local
+};
+
+/// The reasons why request_wake_lock can fail.
+#[derive(Debug)]
+pub enum WakeLockError {
+    /// The browser doesn't support the Screen Wake Lock API
+    Unsupported,
+    /// The browser refused the wake lock, for instance because the page is not visible or the battery is low
+    Rejected(JsValue)
+}
+
+impl std::fmt::Display for WakeLockError {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            WakeLockError::Unsupported => write!(f, "The Screen Wake Lock API is not supported"),
+            WakeLockError::Rejected(reason) => write!(f, "The wake lock was rejected: {:?}", reason)
+        }
+    }
+}
+
+impl std::error::Error for WakeLockError {}
+
+struct WakeLockState {
+    sentinel: Option<JsValue>,
+    released: bool,
+    on_visibility_change: Option<Closure<dyn FnMut()>>
+}
+
+/// Prevents the display from dimming or going to sleep while it exists, which is useful for kiosk and presentation
+/// modes. Browsers automatically release wake locks when the page is hidden, so this type re-acquires the lock
+/// whenever the page becomes visible again.
+/// 
+/// The lock is released when release is called or when this struct is dropped.
+pub struct WakeLock {
+    state: Rc<RefCell<WakeLockState>>
+}
+
+impl WakeLock {
+
+    /// Checks if the wake lock is currently active. This will be false while the page is hidden.
+    pub fn is_active(&self) -> bool {
+        let state = self.state.borrow();
+        match &state.sentinel {
+            Some(sentinel) => !js_sys::Reflect::get(sentinel, &JsValue::from_str("released"))
+                .ok().and_then(|released| released.as_bool()).unwrap_or(true),
+            None => false
+        }
+    }
+
+    /// Releases the wake lock, allowing the display to sleep again.
+    pub fn release(self) {
+        // The actual work is done in drop
+    }
+}
+
+impl Drop for WakeLock {
+
+    fn drop(&mut self) {
+        let mut state = self.state.borrow_mut();
+        state.released = true;
+
+        if let Some(on_visibility_change) = state.on_visibility_change.take() {
+            if let Some(document) = web_sys::window().and_then(|window| window.document()) {
+                let _ = document.remove_event_listener_with_callback("visibilitychange", on_visibility_change.as_ref().unchecked_ref());
+            }
+        }
+
+        if let Some(sentinel) = state.sentinel.take() {
+            release_sentinel(&sentinel);
+        }
+    }
+}
+
+/// Checks if the browser supports the Screen Wake Lock API.
+pub fn supports_wake_lock() -> bool {
+    match web_sys::window() {
+        Some(window) => js_sys::Reflect::has(&window.navigator(), &JsValue::from_str("wakeLock")).unwrap_or(false),
+        None => false
+    }
+}
+
+/// Requests a screen wake lock, which prevents the display from going to sleep until the returned WakeLock is
+/// released or dropped. This can only succeed while the page is visible.
+pub async fn request_wake_lock() -> Result<WakeLock, WakeLockError> {
+    let sentinel = request_sentinel().await?;
+    let state = Rc::new(RefCell::new(WakeLockState {
+        sentinel: Some(sentinel),
+        released: false,
+        on_visibility_change: None
+    }));
+
+    let weak_state = Rc::downgrade(&state);
+    let on_visibility_change = Closure::wrap(Box::new(move || {
+        let document = web_sys::window().and_then(|window| window.document());
+        if document.map(|document| !document.hidden()).unwrap_or(false) {
+            reacquire(weak_state.clone());
+        }
+    }) as Box<dyn FnMut()>);
+
+    let document = web_sys::window().expect("There should be a window").document().expect("The window should have a document");
+    document.add_event_listener_with_callback("visibilitychange", on_visibility_change.as_ref().unchecked_ref())
+        .expect("Should be able to listen for visibilitychange");
+    state.borrow_mut().on_visibility_change = Some(on_visibility_change);
+
+    Ok(WakeLock {
+        state
+    })
+}
+
+fn reacquire(weak_state: Weak<RefCell<WakeLockState>>) {
+    spawn_local(async move {
+        // The request fails when the page became hidden again in the meantime, but then we will try again later
+        if let Ok(sentinel) = request_sentinel().await {
+            match weak_state.upgrade() {
+                Some(state) if !state.borrow().released => {
+                    let previous = state.borrow_mut().sentinel.replace(sentinel);
+                    if let Some(previous) = previous {
+                        release_sentinel(&previous);
+                    }
+                }, _ => release_sentinel(&sentinel)
+            }
+        }
+    });
+}
+
+async fn request_sentinel() -> Result<JsValue, WakeLockError> {
+    if !supports_wake_lock() {
+        return Err(WakeLockError::Unsupported);
+    }
+
+    // The Screen Wake Lock API is still unstable in web_sys, so it is accessed through reflection
+    let navigator = web_sys::window().expect("There should be a window").navigator();
+    let wake_lock = js_sys::Reflect::get(&navigator, &JsValue::from_str("wakeLock")).map_err(WakeLockError::Rejected)?;
+    let request = js_sys::Reflect::get(&wake_lock, &JsValue::from_str("request")).map_err(WakeLockError::Rejected)?
+        .dyn_into::<js_sys::Function>().map_err(|_| WakeLockError::Unsupported)?;
+    let promise = request.call1(&wake_lock, &JsValue::from_str("screen")).map_err(WakeLockError::Rejected)?
+        .dyn_into::<js_sys::Promise>().map_err(WakeLockError::Rejected)?;
+    JsFuture::from(promise).await.map_err(WakeLockError::Rejected)
+}
+
+fn release_sentinel(sentinel: &JsValue) {
+    let release = js_sys::Reflect::get(sentinel, &JsValue::from_str("release")).ok()
+        .and_then(|release| release.dyn_into::<js_sys::Function>().ok());
+    if let Some(release) = release {
+        let _ = release.call0(sentinel);
+    }
+}
@@ -0,0 +1,214 @@
+/// A handle to an element of an Arena. Handles stay valid until their element is removed, and a handle to a removed
+/// element will never refer to another element, even if the slot of the removed element is reused.
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+pub struct Handle {
+    index: u32,
+    generation: u32
+}
+
+enum Entry<T> {
+    Occupied {
+        generation: u32,
+        value: T
+    },
+    Free {
+        generation: u32,
+        next_free: Option<u32>
+    }
+}
+
+/// A generational arena: a collection that owns its elements and gives out Handles to them, with O(1) insertion,
+/// removal and lookup. Iterating over an Arena is much cheaper than iterating over a WeakVec, because there are no
+/// Weak pointers to upgrade and no RefCells to borrow, which makes this a good choice for component storage that is
+/// iterated every frame.
+pub struct Arena<T> {
+    entries: Vec<Entry<T>>,
+    free_head: Option<u32>,
+    len: usize
+}
+
+impl<T> Arena<T> {
+
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            free_head: None,
+            len: 0
+        }
+    }
+
+    pub fn with_capacity(initial_capacity: usize) -> Self {
+        Self {
+            entries: Vec::with_capacity(initial_capacity),
+            free_head: None,
+            len: 0
+        }
+    }
+
+    /// Adds the given value to this arena and returns a handle to it.
+    pub fn insert(&mut self, value: T) -> Handle {
+        self.len += 1;
+        match self.free_head {
+            Some(index) => {
+                let entry = &mut self.entries[index as usize];
+                let (generation, next_free) = match entry {
+                    Entry::Free { generation, next_free } => (*generation, *next_free),
+                    Entry::Occupied { .. } => unreachable!("The free list should only contain free entries")
+                };
+                *entry = Entry::Occupied {
+                    generation,
+                    value
+                };
+                self.free_head = next_free;
+                Handle {
+                    index,
+                    generation
+                }
+            }, None => {
+                let index = self.entries.len() as u32;
+                self.entries.push(Entry::Occupied {
+                    generation: 0,
+                    value
+                });
+                Handle {
+                    index,
+                    generation: 0
+                }
+            }
+        }
+    }
+
+    /// Removes the element with the given handle and returns it, or returns None if the element was already removed.
+    pub fn remove(&mut self, handle: Handle) -> Option<T> {
+        let entry = self.entries.get_mut(handle.index as usize)?;
+        match entry {
+            Entry::Occupied { generation, .. } if *generation == handle.generation => {
+                let old_entry = std::mem::replace(entry, Entry::Free {
+                    generation: handle.generation.wrapping_add(1),
+                    next_free: self.free_head
+                });
+                self.free_head = Some(handle.index);
+                self.len -= 1;
+                match old_entry {
+                    Entry::Occupied { value, .. } => Some(value),
+                    Entry::Free { .. } => unreachable!("The entry was just matched as occupied")
+                }
+            }, _ => None
+        }
+    }
+
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        match self.entries.get(handle.index as usize)? {
+            Entry::Occupied { generation, value } if *generation == handle.generation => Some(value),
+            _ => None
+        }
+    }
+
+    pub fn get_mut(&mut self, handle: Handle) -> Option<&mut T> {
+        match self.entries.get_mut(handle.index as usize)? {
+            Entry::Occupied { generation, value } if *generation == handle.generation => Some(value),
+            _ => None
+        }
+    }
+
+    /// Checks if the element with the given handle is still in this arena.
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.get(handle).is_some()
+    }
+
+    /// Returns the number of elements in this arena.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterates over the elements of this arena (and their handles). The iteration order is the order of the slots,
+    /// which is not necessarily the insertion order because slots of removed elements are reused.
+    pub fn iter(&self) -> impl Iterator<Item = (Handle, &T)> {
+        self.entries.iter().enumerate().filter_map(|(index, entry)| match entry {
+            Entry::Occupied { generation, value } => Some((Handle { index: index as u32, generation: *generation }, value)),
+            Entry::Free { .. } => None
+        })
+    }
+
+    /// Like iter, but gives mutable access to the elements.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Handle, &mut T)> {
+        self.entries.iter_mut().enumerate().filter_map(|(index, entry)| match entry {
+            Entry::Occupied { generation, value } => Some((Handle { index: index as u32, generation: *generation }, value)),
+            Entry::Free { .. } => None
+        })
+    }
+}
+
+impl<T> Default for Arena<T> {
+
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_insert_and_get() {
+
+        let mut arena = Arena::new();
+        assert!(arena.is_empty());
+
+        let a = arena.insert("a");
+        let b = arena.insert("b");
+        assert_eq!(2, arena.len());
+        assert_eq!(Some(&"a"), arena.get(a));
+        assert_eq!(Some(&"b"), arena.get(b));
+
+        *arena.get_mut(a).unwrap() = "c";
+        assert_eq!(Some(&"c"), arena.get(a));
+    }
+
+    #[test]
+    fn test_remove() {
+
+        let mut arena = Arena::with_capacity(2);
+
+        let a = arena.insert(1);
+        let b = arena.insert(2);
+        assert_eq!(Some(1), arena.remove(a));
+        assert_eq!(None, arena.remove(a));
+        assert!(!arena.contains(a));
+        assert!(arena.contains(b));
+        assert_eq!(1, arena.len());
+
+        // The slot of a should be reused, but the old handle should stay invalid
+        let c = arena.insert(3);
+        assert_eq!(a.index, c.index);
+        assert_ne!(a, c);
+        assert_eq!(None, arena.get(a));
+        assert_eq!(Some(&3), arena.get(c));
+        assert_eq!(2, arena.len());
+    }
+
+    #[test]
+    fn test_iter() {
+
+        let mut arena = Arena::new();
+
+        let handles: Vec<Handle> = (0..5).map(|number| arena.insert(number)).collect();
+        arena.remove(handles[1]);
+        arena.remove(handles[3]);
+
+        let values: Vec<i32> = arena.iter().map(|(_, value)| *value).collect();
+        assert_eq!(vec![0, 2, 4], values);
+
+        for (handle, value) in arena.iter_mut() {
+            assert!(handles.contains(&handle));
+            *value *= 10;
+        }
+        assert_eq!(Some(&40), arena.get(handles[4]));
+    }
+}
@@ -0,0 +1,71 @@
+use std::fmt::Arguments;
+
+use crate::error;
+
+/// Checks that the condition is true, and logs an error (with the file, line and expression) to the console if it is
+/// not. Unlike assert!, this doesn't panic: a panic takes down the entire app, which is usually worse than a glitch
+/// in a single component. The macro evaluates to the value of the condition, so the caller can still bail out, for
+/// instance with `if !wasmuri_assert!(index < len) { return; }`. An additional message can be given in the same
+/// format as with assert!.
+#[macro_export]
+macro_rules! wasmuri_assert {
+    ($condition:expr $(,)?) => {{
+        let passed: bool = $condition;
+        if !passed {
+            $crate::report_assertion_failure(file!(), line!(), stringify!($condition), None);
+        }
+        passed
+    }};
+    ($condition:expr, $($message:tt)+) => {{
+        let passed: bool = $condition;
+        if !passed {
+            $crate::report_assertion_failure(file!(), line!(), stringify!($condition), Some(format_args!($($message)+)));
+        }
+        passed
+    }};
+}
+
+/// Like wasmuri_assert!, but the condition is only checked in debug builds (just like debug_assert!). In release
+/// builds, the condition is not evaluated and the macro evaluates to true.
+#[macro_export]
+macro_rules! wasmuri_debug_assert {
+    ($($arguments:tt)+) => {
+        !cfg!(debug_assertions) || $crate::wasmuri_assert!($($arguments)+)
+    };
+}
+
+/// Logs the failed assertion with util::error. This is used by the assertion macros.
+#[doc(hidden)]
+pub fn report_assertion_failure(file: &str, line: u32, expression: &str, message: Option<Arguments>) {
+    error(&assertion_message(file, line, expression, message));
+}
+
+fn assertion_message(file: &str, line: u32, expression: &str, message: Option<Arguments>) -> String {
+    match message {
+        Some(message) => format!("Assertion failed at {}:{}: {}: {}", file, line, expression, message),
+        None => format!("Assertion failed at {}:{}: {}", file, line, expression)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_assertion_message() {
+        assert_eq!("Assertion failed at src/lib.rs:12: a < b", assertion_message("src/lib.rs", 12, "a < b", None));
+        assert_eq!(
+            "Assertion failed at src/lib.rs:3: x.is_some(): x should be set after 5 frames",
+            assertion_message("src/lib.rs", 3, "x.is_some()", Some(format_args!("x should be set after {} frames", 5)))
+        );
+    }
+
+    #[test]
+    fn test_passed_assertions() {
+        let len = 3;
+        assert!(wasmuri_assert!(1 < len));
+        assert!(wasmuri_assert!(2 < len, "index {} out of bounds", 2));
+        assert!(wasmuri_debug_assert!(0 < len));
+    }
+}
@@ -0,0 +1,91 @@
+use crate::WeakVec;
+
+use std::cell::RefCell;
+use std::rc::Weak;
+
+/// A list of weakly referenced callbacks that all take arguments of type Args. Callbacks whose last strong reference
+/// has been dropped are removed automatically during invoke_all. Unlike Listeners, the caller owns the callbacks.
+pub struct CallbackList<Args: 'static> {
+
+    callbacks: WeakVec<dyn FnMut(Args)>
+}
+
+impl<Args: 'static> CallbackList<Args> {
+
+    pub fn new() -> Self {
+        Self {
+            callbacks: WeakVec::new()
+        }
+    }
+
+    /// Adds the given callback to this list, unless it is already in this list.
+    pub fn add(&mut self, callback: Weak<RefCell<dyn FnMut(Args)>>) {
+        if !self.callbacks.contains(&callback) {
+            self.callbacks.push(callback);
+        }
+    }
+
+    /// Removes the given callback from this list. Returns true if it was in this list.
+    pub fn remove(&mut self, callback: &Weak<RefCell<dyn FnMut(Args)>>) -> bool {
+        self.callbacks.remove(callback)
+    }
+
+    /// Calls each callback that is still alive with a clone of args, in the order in which they were added.
+    pub fn invoke_all(&mut self, args: Args) where Args: Clone {
+        self.callbacks.for_each_mut(|callback| callback(args.clone()));
+    }
+
+    /// Counts the number of callbacks that are still alive.
+    pub fn live_count(&self) -> usize {
+        self.callbacks.live_count()
+    }
+
+    /// Returns true if none of the callbacks is still alive.
+    pub fn is_empty(&self) -> bool {
+        self.live_count() == 0
+    }
+}
+
+impl<Args: 'static> Default for CallbackList<Args> {
+
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use std::rc::Rc;
+
+    type Adder = dyn FnMut((u32, u32));
+
+    #[test]
+    fn test_invoke_all() {
+
+        let mut callbacks = CallbackList::new();
+        let sum = Rc::new(RefCell::new(0));
+
+        let sum1 = Rc::clone(&sum);
+        let callback1: Rc<RefCell<Adder>> = Rc::new(RefCell::new(move |(a, b)| *sum1.borrow_mut() += a + b));
+        let sum2 = Rc::clone(&sum);
+        let callback2: Rc<RefCell<Adder>> = Rc::new(RefCell::new(move |(a, _)| *sum2.borrow_mut() += a));
+
+        callbacks.add(Rc::downgrade(&callback1));
+        callbacks.add(Rc::downgrade(&callback1));
+        callbacks.add(Rc::downgrade(&callback2));
+        assert_eq!(2, callbacks.live_count());
+
+        callbacks.invoke_all((1, 2));
+        assert_eq!(4, *sum.borrow());
+
+        drop(callback2);
+        callbacks.invoke_all((1, 2));
+        assert_eq!(7, *sum.borrow());
+
+        assert!(callbacks.remove(&Rc::downgrade(&callback1)));
+        assert!(callbacks.is_empty());
+    }
+}
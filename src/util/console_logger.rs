@@ -0,0 +1,81 @@
+use log::{
+    Level,
+    LevelFilter,
+    Log,
+    Metadata,
+    Record,
+    SetLoggerError
+};
+
+use crate::{
+    LogLevel,
+    log_enabled_for,
+    log_target,
+    set_log_level
+};
+
+/// The logger that routes the messages of the log crate to log_target (and thus to the browser console).
+struct ConsoleLogger;
+
+static LOGGER: ConsoleLogger = ConsoleLogger;
+
+impl Log for ConsoleLogger {
+
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        log_enabled_for(metadata.target(), to_log_level(metadata.level()))
+    }
+
+    fn log(&self, record: &Record) {
+        // Check this first to avoid formatting messages that will be ignored
+        if self.enabled(record.metadata()) {
+            log_target(to_log_level(record.level()), record.target(), &record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Installs the logger for the log crate, so that the log::info!, log::warn!, ... macros of any crate are logged to the
+/// browser console, prefixed with their target (usually the module path). The global log level is set to the given
+/// level, and set_log_level and set_log_filter will keep the log crate in sync afterwards. This fails if another
+/// logger was installed already.
+pub fn init_logger(level: LogLevel) -> Result<(), SetLoggerError> {
+    log::set_logger(&LOGGER)?;
+    set_log_level(level);
+    Ok(())
+}
+
+pub(crate) fn to_level_filter(level: LogLevel) -> LevelFilter {
+    match level {
+        LogLevel::Off => LevelFilter::Off,
+        LogLevel::Error => LevelFilter::Error,
+        LogLevel::Warn => LevelFilter::Warn,
+        LogLevel::Info => LevelFilter::Info,
+        LogLevel::Debug => LevelFilter::Debug,
+        LogLevel::Trace => LevelFilter::Trace
+    }
+}
+
+fn to_log_level(level: Level) -> LogLevel {
+    match level {
+        Level::Error => LogLevel::Error,
+        Level::Warn => LogLevel::Warn,
+        Level::Info => LogLevel::Info,
+        Level::Debug => LogLevel::Debug,
+        Level::Trace => LogLevel::Trace
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_level_conversions() {
+        for level in [Level::Error, Level::Warn, Level::Info, Level::Debug, Level::Trace].iter() {
+            assert_eq!(*level, to_level_filter(to_log_level(*level)));
+        }
+        assert_eq!(LevelFilter::Off, to_level_filter(LogLevel::Off));
+    }
+}
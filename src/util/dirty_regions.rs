@@ -0,0 +1,91 @@
+use crate::{
+    LogLevel,
+    Region,
+    log_enabled_for,
+    log_target
+};
+
+/// The log target of the dirty region tracking, which can be used to trace which regions are marked dirty (see
+/// set_log_filter)
+pub const REGION_LOG_TARGET: &str = "region";
+
+/// Collects the regions of the viewport that changed since the previous frame, so that only those need to be redrawn.
+/// Regions that are covered by another dirty region are dropped, so the same pixels are not redrawn twice when one
+/// component changes inside another one that changed as well.
+#[derive(Clone,Debug,Default)]
+pub struct DirtyRegionTracker {
+    regions: Vec<Region>
+}
+
+impl DirtyRegionTracker {
+
+    pub fn new() -> DirtyRegionTracker {
+        DirtyRegionTracker {
+            regions: Vec::new()
+        }
+    }
+
+    /// Marks the region as changed.
+    pub fn mark_dirty(&mut self, region: Region) {
+        if log_enabled_for(REGION_LOG_TARGET, LogLevel::Trace) {
+            log_target(LogLevel::Trace, REGION_LOG_TARGET, &format!("Marking {:?} dirty", region));
+        }
+        if self.regions.iter().any(|dirty| region.is_covered_by(*dirty)) {
+            return;
+        }
+        self.regions.retain(|dirty| !dirty.is_covered_by(region));
+        self.regions.push(region);
+    }
+
+    /// Marks the entire viewport as changed, for instance after the canvas was resized or the context was restored.
+    pub fn mark_all_dirty(&mut self) {
+        if log_enabled_for(REGION_LOG_TARGET, LogLevel::Trace) {
+            log_target(LogLevel::Trace, REGION_LOG_TARGET, "Marking the entire viewport dirty");
+        }
+        self.regions.clear();
+        self.regions.push(Region::entire_viewport());
+    }
+
+    /// Returns true if nothing changed since the previous call to take.
+    pub fn is_clean(&self) -> bool {
+        self.regions.is_empty()
+    }
+
+    /// Gets the current dirty regions.
+    pub fn get_regions(&self) -> &[Region] {
+        &self.regions
+    }
+
+    /// Gets the dirty regions and clears them, which should be done once per frame.
+    pub fn take(&mut self) -> Vec<Region> {
+        std::mem::take(&mut self.regions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_mark_dirty() {
+        let mut tracker = DirtyRegionTracker::new();
+        assert!(tracker.is_clean());
+
+        tracker.mark_dirty(Region::new(0, 0, 100, 100));
+        tracker.mark_dirty(Region::new(10, 10, 20, 20));
+        assert_eq!(&[Region::new(0, 0, 100, 100)], tracker.get_regions());
+
+        tracker.mark_dirty(Region::new(500, 500, 600, 600));
+        tracker.mark_dirty(Region::new(-10, -10, 200, 200));
+        assert_eq!(&[Region::new(500, 500, 600, 600), Region::new(-10, -10, 200, 200)], tracker.get_regions());
+
+        assert_eq!(2, tracker.take().len());
+        assert!(tracker.is_clean());
+
+        tracker.mark_dirty(Region::new(0, 0, 100, 100));
+        tracker.mark_all_dirty();
+        tracker.mark_dirty(Region::new(0, 0, 100, 100));
+        assert_eq!(vec![Region::entire_viewport()], tracker.take());
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::VecDeque;
+
+use crate::{
+    now,
+    print
+};
+
+/// Collects the CPU time spent on the most recent frames, so that the effect of optimizations (like only redrawing
+/// the parts of the screen that changed) can be measured. Only the last `window_size` frames are remembered, so
+/// all statistics are rolling statistics.
+///
+/// Call begin_frame at the start of each frame and end_frame at the end of it. Alternatively, record can be used to
+/// add frame times that were measured in another way.
+pub struct FrameStats {
+
+    frame_times: VecDeque<f64>,
+    frame_intervals: VecDeque<f64>,
+    window_size: usize,
+
+    current_frame_start: Option<f64>,
+    last_frame_start: Option<f64>,
+    total_frames: u64
+}
+
+impl FrameStats {
+
+    /// Creates a new FrameStats that keeps track of the last window_size frames. A window_size of 0 is treated as 1.
+    pub fn new(window_size: usize) -> FrameStats {
+        let window_size = window_size.max(1);
+        FrameStats {
+            frame_times: VecDeque::with_capacity(window_size),
+            frame_intervals: VecDeque::with_capacity(window_size),
+            window_size,
+            current_frame_start: None,
+            last_frame_start: None,
+            total_frames: 0
+        }
+    }
+
+    /// Marks the start of a new frame
+    pub fn begin_frame(&mut self) {
+        self.begin_frame_at(now());
+    }
+
+    /// Marks the end of the current frame and returns its duration in milliseconds. If begin_frame wasn't called
+    /// first, nothing will be recorded and None will be returned.
+    pub fn end_frame(&mut self) -> Option<f64> {
+        self.end_frame_at(now())
+    }
+
+    fn begin_frame_at(&mut self, time: f64) {
+        if let Some(last_start) = self.last_frame_start {
+            push_rolling(&mut self.frame_intervals, time - last_start, self.window_size);
+        }
+        self.last_frame_start = Some(time);
+        self.current_frame_start = Some(time);
+    }
+
+    fn end_frame_at(&mut self, time: f64) -> Option<f64> {
+        let start = self.current_frame_start.take()?;
+        let duration = time - start;
+        self.record(duration);
+        Some(duration)
+    }
+
+    /// Records a frame that took frame_time milliseconds of CPU time
+    pub fn record(&mut self, frame_time: f64) {
+        push_rolling(&mut self.frame_times, frame_time, self.window_size);
+        self.total_frames += 1;
+    }
+
+    /// Gets the number of frames whose times are currently remembered (at most the window size)
+    pub fn len(&self) -> usize {
+        self.frame_times.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frame_times.is_empty()
+    }
+
+    /// Gets the total number of frames that have been recorded since the creation of this FrameStats (or the last
+    /// call to clear), including the frames that are no longer remembered.
+    pub fn get_total_frames(&self) -> u64 {
+        self.total_frames
+    }
+
+    /// Gets the average frame time of the remembered frames, in milliseconds
+    pub fn average(&self) -> Option<f64> {
+        if self.frame_times.is_empty() {
+            None
+        } else {
+            Some(self.frame_times.iter().sum::<f64>() / self.frame_times.len() as f64)
+        }
+    }
+
+    /// Gets the longest frame time of the remembered frames, in milliseconds
+    pub fn max(&self) -> Option<f64> {
+        self.frame_times.iter().copied().fold(None, |max, time| Some(max.map_or(time, |max: f64| max.max(time))))
+    }
+
+    /// Gets the given percentile (between 0.0 and 100.0) of the remembered frame times, in milliseconds. For
+    /// instance, percentile(99.0) returns the frame time that 99% of the remembered frames didn't exceed. This uses
+    /// the nearest-rank method, so the result is always one of the recorded frame times.
+    pub fn percentile(&self, percentile: f64) -> Option<f64> {
+        if self.frame_times.is_empty() {
+            return None;
+        }
+
+        let mut sorted: Vec<f64> = self.frame_times.iter().copied().collect();
+        sorted.sort_by(f64::total_cmp);
+
+        let fraction = percentile.clamp(0.0, 100.0) / 100.0;
+        let rank = (fraction * sorted.len() as f64).ceil() as usize;
+        Some(sorted[rank.max(1) - 1])
+    }
+
+    /// Estimates the number of frames per second, based on the time between the starts of the remembered frames.
+    /// Unlike the frame times, this includes the time the browser spent between the frames.
+    pub fn fps(&self) -> Option<f64> {
+        if self.frame_intervals.is_empty() {
+            return None;
+        }
+
+        let average_interval = self.frame_intervals.iter().sum::<f64>() / self.frame_intervals.len() as f64;
+        if average_interval > 0.0 {
+            Some(1000.0 / average_interval)
+        } else {
+            None
+        }
+    }
+
+    /// Forgets all recorded frames
+    pub fn clear(&mut self) {
+        self.frame_times.clear();
+        self.frame_intervals.clear();
+        self.current_frame_start = None;
+        self.last_frame_start = None;
+        self.total_frames = 0;
+    }
+
+    /// Creates a one-line summary of these statistics
+    pub fn summary(&self) -> String {
+        match (self.average(), self.percentile(50.0), self.percentile(99.0), self.max()) {
+            (Some(average), Some(median), Some(p99), Some(max)) => {
+                let fps = self.fps().map_or("?".to_string(), |fps| format!("{:.1}", fps));
+                format!("{} frames: avg {:.2}ms, p50 {:.2}ms, p99 {:.2}ms, max {:.2}ms, {} fps",
+                    self.len(), average, median, p99, max, fps)
+            },
+            _ => "No frames recorded".to_string()
+        }
+    }
+
+    /// Prints the summary of these statistics to the console
+    pub fn print(&self) {
+        print(&self.summary());
+    }
+}
+
+fn push_rolling(values: &mut VecDeque<f64>, value: f64, window_size: usize) {
+    if values.len() == window_size {
+        values.pop_front();
+    }
+    values.push_back(value);
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_rolling_window() {
+        let mut stats = FrameStats::new(3);
+        assert!(stats.is_empty());
+        assert_eq!(None, stats.average());
+
+        for time in &[10.0, 2.0, 4.0, 6.0] {
+            stats.record(*time);
+        }
+
+        assert_eq!(3, stats.len());
+        assert_eq!(4, stats.get_total_frames());
+        assert_eq!(Some(4.0), stats.average());
+        assert_eq!(Some(6.0), stats.max());
+    }
+
+    #[test]
+    fn test_percentile() {
+        let mut stats = FrameStats::new(100);
+        for time in 1..=100 {
+            stats.record(time as f64);
+        }
+
+        assert_eq!(Some(1.0), stats.percentile(0.0));
+        assert_eq!(Some(50.0), stats.percentile(50.0));
+        assert_eq!(Some(99.0), stats.percentile(99.0));
+        assert_eq!(Some(100.0), stats.percentile(100.0));
+
+        // A NaN frame time (from a broken clock) is sorted after all other times instead of causing a panic
+        // The window is full, so this pushes out the frame time 1
+        stats.record(f64::NAN);
+        assert_eq!(Some(51.0), stats.percentile(50.0));
+    }
+
+    #[test]
+    fn test_frame_timing() {
+        let mut stats = FrameStats::new(10);
+        assert_eq!(None, stats.end_frame_at(5.0));
+
+        stats.begin_frame_at(0.0);
+        assert_eq!(Some(4.0), stats.end_frame_at(4.0));
+        assert_eq!(None, stats.fps());
+
+        stats.begin_frame_at(20.0);
+        stats.end_frame_at(22.0);
+        assert_eq!(Some(50.0), stats.fps());
+        assert_eq!(Some(3.0), stats.average());
+
+        stats.clear();
+        assert!(stats.is_empty());
+        assert_eq!("No frames recorded", stats.summary());
+    }
+}
@@ -0,0 +1,138 @@
+use crate::WeakVec;
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+type Callback<E> = RefCell<dyn FnMut(&E)>;
+
+/// A registry of listeners for events of type E. Unlike a plain WeakVec, the caller doesn't need to keep an Rc alive:
+/// subscribe returns a Subscription guard that owns the listener, and the listener is removed as soon as that guard
+/// is dropped.
+pub struct Listeners<E: 'static> {
+
+    listeners: WeakVec<dyn FnMut(&E)>
+}
+
+/// Keeps a listener (that was registered by Listeners::subscribe) alive. Dropping it unsubscribes the listener.
+#[must_use = "The listener is removed as soon as its Subscription is dropped"]
+pub struct Subscription {
+
+    _listener: Box<dyn Any>
+}
+
+impl<E: 'static> Listeners<E> {
+
+    pub fn new() -> Self {
+        Self {
+            listeners: WeakVec::new()
+        }
+    }
+
+    /// Registers the given callback, which will be called during each subsequent notify until the returned Subscription
+    /// is dropped.
+    pub fn subscribe<F: FnMut(&E) + 'static>(&mut self, callback: F) -> Subscription {
+        let listener: Rc<Callback<E>> = Rc::new(RefCell::new(callback));
+        self.listeners.push(Rc::downgrade(&listener));
+        Subscription {
+            _listener: Box::new(listener)
+        }
+    }
+
+    /// Calls all listeners whose Subscription hasn't been dropped yet, in the order in which they subscribed.
+    pub fn notify(&mut self, event: &E) {
+        self.listeners.for_each_mut(|listener| listener(event));
+    }
+
+    /// Like notify, but the Listeners are only borrowed to take a snapshot of the listeners, so the listeners can
+    /// subscribe new listeners or notify the same Listeners again. New listeners are not called until the next
+    /// notification, and a listener that is already running is skipped by nested notifications.
+    pub fn notify_shared(shared: &RefCell<Self>, event: &E) {
+        let snapshot: Vec<_> = shared.borrow().listeners.iter_upgraded().collect();
+        for listener in snapshot {
+            if let Ok(mut listener) = listener.try_borrow_mut() {
+                listener(event);
+            }
+        }
+
+        // The Listeners may still be borrowed when this is a nested call
+        if let Ok(mut listeners) = shared.try_borrow_mut() {
+            listeners.listeners.prune();
+        }
+    }
+
+    /// Counts the number of listeners whose Subscription is still alive.
+    pub fn live_count(&self) -> usize {
+        self.listeners.live_count()
+    }
+
+    /// Returns true if there are no listeners whose Subscription is still alive.
+    pub fn is_empty(&self) -> bool {
+        self.live_count() == 0
+    }
+}
+
+impl<E: 'static> Default for Listeners<E> {
+
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_subscription_guard() {
+
+        let mut listeners = Listeners::new();
+        let received = Rc::new(RefCell::new(Vec::new()));
+
+        let received1 = Rc::clone(&received);
+        let subscription1 = listeners.subscribe(move |event: &u32| received1.borrow_mut().push(*event));
+        let received2 = Rc::clone(&received);
+        let subscription2 = listeners.subscribe(move |event: &u32| received2.borrow_mut().push(*event * 10));
+        assert_eq!(2, listeners.live_count());
+
+        listeners.notify(&1);
+        assert_eq!(vec![1, 10], *received.borrow());
+
+        drop(subscription1);
+        listeners.notify(&2);
+        assert_eq!(vec![1, 10, 20], *received.borrow());
+
+        drop(subscription2);
+        assert!(listeners.is_empty());
+        listeners.notify(&3);
+        assert_eq!(vec![1, 10, 20], *received.borrow());
+    }
+
+    #[test]
+    fn test_notify_shared() {
+
+        let listeners = Rc::new(RefCell::new(Listeners::new()));
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let subscriptions = Rc::new(RefCell::new(Vec::new()));
+
+        let (inner_listeners, inner_received, inner_subscriptions) = (Rc::clone(&listeners), Rc::clone(&received), Rc::clone(&subscriptions));
+        let _subscription = listeners.borrow_mut().subscribe(move |event: &u32| {
+            inner_received.borrow_mut().push(*event);
+            if *event == 1 {
+                // Both subscribing and notifying again are allowed from within a listener
+                let late_received = Rc::clone(&inner_received);
+                let late = inner_listeners.borrow_mut().subscribe(move |event: &u32| late_received.borrow_mut().push(*event * 10));
+                inner_subscriptions.borrow_mut().push(late);
+                Listeners::notify_shared(&inner_listeners, &2);
+            }
+        });
+
+        Listeners::notify_shared(&listeners, &1);
+        assert_eq!(vec![1, 20], *received.borrow());
+        assert_eq!(2, listeners.borrow().live_count());
+
+        Listeners::notify_shared(&listeners, &3);
+        assert_eq!(vec![1, 20, 3, 30], *received.borrow());
+    }
+}
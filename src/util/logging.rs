@@ -0,0 +1,331 @@
+use std::sync::Mutex;
+use std::sync::atomic::{
+    AtomicU8,
+    Ordering
+};
+
+use web_sys::console;
+
+use wasm_bindgen::JsValue;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// The importance of a log message. Messages are only logged if their level is at most the global level (see
+/// set_log_level), so Error messages are always logged unless logging is Off.
+#[derive(Clone,Copy,Debug,PartialEq,Eq,PartialOrd,Ord,Hash)]
+pub enum LogLevel {
+
+    /// Disables all logging (this is only meaningful as global level)
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace
+}
+
+impl LogLevel {
+
+    const ALL: [LogLevel; 6] = [LogLevel::Off, LogLevel::Error, LogLevel::Warn, LogLevel::Info, LogLevel::Debug, LogLevel::Trace];
+
+    /// The global level that is used until set_log_level is called: Debug in debug builds and Info in release builds
+    pub const DEFAULT: LogLevel = if cfg!(debug_assertions) { LogLevel::Debug } else { LogLevel::Info };
+
+    /// Gets the name of this level, which is used in log filters
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            LogLevel::Off => "off",
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+            LogLevel::Trace => "trace"
+        }
+    }
+
+    /// Gets the level with the given name (ignoring case), or None if there is no such level
+    pub fn from_name(name: &str) -> Option<LogLevel> {
+        LogLevel::ALL.iter().copied().find(|level| level.get_name().eq_ignore_ascii_case(name))
+    }
+}
+
+/// The reasons why set_log_filter can fail.
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub enum LogFilterError {
+    /// The filter contains a level name that doesn't exist
+    UnknownLevel(String),
+    /// The filter contains an entry with an empty target (like "=debug")
+    EmptyTarget
+}
+
+impl std::fmt::Display for LogFilterError {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            LogFilterError::UnknownLevel(name) => write!(f, "Unknown log level {:?}", name),
+            LogFilterError::EmptyTarget => write!(f, "The log filter contains an empty target")
+        }
+    }
+}
+
+impl std::error::Error for LogFilterError {}
+
+static LOG_LEVEL: AtomicU8 = AtomicU8::new(LogLevel::DEFAULT as u8);
+
+// The levels of the targets that deviate from the global level
+static TARGET_LEVELS: Mutex<Vec<(String, LogLevel)>> = Mutex::new(Vec::new());
+
+/// Sets the global log level: messages that are less important than this level will be ignored. This makes it
+/// possible to keep verbose diagnostics in the code, but silence them in production.
+pub fn set_log_level(level: LogLevel) {
+    LOG_LEVEL.store(level as u8, Ordering::Relaxed);
+    sync_max_level();
+}
+
+pub fn get_log_level() -> LogLevel {
+    LogLevel::ALL[LOG_LEVEL.load(Ordering::Relaxed) as usize]
+}
+
+/// Checks whether messages of the given level would currently be logged. This can be used to skip computing expensive
+/// log messages.
+pub fn log_enabled(level: LogLevel) -> bool {
+    level != LogLevel::Off && level <= get_log_level()
+}
+
+/// Sets the level of a target: messages of that target (or of its sub-targets, like "compositor::layers" for the
+/// target "compositor") are logged if their level is at most the target level, regardless of the global level. This
+/// makes it possible to turn on verbose logging for only the subsystem that is being debugged.
+pub fn set_target_log_level(target: &str, level: LogLevel) {
+    let mut targets = target_levels();
+    targets.retain(|(existing, _)| existing != target);
+    targets.push((target.to_string(), level));
+    drop(targets);
+    sync_max_level();
+}
+
+/// Removes the levels of all targets, so that the global level applies to all messages again.
+pub fn clear_target_log_levels() {
+    target_levels().clear();
+    sync_max_level();
+}
+
+/// Sets the global level and the target levels from a comma-separated filter. Each entry is either a level name (like
+/// "warn"), which sets the global level, or a target with a level (like "compositor=trace"). The target levels
+/// replace all previous target levels. For instance, "warn,compositor=trace,region=debug" only logs warnings and
+/// errors, except for the compositor and region subsystems. Nothing is changed if the filter is invalid.
+pub fn set_log_filter(filter: &str) -> Result<(), LogFilterError> {
+    let (global_level, targets) = parse_log_filter(filter)?;
+    if let Some(level) = global_level {
+        LOG_LEVEL.store(level as u8, Ordering::Relaxed);
+    }
+    *target_levels() = targets;
+    sync_max_level();
+    Ok(())
+}
+
+/// Calls set_log_filter, and is exported to JavaScript as wasmuriSetLogFilter so that the log filter can be changed
+/// while the app is running, for instance with `wasmuriSetLogFilter("info,compositor=trace,region=trace")`. Like all
+/// wasm-bindgen exports, it is a member of the generated module rather than of window, so the app needs to expose it
+/// (for instance with `window.wasmuriSetLogFilter = wasm.wasmuriSetLogFilter`) to make it usable from the browser
+/// console.
+#[wasm_bindgen(js_name = wasmuriSetLogFilter)]
+pub fn set_log_filter_js(filter: &str) -> Result<(), JsValue> {
+    set_log_filter(filter).map_err(|error| JsValue::from_str(&error.to_string()))
+}
+
+/// Gets the level that applies to messages of the given target: the level of the most specific target that it belongs
+/// to, or the global level if it doesn't belong to any target with its own level.
+pub fn get_target_log_level(target: &str) -> LogLevel {
+    target_level(&target_levels(), target, get_log_level())
+}
+
+/// Checks whether messages of the given target and level would currently be logged.
+pub fn log_enabled_for(target: &str, level: LogLevel) -> bool {
+    level != LogLevel::Off && level <= get_target_log_level(target)
+}
+
+/// Logs the message with the console method of its level (like warn for LogLevel::Warn), prefixed with its target,
+/// if it passes the level of its target.
+pub fn log_target(level: LogLevel, target: &str, message: &str) {
+    if !log_enabled_for(target, level) {
+        return;
+    }
+    let message = JsValue::from_str(&format!("[{}] {}", target, message));
+    match level {
+        LogLevel::Off => {},
+        LogLevel::Error => console::error_1(&message),
+        LogLevel::Warn => console::warn_1(&message),
+        LogLevel::Info => console::log_1(&message),
+        LogLevel::Debug => console::debug_1(&message),
+        LogLevel::Trace => console::trace_1(&message)
+    }
+}
+
+fn target_levels() -> std::sync::MutexGuard<'static, Vec<(String, LogLevel)>> {
+    TARGET_LEVELS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Lets the log crate skip the messages that would be ignored anyway
+fn sync_max_level() {
+    #[cfg(feature = "log")]
+    {
+        let max_level = target_levels().iter().map(|(_, level)| *level).fold(get_log_level(), LogLevel::max);
+        log::set_max_level(crate::to_level_filter(max_level));
+    }
+}
+
+type ParsedLogFilter = (Option<LogLevel>, Vec<(String, LogLevel)>);
+
+fn parse_log_filter(filter: &str) -> Result<ParsedLogFilter, LogFilterError> {
+    let parse_level = |name: &str| LogLevel::from_name(name.trim()).ok_or_else(|| LogFilterError::UnknownLevel(name.trim().to_string()));
+
+    let mut global_level = None;
+    let mut targets: Vec<(String, LogLevel)> = Vec::new();
+    for entry in filter.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+        match entry.split_once('=') {
+            Some((target, level)) => {
+                let target = target.trim();
+                if target.is_empty() {
+                    return Err(LogFilterError::EmptyTarget);
+                }
+                let level = parse_level(level)?;
+                targets.retain(|(existing, _)| existing != target);
+                targets.push((target.to_string(), level));
+            },
+            None => global_level = Some(parse_level(entry)?)
+        }
+    }
+    Ok((global_level, targets))
+}
+
+fn target_level(targets: &[(String, LogLevel)], target: &str, global_level: LogLevel) -> LogLevel {
+    let belongs_to = |prefix: &str| target == prefix || (target.starts_with(prefix) && target[prefix.len()..].starts_with("::"));
+    targets.iter().filter(|(prefix, _)| belongs_to(prefix)).max_by_key(|(prefix, _)| prefix.len())
+        .map_or(global_level, |(_, level)| *level)
+}
+
+/// Logs the message with console.log (at the Info level).
+pub fn print(message: &str){
+    if log_enabled(LogLevel::Info) {
+        console::log_1(&JsValue::from_str(message));
+    }
+}
+
+/// Logs the message with console.error (at the Error level).
+pub fn error(message: &str) {
+    if log_enabled(LogLevel::Error) {
+        console::error_1(&JsValue::from_str(message));
+    }
+}
+
+/// Logs the message with console.warn (at the Warn level).
+pub fn warn(message: &str) {
+    if log_enabled(LogLevel::Warn) {
+        console::warn_1(&JsValue::from_str(message));
+    }
+}
+
+/// Logs the message with console.debug (at the Debug level). Note that browsers hide these messages unless the
+/// verbose messages are enabled in the console.
+pub fn debug(message: &str) {
+    if log_enabled(LogLevel::Debug) {
+        console::debug_1(&JsValue::from_str(message));
+    }
+}
+
+/// Logs the message with console.trace (at the Trace level), which includes the JavaScript stack trace.
+pub fn trace(message: &str) {
+    if log_enabled(LogLevel::Trace) {
+        console::trace_1(&JsValue::from_str(message));
+    }
+}
+
+/// Starts a console group with the given label: all following console messages are indented under the label until the
+/// returned guard is dropped. Groups can be nested.
+pub fn group(label: &str) -> ConsoleGroup {
+    ConsoleGroup::start(label, false)
+}
+
+/// Like group, but the group is collapsed by default, so its messages are only shown after clicking on its label.
+pub fn group_collapsed(label: &str) -> ConsoleGroup {
+    ConsoleGroup::start(label, true)
+}
+
+/// Ends its console group when it is dropped. Nothing happens when logging is Off.
+#[must_use = "The console group ends immediately if the guard is not kept"]
+pub struct ConsoleGroup {
+    started: bool
+}
+
+impl ConsoleGroup {
+
+    fn start(label: &str, collapsed: bool) -> ConsoleGroup {
+        let started = log_enabled(LogLevel::Error);
+        if started {
+            let label = JsValue::from_str(label);
+            if collapsed {
+                console::group_collapsed_1(&label);
+            } else {
+                console::group_1(&label);
+            }
+        }
+        ConsoleGroup { started }
+    }
+}
+
+impl Drop for ConsoleGroup {
+
+    fn drop(&mut self) {
+        if self.started {
+            console::group_end();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_log_level_filter() {
+        assert!(LogLevel::Error < LogLevel::Warn && LogLevel::Debug < LogLevel::Trace);
+        for level in LogLevel::ALL.iter() {
+            assert_eq!(*level, LogLevel::ALL[*level as usize]);
+        }
+
+        set_log_level(LogLevel::Warn);
+        assert_eq!(LogLevel::Warn, get_log_level());
+        assert!(log_enabled(LogLevel::Error) && log_enabled(LogLevel::Warn));
+        assert!(!log_enabled(LogLevel::Info));
+
+        set_log_level(LogLevel::Off);
+        assert!(!log_enabled(LogLevel::Error) && !log_enabled(LogLevel::Off));
+
+        set_log_level(LogLevel::DEFAULT);
+    }
+
+    #[test]
+    fn test_parse_log_filter() {
+        assert_eq!(Ok((None, vec![])), parse_log_filter(""));
+        assert_eq!(Ok((Some(LogLevel::Warn), vec![
+            ("compositor".to_string(), LogLevel::Trace),
+            ("region".to_string(), LogLevel::Debug)
+        ])), parse_log_filter(" WARN, compositor = trace,,region=debug"));
+        assert_eq!(Ok((None, vec![("region".to_string(), LogLevel::Off)])), parse_log_filter("region=info,region=off"));
+
+        assert_eq!(Err(LogFilterError::UnknownLevel("verbose".to_string())), parse_log_filter("warn,compositor=verbose"));
+        assert_eq!(Err(LogFilterError::EmptyTarget), parse_log_filter("=debug"));
+    }
+
+    #[test]
+    fn test_target_level() {
+        let targets = vec![
+            ("compositor".to_string(), LogLevel::Debug),
+            ("compositor::layers".to_string(), LogLevel::Trace)
+        ];
+        assert_eq!(LogLevel::Debug, target_level(&targets, "compositor", LogLevel::Warn));
+        assert_eq!(LogLevel::Trace, target_level(&targets, "compositor::layers::cache", LogLevel::Warn));
+        assert_eq!(LogLevel::Warn, target_level(&targets, "compositors", LogLevel::Warn));
+        assert_eq!(LogLevel::Warn, target_level(&targets, "region", LogLevel::Warn));
+    }
+}
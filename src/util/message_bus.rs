@@ -0,0 +1,143 @@
+use std::any::{
+    Any,
+    TypeId
+};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::{
+    Listeners,
+    Subscription
+};
+
+/// Delivers messages to the listeners that subscribed to their type, so that the parts of an app don't need to know
+/// each other (or the browser APIs that cause the messages). Every message type has its own Listeners.
+///
+/// The crate publishes its platform events (like OrientationChange) on the global bus of the thread, see
+/// subscribe_message and publish_message. Listeners can subscribe and publish messages of any type (including the
+/// type they are being notified of), see Listeners::notify_shared.
+pub struct MessageBus {
+
+    channels: RefCell<HashMap<TypeId, Rc<dyn Any>>>
+}
+
+impl MessageBus {
+
+    pub fn new() -> MessageBus {
+        MessageBus {
+            channels: RefCell::new(HashMap::new())
+        }
+    }
+
+    /// Registers the callback for messages of type M, until the returned Subscription is dropped.
+    pub fn subscribe<M: 'static, F: FnMut(&M) + 'static>(&self, callback: F) -> Subscription {
+        self.channel::<M>().borrow_mut().subscribe(callback)
+    }
+
+    /// Calls all listeners of messages of type M with the message, in the order in which they subscribed.
+    pub fn publish<M: 'static>(&self, message: &M) {
+        // Don't keep the map borrowed, so that listeners can use the bus as well
+        let channel = self.channels.borrow().get(&TypeId::of::<M>()).cloned();
+        if let Some(channel) = channel {
+            let listeners = channel.downcast::<RefCell<Listeners<M>>>().expect("Channels are stored by the TypeId of their message");
+            Listeners::notify_shared(&listeners, message);
+        }
+    }
+
+    /// Counts the listeners of messages of type M whose Subscription is still alive.
+    pub fn live_count<M: 'static>(&self) -> usize {
+        self.channels.borrow().get(&TypeId::of::<M>()).map_or(0, |channel| {
+            channel.downcast_ref::<RefCell<Listeners<M>>>().expect("Channels are stored by the TypeId of their message").borrow().live_count()
+        })
+    }
+
+    fn channel<M: 'static>(&self) -> Rc<RefCell<Listeners<M>>> {
+        let channel = Rc::clone(self.channels.borrow_mut().entry(TypeId::of::<M>())
+            .or_insert_with(|| Rc::new(RefCell::new(Listeners::<M>::new()))));
+        channel.downcast().expect("Channels are stored by the TypeId of their message")
+    }
+}
+
+impl Default for MessageBus {
+
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+thread_local! {
+    static GLOBAL_BUS: MessageBus = MessageBus::new();
+}
+
+/// Subscribes to messages of type M on the global message bus of this thread.
+pub fn subscribe_message<M: 'static, F: FnMut(&M) + 'static>(callback: F) -> Subscription {
+    GLOBAL_BUS.with(|bus| bus.subscribe(callback))
+}
+
+/// Publishes the message on the global message bus of this thread.
+pub fn publish_message<M: 'static>(message: &M) {
+    GLOBAL_BUS.with(|bus| bus.publish(message));
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_publish_by_type() {
+        let bus = MessageBus::new();
+        let received = Rc::new(RefCell::new(Vec::new()));
+
+        let received_numbers = Rc::clone(&received);
+        let numbers = bus.subscribe(move |number: &u32| received_numbers.borrow_mut().push(number.to_string()));
+        let received_texts = Rc::clone(&received);
+        let _texts = bus.subscribe(move |text: &&str| received_texts.borrow_mut().push(text.to_string()));
+        assert_eq!(1, bus.live_count::<u32>());
+        assert_eq!(0, bus.live_count::<i64>());
+
+        bus.publish(&5u32);
+        bus.publish(&"hello");
+        bus.publish(&7i64);
+        assert_eq!(vec!["5", "hello"], *received.borrow());
+
+        drop(numbers);
+        bus.publish(&6u32);
+        assert_eq!(0, bus.live_count::<u32>());
+        assert_eq!(2, received.borrow().len());
+    }
+
+    #[test]
+    fn test_publish_from_listener() {
+        let bus = Rc::new(MessageBus::new());
+        let received = Rc::new(RefCell::new(Vec::new()));
+
+        let inner_bus = Rc::clone(&bus);
+        let _forward = bus.subscribe(move |number: &u32| inner_bus.publish(&(*number as u64 * 2)));
+        let received_doubled = Rc::clone(&received);
+        let _doubled = bus.subscribe(move |number: &u64| received_doubled.borrow_mut().push(*number));
+
+        bus.publish(&4u32);
+        assert_eq!(vec![8], *received.borrow());
+    }
+
+    #[test]
+    fn test_subscribe_from_listener() {
+        let bus = Rc::new(MessageBus::new());
+        let subscriptions = Rc::new(RefCell::new(Vec::new()));
+
+        let (inner_bus, inner_subscriptions) = (Rc::clone(&bus), Rc::clone(&subscriptions));
+        let _subscription = bus.subscribe(move |number: &u32| {
+            inner_subscriptions.borrow_mut().push(inner_bus.subscribe(|_: &u32| {}));
+            if *number > 0 {
+                inner_bus.publish(&(number - 1));
+            }
+        });
+
+        // The nested publish skips the listener that is still running, but reaches the new subscription
+        bus.publish(&1u32);
+        assert_eq!(1, subscriptions.borrow().len());
+        assert_eq!(2, bus.live_count::<u32>());
+    }
+}
@@ -1,12 +1,36 @@
-use web_sys::console;
-
-use wasm_bindgen::JsValue;
-
-pub fn print(message: &str){
-    console::log_1(&JsValue::from_str(message));
-}
-
+mod arena;
+mod assertions;
+mod callback_list;
+#[cfg(feature = "log")]
+mod console_logger;
+mod dirty_regions;
+mod frame_stats;
+mod listeners;
+mod logging;
+mod message_bus;
+mod panic_hook;
+mod property;
 mod region;
+mod sync_weak_vec;
+mod timing;
+mod transform;
+mod weak_hash_map;
 mod weak_vec;
+pub use arena::*;
+pub use assertions::*;
+pub use callback_list::*;
+#[cfg(feature = "log")]
+pub use console_logger::*;
+pub use dirty_regions::*;
+pub use frame_stats::*;
+pub use listeners::*;
+pub use logging::*;
+pub use message_bus::*;
+pub use panic_hook::*;
+pub use property::*;
 pub use region::*;
+pub use sync_weak_vec::*;
+pub use timing::*;
+pub use transform::*;
+pub use weak_hash_map::*;
 pub use weak_vec::*;
\ No newline at end of file
@@ -0,0 +1,53 @@
+use std::panic;
+use std::sync::Once;
+
+use js_sys::Reflect;
+
+use wasm_bindgen::JsValue;
+
+use web_sys::console;
+
+static SET_PANIC_HOOK: Once = Once::new();
+
+/// Installs a panic hook that logs the panic message (including the file and line of the panic) and the JavaScript
+/// stack trace with console.error. Without it, a panic in a wasm module only shows up as an opaque "unreachable
+/// executed" error in the console. Panics are always logged, regardless of the log level. Calling this more than once
+/// has no effect.
+pub fn set_panic_hook() {
+    SET_PANIC_HOOK.call_once(|| {
+        panic::set_hook(Box::new(|info| {
+            console::error_1(&JsValue::from_str(&panic_message(&info.to_string(), js_stack_trace())));
+        }));
+    });
+}
+
+/// Gets the current JavaScript stack trace (which includes the wasm functions, if the module has a name section), or
+/// None if the browser doesn't provide it.
+fn js_stack_trace() -> Option<String> {
+    let error = js_sys::Error::new("");
+    Reflect::get(&error, &JsValue::from_str("stack")).ok().and_then(|stack| stack.as_string())
+}
+
+fn panic_message(info: &str, stack: Option<String>) -> String {
+    match stack {
+        Some(stack) if !stack.trim().is_empty() => format!("{}\n\nStack:\n\n{}", info, stack.trim_end()),
+        _ => info.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_panic_message() {
+        let info = "panicked at src/lib.rs:12:5:\nThere should be a window";
+        assert_eq!(info, panic_message(info, None));
+        assert_eq!(info, panic_message(info, Some(" \n".to_string())));
+        assert_eq!(
+            "panicked at src/lib.rs:12:5:\nThere should be a window\n\nStack:\n\nError\n    at main.wasm",
+            panic_message(info, Some("Error\n    at main.wasm\n".to_string()))
+        );
+    }
+}
@@ -0,0 +1,188 @@
+use crate::WeakVec;
+
+use std::any::Any;
+use std::cell::{
+    Cell,
+    RefCell
+};
+use std::rc::*;
+
+/// A change listener of a Property<T>.
+pub type PropertyListener<T> = RefCell<dyn FnMut(&T)>;
+
+/// An observable value: every time it is changed with set, all its change listeners will be called with the new
+/// value. Properties can be derived from other properties with map, or kept in sync with another property with bind.
+pub struct Property<T: 'static> {
+
+    value: T,
+    listeners: WeakVec<dyn FnMut(&T)>,
+    // The listeners that this property registered on the properties it follows. They are only kept alive as long as
+    // this property is alive.
+    bindings: Vec<Box<dyn Any>>,
+    // Whether set_shared is currently calling the listeners, which is used to break cycles of bindings
+    notifying: Cell<bool>
+}
+
+impl<T: 'static> Property<T> {
+
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            listeners: WeakVec::new(),
+            bindings: Vec::new(),
+            notifying: Cell::new(false)
+        }
+    }
+
+    pub fn get(&self) -> &T {
+        &self.value
+    }
+
+    /// Changes the value of this property and calls all change listeners with the new value. When the property is in a
+    /// RefCell, the listeners are called while the caller still holds the mutable borrow, so listeners that access this
+    /// property (like a binding in the other direction) will panic. Use set_shared in that case.
+    pub fn set(&mut self, value: T) {
+        self.value = value;
+        let value = &self.value;
+        self.listeners.for_each_mut(|listener| listener(value));
+    }
+
+    /// Changes the value of the shared property, and calls all change listeners with (a clone of) the new value after
+    /// the property is no longer borrowed, so the listeners can freely read or change it. If a listener (indirectly)
+    /// sets this property again, the value is stored without notifying the listeners again, which makes it possible
+    /// to bind two properties to each other.
+    pub fn set_shared(shared: &RefCell<Self>, value: T) where T: Clone {
+        let listeners: Vec<_> = {
+            let mut property = shared.borrow_mut();
+            property.value = value;
+            if property.notifying.get() {
+                return;
+            }
+            property.notifying.set(true);
+            property.listeners.iter_upgraded().collect()
+        };
+
+        let value = shared.borrow().value.clone();
+        for listener in listeners {
+            (listener.borrow_mut())(&value);
+        }
+
+        let mut property = shared.borrow_mut();
+        property.notifying.set(false);
+        property.listeners.prune();
+    }
+
+    /// Adds a listener that will be called each time the value of this property changes. The listener is removed
+    /// automatically once its last strong reference is dropped.
+    pub fn add_listener(&mut self, listener: Weak<PropertyListener<T>>) {
+        self.listeners.push(listener);
+    }
+
+    /// Creates a new property whose value is always transform applied to the value of source. The new property stops
+    /// following source when it is dropped.
+    pub fn map<S: 'static, F: FnMut(&S) -> T + 'static>(source: &Rc<RefCell<Property<S>>>, mut transform: F) -> Rc<RefCell<Self>> {
+        let initial_value = transform(source.borrow().get());
+        let derived = Rc::new(RefCell::new(Self::new(initial_value)));
+        Self::follow(&derived, source, transform, |target, value| target.borrow_mut().set(value));
+        derived
+    }
+
+    /// Makes target take the current value of source, and keeps it in sync with source from now on (until target is
+    /// dropped). Note that calling set on target directly will not change source, but target can be bound to source
+    /// as well to keep them in sync in both directions (as long as they are changed with set_shared).
+    pub fn bind(target: &Rc<RefCell<Self>>, source: &Rc<RefCell<Self>>) where T: Clone {
+        let value = source.borrow().get().clone();
+        Self::set_shared(target, value);
+        Self::follow(target, source, T::clone, |target, value| Self::set_shared(target, value));
+    }
+
+    fn follow<S: 'static, F: FnMut(&S) -> T + 'static>(
+        target: &Rc<RefCell<Self>>, source: &Rc<RefCell<Property<S>>>, mut transform: F,
+        set: impl Fn(&RefCell<Self>, T) + 'static
+    ) {
+        let weak_target = Rc::downgrade(target);
+        let listener: Rc<PropertyListener<S>> = Rc::new(RefCell::new(move |value: &S| {
+            if let Some(target) = weak_target.upgrade() {
+                set(&target, transform(value));
+            }
+        }));
+        source.borrow_mut().add_listener(Rc::downgrade(&listener));
+        target.borrow_mut().bindings.push(Box::new(listener));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_set_and_listen() {
+
+        let mut property = Property::new(1);
+        let last_seen = Rc::new(RefCell::new(0));
+
+        let listener_last_seen = Rc::clone(&last_seen);
+        let listener: Rc<PropertyListener<i32>> = Rc::new(RefCell::new(move |value: &i32| *listener_last_seen.borrow_mut() = *value));
+        property.add_listener(Rc::downgrade(&listener));
+
+        property.set(5);
+        assert_eq!(5, *property.get());
+        assert_eq!(5, *last_seen.borrow());
+
+        drop(listener);
+        property.set(6);
+        assert_eq!(5, *last_seen.borrow());
+    }
+
+    #[test]
+    fn test_map() {
+
+        let width = Rc::new(RefCell::new(Property::new(100)));
+        let label = Property::map(&width, |width: &i32| format!("{}px", width));
+        assert_eq!("100px", label.borrow().get());
+
+        width.borrow_mut().set(250);
+        assert_eq!("250px", label.borrow().get());
+
+        drop(label);
+        width.borrow_mut().set(300);
+        assert_eq!(0, width.borrow().listeners.live_count());
+    }
+
+    #[test]
+    fn test_bind() {
+
+        let source = Rc::new(RefCell::new(Property::new('a')));
+        let target = Rc::new(RefCell::new(Property::new('z')));
+
+        Property::bind(&target, &source);
+        assert_eq!('a', *target.borrow().get());
+
+        source.borrow_mut().set('b');
+        assert_eq!('b', *target.borrow().get());
+    }
+
+    #[test]
+    fn test_two_way_binding() {
+
+        let left = Rc::new(RefCell::new(Property::new(1)));
+        let right = Rc::new(RefCell::new(Property::new(2)));
+        Property::bind(&right, &left);
+        Property::bind(&left, &right);
+
+        Property::set_shared(&left, 3);
+        assert_eq!((3, 3), (*left.borrow().get(), *right.borrow().get()));
+        Property::set_shared(&right, 4);
+        assert_eq!((4, 4), (*left.borrow().get(), *right.borrow().get()));
+
+        // Listeners can read the property that changed
+        let seen = Rc::new(Cell::new(0));
+        let listener_left = Rc::clone(&left);
+        let listener_seen = Rc::clone(&seen);
+        let listener: Rc<PropertyListener<i32>> = Rc::new(RefCell::new(move |_: &i32| listener_seen.set(*listener_left.borrow().get())));
+        left.borrow_mut().add_listener(Rc::downgrade(&listener));
+        Property::set_shared(&left, 5);
+        assert_eq!(5, seen.get());
+    }
+}
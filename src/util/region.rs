@@ -85,10 +85,10 @@ impl Region {
         let mut uncovered_to_add = Vec::new();
         for region in regions {
             
-            uncovered_regions.drain_filter(|uncovered| {
+            uncovered_regions.retain(|uncovered| {
                 if uncovered.is_covered_by(*region) {
                     // The current region is entirely covered by the region, so it must be removed completely
-                    true
+                    false
                 } else if uncovered.intersects_with(*region) {
                     // This is the most complex scenario because the regions partially overlap
                     // We will split it into at most 4 regions: above, below, left and right
@@ -134,10 +134,10 @@ impl Region {
                     }
 
                     // Finally remove the original region
-                    true
+                    false
                 } else {
                     // The regions don't share any space, so just continue normally
-                    false
+                    true
                 }
             });
 
@@ -0,0 +1,488 @@
+use std::sync::{
+    Arc,
+    Mutex,
+    MutexGuard,
+    TryLockError,
+    Weak
+};
+
+use crate::{
+    BorrowPolicy,
+    CompactionPolicy,
+    compact
+};
+
+/// The thread-safe counterpart of WeakVec: it stores Weak<Mutex<T>> instead of Weak<RefCell<T>>, so it can be shared
+/// between threads (for instance when using web workers with shared memory). The API is the same as that of WeakVec,
+/// except that the try_borrow variants are called try_lock (and use Mutex::try_lock), and the shared variants take a
+/// Mutex instead of a RefCell.
+/// 
+/// If an element is poisoned (because a thread panicked while it was locked), the element will still be visited.
+pub struct SyncWeakVec<T: ?Sized> {
+
+    vec: Vec<Weak<Mutex<T>>>,
+    compaction_policy: CompactionPolicy
+}
+
+impl<T: ?Sized> SyncWeakVec<T> {
+
+    pub fn new() -> Self {
+        Self {
+            vec: Vec::new(),
+            compaction_policy: CompactionPolicy::Never
+        }
+    }
+
+    pub fn with_capacity(initial_capacity: usize) -> Self {
+        Self {
+            vec: Vec::with_capacity(initial_capacity),
+            compaction_policy: CompactionPolicy::Never
+        }
+    }
+
+    pub fn push(&mut self, element: Weak<Mutex<T>>) {
+        self.vec.push(element);
+    }
+
+    /// Moves all slots of other to the end of this SyncWeakVec, leaving other empty, see WeakVec::append.
+    pub fn append(&mut self, other: &mut SyncWeakVec<T>) {
+        other.prune();
+        self.vec.append(&mut other.vec);
+    }
+
+    /// Removes all slots that point to the same element as target (using pointer equality). Returns true if at least
+    /// one slot was removed.
+    pub fn remove(&mut self, target: &Weak<Mutex<T>>) -> bool {
+        let old_len = self.vec.len();
+        self.vec.retain(|weak_mutex| !Weak::ptr_eq(weak_mutex, target));
+        self.vec.len() != old_len
+    }
+
+    /// Gets the element in the slot at the given index, or None if the index is out of bounds or the element has been
+    /// dropped.
+    pub fn get(&self, index: usize) -> Option<Arc<Mutex<T>>> {
+        self.vec.get(index).and_then(|weak_mutex| weak_mutex.upgrade())
+    }
+
+    /// Removes the slot at the given index and returns it, see WeakVec::swap_remove.
+    /// 
+    /// Panics if the index is out of bounds.
+    pub fn swap_remove(&mut self, index: usize) -> Weak<Mutex<T>> {
+        self.vec.swap_remove(index)
+    }
+
+    /// Shortens this SyncWeakVec to the given number of slots. This has no effect if it doesn't have more slots.
+    pub fn truncate(&mut self, len: usize) {
+        self.vec.truncate(len);
+    }
+
+    /// Returns true if this SyncWeakVec has a slot that points to the same element as target (using pointer equality).
+    pub fn contains(&self, target: &Weak<Mutex<T>>) -> bool {
+        self.vec.iter().any(|weak_mutex| Weak::ptr_eq(weak_mutex, target))
+    }
+
+    /// Returns the number of slots, including the slots of elements that have been dropped, but not yet removed.
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Returns true if there are no slots at all, see WeakVec::is_empty.
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// Counts the number of elements that are still alive.
+    pub fn live_count(&self) -> usize {
+        self.vec.iter().filter(|weak_mutex| weak_mutex.strong_count() > 0).count()
+    }
+
+    /// Removes the slots of all elements that have been dropped.
+    pub fn prune(&mut self) {
+        self.vec.retain(|weak_mutex| weak_mutex.strong_count() > 0);
+        compact(&mut self.vec, self.compaction_policy);
+    }
+
+    /// Removes the slots of all elements that have been dropped, and shrinks the capacity as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        self.vec.retain(|weak_mutex| weak_mutex.strong_count() > 0);
+        self.vec.shrink_to_fit();
+    }
+
+    /// Returns the number of slots this SyncWeakVec can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    /// Sets the policy that determines whether the capacity is shrunk after removing the slots of dropped elements.
+    pub fn set_compaction_policy(&mut self, policy: CompactionPolicy) {
+        self.compaction_policy = policy;
+    }
+
+    /// Calls the closure for each element that is still alive. If the closure returns true, the element will be removed.
+    /// The slots of elements that have been dropped are removed as well.
+    pub fn for_each_cell<F: FnMut(Arc<Mutex<T>>) -> bool>(&mut self, mut closure: F) {
+        self.vec.retain(|weak_mutex| {
+            match weak_mutex.upgrade() {
+                Some(mutex) => {
+                    !closure(mutex)
+                }, None => {
+                    false
+                }
+            }
+        });
+        compact(&mut self.vec, self.compaction_policy);
+    }
+
+    pub fn for_each<F: FnMut(&T)>(&mut self, mut closure: F) {
+        self.for_each_cell(|mutex| {
+            let locked = lock(&mutex);
+            closure(&locked);
+            false
+        });
+    }
+
+    pub fn for_each_mut<F: FnMut(&mut T)>(&mut self, mut closure: F) {
+        self.for_each_cell(|mutex| {
+            let mut locked = lock(&mutex);
+            closure(&mut locked);
+            false
+        });
+    }
+
+    /// Like for_each_cell, but visits the elements in reverse order (the element that was pushed last comes first).
+    pub fn for_each_cell_rev<F: FnMut(Arc<Mutex<T>>) -> bool>(&mut self, mut closure: F) {
+        let mut keep = vec![false; self.vec.len()];
+        for (index, weak_mutex) in self.vec.iter().enumerate().rev() {
+            if let Some(mutex) = weak_mutex.upgrade() {
+                keep[index] = !closure(mutex);
+            }
+        }
+        let mut keep = keep.into_iter();
+        self.vec.retain(|_| keep.next().expect("There is a keep flag for every slot"));
+        compact(&mut self.vec, self.compaction_policy);
+    }
+
+    /// Like for_each, but visits the elements in reverse order.
+    pub fn for_each_rev<F: FnMut(&T)>(&mut self, mut closure: F) {
+        self.for_each_cell_rev(|mutex| {
+            let locked = lock(&mutex);
+            closure(&locked);
+            false
+        });
+    }
+
+    /// Like for_each_mut, but visits the elements in reverse order.
+    pub fn for_each_mut_rev<F: FnMut(&mut T)>(&mut self, mut closure: F) {
+        self.for_each_cell_rev(|mutex| {
+            let mut locked = lock(&mutex);
+            closure(&mut locked);
+            false
+        });
+    }
+
+    /// Like for_each_cell, but stops as soon as the closure returns an error, and returns that error.
+    pub fn try_for_each_cell<E, F: FnMut(Arc<Mutex<T>>) -> Result<(), E>>(&mut self, mut closure: F) -> Result<(), E> {
+        let mut result = Ok(());
+        self.vec.retain(|weak_mutex| {
+            if result.is_err() {
+                return true;
+            }
+            match weak_mutex.upgrade() {
+                Some(mutex) => {
+                    result = closure(mutex);
+                    true
+                }, None => {
+                    false
+                }
+            }
+        });
+        result
+    }
+
+    /// Like for_each, but stops as soon as the closure returns an error, and returns that error.
+    pub fn try_for_each<E, F: FnMut(&T) -> Result<(), E>>(&mut self, mut closure: F) -> Result<(), E> {
+        self.try_for_each_cell(|mutex| {
+            let locked = lock(&mutex);
+            closure(&locked)
+        })
+    }
+
+    /// Like for_each_mut, but stops as soon as the closure returns an error, and returns that error.
+    pub fn try_for_each_mut<E, F: FnMut(&mut T) -> Result<(), E>>(&mut self, mut closure: F) -> Result<(), E> {
+        self.try_for_each_cell(|mutex| {
+            let mut locked = lock(&mutex);
+            closure(&mut locked)
+        })
+    }
+
+    /// Like for_each, but uses try_lock instead of lock, so it won't block (or deadlock) when an element is already
+    /// locked. The policy determines what happens with such elements, like in WeakVec::for_each_try_borrow.
+    pub fn for_each_try_lock<F: FnMut(&T)>(&mut self, policy: BorrowPolicy, mut closure: F) -> Result<Vec<Arc<Mutex<T>>>, Arc<Mutex<T>>> {
+        self.for_each_with_policy(policy, |mutex| {
+            match try_lock(mutex) {
+                Some(locked) => {
+                    closure(&locked);
+                    true
+                }, None => {
+                    false
+                }
+            }
+        })
+    }
+
+    /// Like for_each_try_lock, but gives mutable access to the elements.
+    pub fn for_each_mut_try_lock<F: FnMut(&mut T)>(&mut self, policy: BorrowPolicy, mut closure: F) -> Result<Vec<Arc<Mutex<T>>>, Arc<Mutex<T>>> {
+        self.for_each_with_policy(policy, |mutex| {
+            match try_lock(mutex) {
+                Some(mut locked) => {
+                    closure(&mut locked);
+                    true
+                }, None => {
+                    false
+                }
+            }
+        })
+    }
+
+    fn for_each_with_policy<F: FnMut(&Mutex<T>) -> bool>(&mut self, policy: BorrowPolicy, mut visit: F) -> Result<Vec<Arc<Mutex<T>>>, Arc<Mutex<T>>> {
+        let mut deferred = Vec::new();
+        self.try_for_each_cell(|mutex| {
+            if visit(&mutex) {
+                return Ok(());
+            }
+            match policy {
+                BorrowPolicy::Skip => Ok(()),
+                BorrowPolicy::Defer => {
+                    deferred.push(mutex);
+                    Ok(())
+                },
+                BorrowPolicy::Report => Err(mutex)
+            }
+        })?;
+        Ok(deferred)
+    }
+
+    /// Visits the elements in order until the closure returns true. Returns true if the closure returned true for any
+    /// element.
+    pub fn for_each_until<F: FnMut(&T) -> bool>(&mut self, mut closure: F) -> bool {
+        self.try_for_each(|element| if closure(element) { Err(()) } else { Ok(()) }).is_err()
+    }
+
+    /// Like for_each_until, but gives mutable access to the elements.
+    pub fn for_each_mut_until<F: FnMut(&mut T) -> bool>(&mut self, mut closure: F) -> bool {
+        self.try_for_each_mut(|element| if closure(element) { Err(()) } else { Ok(()) }).is_err()
+    }
+
+    /// Returns the first element for which the predicate returns true, or None if there is no such element.
+    pub fn find<F: FnMut(&T) -> bool>(&mut self, mut predicate: F) -> Option<Arc<Mutex<T>>> {
+        self.try_for_each_cell(|mutex| {
+            let matches = predicate(&lock(&mutex));
+            if matches { Err(mutex) } else { Ok(()) }
+        }).err()
+    }
+
+    /// Removes all elements for which the given closure returns false (as well as all elements that have been dropped).
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut keep: F) {
+        self.for_each_cell(|mutex| {
+            let locked = lock(&mutex);
+            !keep(&locked)
+        });
+    }
+
+    /// Calls the closure for each element of the shared SyncWeakVec that is alive at the start of this call, without
+    /// keeping the SyncWeakVec locked while the closure runs, see WeakVec::for_each_shared.
+    pub fn for_each_shared<F: FnMut(&T)>(shared: &Mutex<SyncWeakVec<T>>, mut closure: F) {
+        Self::for_each_cell_shared(shared, |mutex| closure(&lock(&mutex)));
+    }
+
+    /// Like for_each_shared, but gives mutable access to the elements.
+    pub fn for_each_mut_shared<F: FnMut(&mut T)>(shared: &Mutex<SyncWeakVec<T>>, mut closure: F) {
+        Self::for_each_cell_shared(shared, |mutex| closure(&mut lock(&mutex)));
+    }
+
+    fn for_each_cell_shared<F: FnMut(Arc<Mutex<T>>)>(shared: &Mutex<SyncWeakVec<T>>, closure: F) {
+        let snapshot: Vec<_> = lock(shared).iter_upgraded().collect();
+        snapshot.into_iter().for_each(closure);
+
+        // The SyncWeakVec may still be locked when this is a nested call
+        if let Some(mut weak_vec) = try_lock(shared) {
+            weak_vec.prune();
+        }
+    }
+
+    /// Returns an iterator over the elements that are still alive.
+    pub fn iter_upgraded(&self) -> impl Iterator<Item = Arc<Mutex<T>>> + '_ {
+        self.vec.iter().filter_map(|weak_mutex| weak_mutex.upgrade())
+    }
+}
+
+impl<T: ?Sized> Default for SyncWeakVec<T> {
+
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A consuming iterator over the elements of a SyncWeakVec that are still alive.
+pub struct SyncWeakVecIntoIter<T: ?Sized> {
+
+    inner: std::vec::IntoIter<Weak<Mutex<T>>>
+}
+
+impl<T: ?Sized> Iterator for SyncWeakVecIntoIter<T> {
+
+    type Item = Arc<Mutex<T>>;
+
+    fn next(&mut self) -> Option<Arc<Mutex<T>>> {
+        for weak_mutex in &mut self.inner {
+            if let Some(mutex) = weak_mutex.upgrade() {
+                return Some(mutex);
+            }
+        }
+        None
+    }
+}
+
+impl<T: ?Sized> IntoIterator for SyncWeakVec<T> {
+
+    type Item = Arc<Mutex<T>>;
+    type IntoIter = SyncWeakVecIntoIter<T>;
+
+    /// Consumes this SyncWeakVec and returns an iterator over the elements that are still alive.
+    fn into_iter(self) -> SyncWeakVecIntoIter<T> {
+        SyncWeakVecIntoIter {
+            inner: self.vec.into_iter()
+        }
+    }
+}
+
+fn try_lock<T: ?Sized>(mutex: &Mutex<T>) -> Option<MutexGuard<'_, T>> {
+    match mutex.try_lock() {
+        Ok(locked) => Some(locked),
+        Err(TryLockError::Poisoned(poisoned)) => Some(poisoned.into_inner()),
+        Err(TryLockError::WouldBlock) => None
+    }
+}
+
+fn lock<T: ?Sized>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use std::thread;
+
+    #[test]
+    fn test_for_each() {
+
+        let mut vec = SyncWeakVec::new();
+
+        let persistent1 = Arc::new(Mutex::new(1));
+        let vanish1 = Arc::new(Mutex::new(2));
+        let persistent2 = Arc::new(Mutex::new(4));
+
+        vec.push(Arc::downgrade(&persistent1));
+        vec.push(Arc::downgrade(&vanish1));
+        vec.push(Arc::downgrade(&persistent2));
+
+        let mut sum = 0;
+        vec.for_each(|number| sum += number);
+        assert_eq!(7, sum);
+
+        drop(vanish1);
+        vec.for_each_mut(|number| *number *= 2);
+        assert_eq!(2, vec.len());
+        assert_eq!(10, *persistent1.lock().unwrap() + *persistent2.lock().unwrap());
+    }
+
+    #[test]
+    fn test_across_threads() {
+
+        let registry = Arc::new(Mutex::new(SyncWeakVec::new()));
+        let counter = Arc::new(Mutex::new(0));
+        registry.lock().unwrap().push(Arc::downgrade(&counter));
+
+        let handles: Vec<_> = (0..4).map(|_| {
+            let registry = Arc::clone(&registry);
+            thread::spawn(move || {
+                registry.lock().unwrap().for_each_mut(|count| *count += 1);
+            })
+        }).collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(4, *counter.lock().unwrap());
+    }
+
+    #[test]
+    fn test_until_and_remove() {
+
+        let mut vec = SyncWeakVec::new();
+
+        let numbers: Vec<_> = (1..=3).map(|number| Arc::new(Mutex::new(number))).collect();
+        for number in &numbers {
+            vec.push(Arc::downgrade(number));
+        }
+
+        let mut visited = 0;
+        assert!(vec.for_each_until(|number| {
+            visited += 1;
+            *number == 2
+        }));
+        assert_eq!(2, visited);
+
+        assert!(vec.remove(&Arc::downgrade(&numbers[0])));
+        vec.retain(|number| *number != 3);
+        assert_eq!(vec![2], vec.iter_upgraded().map(|number| *number.lock().unwrap()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_rev_and_try_lock() {
+
+        let mut vec = SyncWeakVec::new();
+
+        let numbers: Vec<_> = (1..=3).map(|number| Arc::new(Mutex::new(number))).collect();
+        for number in &numbers {
+            vec.push(Arc::downgrade(number));
+        }
+
+        let mut visited = Vec::new();
+        vec.for_each_rev(|number| visited.push(*number));
+        assert_eq!(vec![3, 2, 1], visited);
+        vec.for_each_cell_rev(|mutex| *mutex.lock().unwrap() == 2);
+        assert_eq!(2, vec.len());
+
+        let locked = numbers[0].lock().unwrap();
+        let deferred = vec.for_each_mut_try_lock(BorrowPolicy::Defer, |number| *number *= 10).unwrap();
+        assert_eq!(1, deferred.len());
+        assert!(Arc::ptr_eq(&numbers[0], &deferred[0]));
+        assert!(vec.for_each_try_lock(BorrowPolicy::Report, |_| {}).is_err());
+        drop(locked);
+        assert_eq!(30, *numbers[2].lock().unwrap());
+
+        assert!(Arc::ptr_eq(&numbers[2], &vec.find(|number| *number == 30).unwrap()));
+        assert_eq!(vec![1, 30], vec.into_iter().map(|number| *number.lock().unwrap()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_for_each_shared() {
+
+        let shared = Mutex::new(SyncWeakVec::new());
+        let first = Arc::new(Mutex::new(1));
+        lock(&shared).push(Arc::downgrade(&first));
+
+        // The closure can push to the SyncWeakVec, because it is not locked during the iteration
+        let second = Arc::new(Mutex::new(2));
+        let mut visited = 0;
+        SyncWeakVec::for_each_shared(&shared, |_| {
+            visited += 1;
+            lock(&shared).push(Arc::downgrade(&second));
+        });
+        assert_eq!(1, visited);
+        assert_eq!(2, lock(&shared).live_count());
+    }
+}
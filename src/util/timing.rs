@@ -0,0 +1,109 @@
+use wasm_bindgen::{
+    JsCast,
+    JsValue
+};
+
+use web_sys::{
+    Performance,
+    console
+};
+
+use crate::{
+    LogLevel,
+    log_enabled,
+    log_target
+};
+
+/// The log target of the messages of TimedScope::finish_and_log, so that they can be filtered separately
+pub const TIMING_LOG_TARGET: &str = "timing";
+
+/// Measures the time between its creation and the moment it is dropped (or finished). If Debug messages are enabled
+/// when the scope is created, it also calls console.time and console.timeEnd with its label, so the duration shows up
+/// in the console (and in the performance profiler of most browsers).
+///
+/// The time! macro is a shorthand for timing a block of code.
+pub struct TimedScope {
+    label: String,
+    start: f64,
+    logged: bool
+}
+
+impl TimedScope {
+
+    pub fn new(label: &str) -> TimedScope {
+        let logged = log_enabled(LogLevel::Debug);
+        if logged {
+            console::time_with_label(label);
+        }
+        TimedScope {
+            label: label.to_string(),
+            start: now(),
+            logged
+        }
+    }
+
+    pub fn get_label(&self) -> &str {
+        &self.label
+    }
+
+    /// Gets the number of milliseconds since this scope was created
+    pub fn elapsed(&self) -> f64 {
+        now() - self.start
+    }
+
+    /// Ends this scope and returns its duration in milliseconds
+    pub fn finish(self) -> f64 {
+        self.elapsed()
+    }
+
+    /// Ends this scope, logs its duration at the given level (with TIMING_LOG_TARGET as target), and returns it
+    pub fn finish_and_log(self, level: LogLevel) -> f64 {
+        let duration = self.elapsed();
+        log_target(level, TIMING_LOG_TARGET, &duration_message(&self.label, duration));
+        duration
+    }
+}
+
+impl Drop for TimedScope {
+
+    fn drop(&mut self) {
+        if self.logged {
+            console::time_end_with_label(&self.label);
+        }
+    }
+}
+
+/// Evaluates the expression (usually a block) inside a TimedScope with the given label, and returns its value.
+/// For instance, `let layout = time!("layout", { compute_layout(&components) });`
+#[macro_export]
+macro_rules! time {
+    ($label:expr, $body:expr) => {{
+        let _scope = $crate::TimedScope::new($label);
+        $body
+    }};
+}
+
+fn duration_message(label: &str, duration: f64) -> String {
+    format!("{} took {:.2} ms", label, duration)
+}
+
+/// Gets the current time in milliseconds, with performance.now. The performance object is read from the global
+/// object, so this works in both the main thread and web workers.
+pub(crate) fn now() -> f64 {
+    let performance = js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str("performance"))
+        .expect("There should be a performance");
+    performance.unchecked_into::<Performance>().now()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_duration_message() {
+        assert_eq!("layout took 1.25 ms", duration_message("layout", 1.25));
+        assert_eq!("upload took 0.00 ms", duration_message("upload", 0.0));
+        assert_eq!("frame took 16.67 ms", duration_message("frame", 16.666_67));
+    }
+}
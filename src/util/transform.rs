@@ -0,0 +1,96 @@
+/// A 2D affine transformation, which maps a point (x, y) to (a * x + c * y + tx, b * x + d * y + ty). This can be
+/// used to position, scale and rotate things in GL coordinates.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct Transform2D {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub tx: f32,
+    pub ty: f32
+}
+
+impl Transform2D {
+
+    /// The transformation that doesn't change anything.
+    pub const IDENTITY: Transform2D = Transform2D { a: 1.0, b: 0.0, c: 0.0, d: 1.0, tx: 0.0, ty: 0.0 };
+
+    pub const fn translation(tx: f32, ty: f32) -> Transform2D {
+        Transform2D { a: 1.0, b: 0.0, c: 0.0, d: 1.0, tx, ty }
+    }
+
+    pub const fn scale(sx: f32, sy: f32) -> Transform2D {
+        Transform2D { a: sx, b: 0.0, c: 0.0, d: sy, tx: 0.0, ty: 0.0 }
+    }
+
+    /// A counter-clockwise rotation around the origin by the given angle (in radians).
+    pub fn rotation(angle: f32) -> Transform2D {
+        let (sin, cos) = angle.sin_cos();
+        Transform2D { a: cos, b: sin, c: -sin, d: cos, tx: 0.0, ty: 0.0 }
+    }
+
+    /// Returns the transformation that first applies self, and then applies next.
+    pub fn then(&self, next: &Transform2D) -> Transform2D {
+        Transform2D {
+            a: next.a * self.a + next.c * self.b,
+            b: next.b * self.a + next.d * self.b,
+            c: next.a * self.c + next.c * self.d,
+            d: next.b * self.c + next.d * self.d,
+            tx: next.a * self.tx + next.c * self.ty + next.tx,
+            ty: next.b * self.tx + next.d * self.ty + next.ty
+        }
+    }
+
+    pub fn apply(&self, point: (f32, f32)) -> (f32, f32) {
+        (self.a * point.0 + self.c * point.1 + self.tx, self.b * point.0 + self.d * point.1 + self.ty)
+    }
+
+    /// Converts this transformation to a 3x3 matrix in column-major order, as expected by uniformMatrix3fv.
+    pub fn to_mat3(&self) -> [f32; 9] {
+        [
+            self.a, self.b, 0.0,
+            self.c, self.d, 0.0,
+            self.tx, self.ty, 1.0
+        ]
+    }
+}
+
+impl Default for Transform2D {
+
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn assert_close(expected: (f32, f32), actual: (f32, f32)) {
+        assert!((expected.0 - actual.0).abs() < 0.0001 && (expected.1 - actual.1).abs() < 0.0001, "Expected {:?}, but got {:?}", expected, actual);
+    }
+
+    #[test]
+    fn test_apply() {
+        assert_eq!((3.0, 4.0), Transform2D::IDENTITY.apply((3.0, 4.0)));
+        assert_eq!((4.0, 2.0), Transform2D::translation(1.0, -2.0).apply((3.0, 4.0)));
+        assert_eq!((6.0, 2.0), Transform2D::scale(2.0, 0.5).apply((3.0, 4.0)));
+        assert_close((-4.0, 3.0), Transform2D::rotation(std::f32::consts::FRAC_PI_2).apply((3.0, 4.0)));
+    }
+
+    #[test]
+    fn test_then() {
+        let transform = Transform2D::scale(2.0, 2.0).then(&Transform2D::translation(1.0, 0.0));
+        assert_eq!((3.0, 2.0), transform.apply((1.0, 1.0)));
+
+        let transform = Transform2D::translation(1.0, 0.0).then(&Transform2D::scale(2.0, 2.0));
+        assert_eq!((4.0, 2.0), transform.apply((1.0, 1.0)));
+    }
+
+    #[test]
+    fn test_to_mat3() {
+        let transform = Transform2D::translation(5.0, 6.0);
+        assert_eq!([1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 5.0, 6.0, 1.0], transform.to_mat3());
+    }
+}
@@ -0,0 +1,181 @@
+use std::cell::*;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::rc::*;
+
+/// A HashMap that only stores weak references to its values, just like a WeakVec. Entries whose value has been
+/// dropped are removed automatically when they are accessed (or when prune is called). This is useful for components
+/// that are registered under an ID (like focus targets or named layers), because they can be looked up directly
+/// instead of scanning a WeakVec.
+pub struct WeakHashMap<K: Eq + Hash, T: ?Sized> {
+
+    map: HashMap<K, Weak<RefCell<T>>>
+}
+
+impl<K: Eq + Hash, T: ?Sized> WeakHashMap<K, T> {
+
+    pub fn new() -> Self {
+        Self {
+            map: HashMap::new()
+        }
+    }
+
+    pub fn with_capacity(initial_capacity: usize) -> Self {
+        Self {
+            map: HashMap::with_capacity(initial_capacity)
+        }
+    }
+
+    /// Inserts the given value under the given key. Returns the previous value with that key, if it was still alive.
+    pub fn insert(&mut self, key: K, value: Weak<RefCell<T>>) -> Option<Rc<RefCell<T>>> {
+        self.map.insert(key, value).and_then(|previous| previous.upgrade())
+    }
+
+    /// Gets the value with the given key. If that value has been dropped, its entry is removed and None is returned.
+    pub fn get<Q: ?Sized + Eq + Hash>(&mut self, key: &Q) -> Option<Rc<RefCell<T>>> where K: std::borrow::Borrow<Q> {
+        let result = self.map.get(key)?.upgrade();
+        if result.is_none() {
+            self.map.remove(key);
+        }
+        result
+    }
+
+    /// Checks if there is a value with the given key that is still alive. If that value has been dropped, its entry is
+    /// removed.
+    pub fn contains_key<Q: ?Sized + Eq + Hash>(&mut self, key: &Q) -> bool where K: std::borrow::Borrow<Q> {
+        self.get(key).is_some()
+    }
+
+    /// Removes the value with the given key, and returns it if it was still alive.
+    pub fn remove<Q: ?Sized + Eq + Hash>(&mut self, key: &Q) -> Option<Rc<RefCell<T>>> where K: std::borrow::Borrow<Q> {
+        self.map.remove(key).and_then(|value| value.upgrade())
+    }
+
+    /// Returns the number of entries in this map, including the entries of values that have been dropped, but not yet
+    /// removed. Use live_count to get the number of values that are still alive.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns true if this map has no entries at all. Note that this returns false when all values have been dropped,
+    /// but not yet removed. Call prune first if that matters.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Counts the number of values that are still alive.
+    pub fn live_count(&self) -> usize {
+        self.map.values().filter(|value| value.strong_count() > 0).count()
+    }
+
+    /// Removes the entries of all values that have been dropped.
+    pub fn prune(&mut self) {
+        self.map.retain(|_, value| value.strong_count() > 0);
+    }
+
+    /// Calls the closure for each value that is still alive (in arbitrary order), and removes the entries of the values
+    /// that have been dropped.
+    pub fn for_each<F: FnMut(&K, &T)>(&mut self, mut closure: F) {
+        self.map.retain(|key, value| {
+            match value.upgrade() {
+                Some(cell) => {
+                    let borrowed = cell.borrow();
+                    closure(key, &borrowed);
+                    true
+                }, None => {
+                    false
+                }
+            }
+        });
+    }
+
+    /// Like for_each, but gives mutable access to the values.
+    pub fn for_each_mut<F: FnMut(&K, &mut T)>(&mut self, mut closure: F) {
+        self.map.retain(|key, value| {
+            match value.upgrade() {
+                Some(cell) => {
+                    let mut borrowed = cell.borrow_mut();
+                    closure(key, &mut borrowed);
+                    true
+                }, None => {
+                    false
+                }
+            }
+        });
+    }
+}
+
+impl<K: Eq + Hash, T: ?Sized> Default for WeakHashMap<K, T> {
+
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_get() {
+
+        let mut map = WeakHashMap::new();
+
+        let persistent = Rc::new(RefCell::new(1));
+        let vanish = Rc::new(RefCell::new(2));
+        assert!(map.insert("persistent".to_string(), Rc::downgrade(&persistent)).is_none());
+        map.insert("vanish".to_string(), Rc::downgrade(&vanish));
+
+        assert_eq!(1, *map.get("persistent").unwrap().borrow());
+        assert_eq!(2, *map.get("vanish").unwrap().borrow());
+        assert!(map.get("unknown").is_none());
+
+        drop(vanish);
+        assert_eq!(2, map.len());
+        assert_eq!(1, map.live_count());
+        assert!(!map.contains_key("vanish"));
+        assert_eq!(1, map.len());
+    }
+
+    #[test]
+    fn test_insert_and_remove() {
+
+        let mut map = WeakHashMap::new();
+
+        let first = Rc::new(RefCell::new(1));
+        let second = Rc::new(RefCell::new(2));
+        map.insert(5, Rc::downgrade(&first));
+        assert!(Rc::ptr_eq(&first, &map.insert(5, Rc::downgrade(&second)).unwrap()));
+        assert!(Rc::ptr_eq(&second, &map.remove(&5).unwrap()));
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn test_for_each_and_prune() {
+
+        let mut map = WeakHashMap::new();
+
+        let persistent = Rc::new(RefCell::new(1));
+        let vanish1 = Rc::new(RefCell::new(2));
+        let vanish2 = Rc::new(RefCell::new(4));
+        map.insert('a', Rc::downgrade(&persistent));
+        map.insert('b', Rc::downgrade(&vanish1));
+        map.insert('c', Rc::downgrade(&vanish2));
+
+        drop(vanish1);
+        map.for_each_mut(|_, number| *number *= 10);
+        assert_eq!(2, map.len());
+
+        drop(vanish2);
+        map.prune();
+        assert_eq!(1, map.len());
+
+        let mut sum = 0;
+        map.for_each(|key, number| {
+            assert_eq!('a', *key);
+            sum += number;
+        });
+        assert_eq!(10, sum);
+    }
+}
@@ -1,22 +1,62 @@
 use std::cell::*;
+use std::cmp::Ordering;
 use std::rc::*;
 
+/// A Vec that only stores weak references to its elements. The listener lists of this crate are WeakVecs, so that
+/// registering a listener doesn't keep it (and everything it captures) alive: a listener is removed as soon as the
+/// last Rc to it is dropped, and the slots of dropped elements are cleaned up while iterating.
 pub struct WeakVec<T: ?Sized> {
 
-    vec: Vec<Weak<RefCell<T>>>
+    vec: Vec<Weak<RefCell<T>>>,
+    compaction_policy: CompactionPolicy
+}
+
+/// Determines whether a WeakVec (or WeakMetaVec) automatically gives unused capacity back after removing the slots of
+/// dropped elements. Long-lived listener lists can otherwise keep the capacity of their largest size forever.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum CompactionPolicy {
+
+    /// Never shrink automatically. This is the default.
+    Never,
+    /// Shrink when less than the given fraction (between 0.0 and 1.0) of the capacity is in use after the slots of
+    /// dropped elements have been removed. The capacity is shrunk to twice the number of remaining elements, so that
+    /// growing again doesn't immediately need a reallocation.
+    BelowLiveFraction(f32)
+}
+
+/// Determines how the try_borrow variants of the for_each methods treat elements that are already borrowed.
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum BorrowPolicy {
+
+    /// Silently skip elements that are already borrowed.
+    Skip,
+    /// Don't visit elements that are already borrowed, but return them to the caller.
+    Defer,
+    /// Stop iterating at the first element that is already borrowed, and return it as error.
+    Report
+}
+
+pub(crate) fn compact<X>(vec: &mut Vec<X>, policy: CompactionPolicy) {
+    if let CompactionPolicy::BelowLiveFraction(fraction) = policy {
+        if (vec.len() as f32) < vec.capacity() as f32 * fraction {
+            vec.shrink_to(vec.len() * 2);
+        }
+    }
 }
 
 impl<T: ?Sized> WeakVec<T> {
 
     pub fn new() -> Self {
         Self {
-            vec: Vec::new()
+            vec: Vec::new(),
+            compaction_policy: CompactionPolicy::Never
         }
     }
 
     pub fn with_capacity(initial_capacity: usize) -> Self {
         Self {
-            vec: Vec::with_capacity(initial_capacity)
+            vec: Vec::with_capacity(initial_capacity),
+            compaction_policy: CompactionPolicy::Never
         }
     }
 
@@ -24,16 +64,99 @@ impl<T: ?Sized> WeakVec<T> {
         self.vec.push(element);
     }
 
+    /// Moves all slots of other to the end of this WeakVec, leaving other empty. The slots of dropped elements are
+    /// skipped.
+    pub fn append(&mut self, other: &mut WeakVec<T>) {
+        other.prune();
+        self.vec.append(&mut other.vec);
+    }
+
+    /// Removes all slots that point to the same element as target (using pointer equality, so the elements don't need
+    /// to implement PartialEq). Returns true if at least one slot was removed. This can be used to explicitly unsubscribe
+    /// a listener without waiting until its last strong reference is dropped.
+    pub fn remove(&mut self, target: &Weak<RefCell<T>>) -> bool {
+        let old_len = self.vec.len();
+        self.vec.retain(|weak_cell| !Weak::ptr_eq(weak_cell, target));
+        self.vec.len() != old_len
+    }
+
+    /// Gets the element in the slot at the given index, or None if the index is out of bounds or the element has been
+    /// dropped. Note that the indices of the slots change when the slots of dropped elements are removed.
+    pub fn get(&self, index: usize) -> Option<Rc<RefCell<T>>> {
+        self.vec.get(index).and_then(|weak_cell| weak_cell.upgrade())
+    }
+
+    /// Removes the slot at the given index and returns it. The last slot is moved to the given index, so this doesn't
+    /// preserve the order, but it is O(1).
+    /// 
+    /// Panics if the index is out of bounds.
+    pub fn swap_remove(&mut self, index: usize) -> Weak<RefCell<T>> {
+        self.vec.swap_remove(index)
+    }
+
+    /// Shortens this WeakVec to the given number of slots. This has no effect if it doesn't have more slots.
+    pub fn truncate(&mut self, len: usize) {
+        self.vec.truncate(len);
+    }
+
+    /// Returns true if this WeakVec has a slot that points to the same element as target (using pointer equality). This
+    /// can be used to avoid registering the same listener twice.
+    pub fn contains(&self, target: &Weak<RefCell<T>>) -> bool {
+        self.vec.iter().any(|weak_cell| Weak::ptr_eq(weak_cell, target))
+    }
+
+    /// Returns the number of slots in this WeakVec, including the slots of elements that have been dropped, but not
+    /// yet removed. Use live_count to get the number of elements that are still alive.
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Returns true if this WeakVec has no slots at all. Note that this returns false when all elements have been
+    /// dropped, but not yet removed. Call prune first if that matters.
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// Counts the number of elements that are still alive.
+    pub fn live_count(&self) -> usize {
+        self.vec.iter().filter(|weak_cell| weak_cell.strong_count() > 0).count()
+    }
+
+    /// Removes the slots of all elements that have been dropped. This happens automatically during each iteration,
+    /// but this method can be used to do it without iterating.
+    pub fn prune(&mut self) {
+        self.vec.retain(|weak_cell| weak_cell.strong_count() > 0);
+        compact(&mut self.vec, self.compaction_policy);
+    }
+
+    /// Removes the slots of all elements that have been dropped, and shrinks the capacity as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        self.vec.retain(|weak_cell| weak_cell.strong_count() > 0);
+        self.vec.shrink_to_fit();
+    }
+
+    /// Returns the number of slots this WeakVec can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    /// Sets the policy that determines whether prune and for_each_cell (and the methods built on top of it) shrink
+    /// the capacity after removing the slots of dropped elements.
+    pub fn set_compaction_policy(&mut self, policy: CompactionPolicy) {
+        self.compaction_policy = policy;
+    }
+
     pub fn for_each_cell<F: FnMut(Rc<RefCell<T>>) -> bool>(&mut self, mut closure: F) {
-        self.vec.drain_filter(|weak_cell| {
+        self.vec.retain(|weak_cell| {
             match weak_cell.upgrade() {
                 Some(cell) => {
-                    closure(cell)
+                    !closure(cell)
                 }, None => {
-                    true
+                    false
                 }
             }
         });
+        compact(&mut self.vec, self.compaction_policy);
     }
 
     pub fn for_each<F: FnMut(&T)>(&mut self, mut closure: F) {
@@ -51,11 +174,235 @@ impl<T: ?Sized> WeakVec<T> {
             false
         });
     }
+
+    /// Like for_each_cell, but visits the elements in reverse order (the element that was pushed last comes first).
+    /// This is useful for input dispatching, which should reach the topmost component first, while rendering uses
+    /// the normal order.
+    pub fn for_each_cell_rev<F: FnMut(Rc<RefCell<T>>) -> bool>(&mut self, mut closure: F) {
+        // Decide first and remove afterwards, since removing during the loop would take quadratic time
+        let mut keep = vec![false; self.vec.len()];
+        for (index, weak_cell) in self.vec.iter().enumerate().rev() {
+            if let Some(cell) = weak_cell.upgrade() {
+                keep[index] = !closure(cell);
+            }
+        }
+        let mut keep = keep.into_iter();
+        self.vec.retain(|_| keep.next().expect("There is a keep flag for every slot"));
+        compact(&mut self.vec, self.compaction_policy);
+    }
+
+    /// Like for_each, but visits the elements in reverse order.
+    pub fn for_each_rev<F: FnMut(&T)>(&mut self, mut closure: F) {
+        self.for_each_cell_rev(|cell| {
+            let borrowed = cell.borrow();
+            closure(&borrowed);
+            false
+        });
+    }
+
+    /// Like for_each_mut, but visits the elements in reverse order.
+    pub fn for_each_mut_rev<F: FnMut(&mut T)>(&mut self, mut closure: F) {
+        self.for_each_cell_rev(|cell| {
+            let mut borrowed = cell.borrow_mut();
+            closure(&mut borrowed);
+            false
+        });
+    }
+
+    /// Like for_each_cell, but stops as soon as the closure returns an error, and returns that error. The elements after
+    /// the failing element will not be visited (and their slots won't be pruned). Slots of elements that have been
+    /// dropped are removed, but the closure can't remove elements.
+    pub fn try_for_each_cell<E, F: FnMut(Rc<RefCell<T>>) -> Result<(), E>>(&mut self, mut closure: F) -> Result<(), E> {
+        let mut result = Ok(());
+        self.vec.retain(|weak_cell| {
+            if result.is_err() {
+                return true;
+            }
+            match weak_cell.upgrade() {
+                Some(cell) => {
+                    result = closure(cell);
+                    true
+                }, None => {
+                    false
+                }
+            }
+        });
+        result
+    }
+
+    /// Like for_each, but stops as soon as the closure returns an error, and returns that error.
+    pub fn try_for_each<E, F: FnMut(&T) -> Result<(), E>>(&mut self, mut closure: F) -> Result<(), E> {
+        self.try_for_each_cell(|cell| {
+            let borrowed = cell.borrow();
+            closure(&borrowed)
+        })
+    }
+
+    /// Like for_each_mut, but stops as soon as the closure returns an error, and returns that error.
+    pub fn try_for_each_mut<E, F: FnMut(&mut T) -> Result<(), E>>(&mut self, mut closure: F) -> Result<(), E> {
+        self.try_for_each_cell(|cell| {
+            let mut borrowed = cell.borrow_mut();
+            closure(&mut borrowed)
+        })
+    }
+
+    /// Like for_each, but uses try_borrow instead of borrow, so it won't panic when an element is already mutably
+    /// borrowed (which happens when a listener ends up re-entering this WeakVec). The policy determines what happens
+    /// with such elements. BorrowPolicy::Skip ignores them, and the result is always Ok with an empty Vec.
+    /// BorrowPolicy::Defer doesn't visit them either, but returns them in the Ok Vec so the caller can visit them later
+    /// (for instance after the outer borrow has been released). BorrowPolicy::Report stops the iteration at the first
+    /// such element, and returns it as Err.
+    pub fn for_each_try_borrow<F: FnMut(&T)>(&mut self, policy: BorrowPolicy, mut closure: F) -> Result<Vec<Rc<RefCell<T>>>, Rc<RefCell<T>>> {
+        self.for_each_with_policy(policy, |cell| {
+            match cell.try_borrow() {
+                Ok(borrowed) => {
+                    closure(&borrowed);
+                    true
+                }, Err(_) => {
+                    false
+                }
+            }
+        })
+    }
+
+    /// Like for_each_try_borrow, but uses try_borrow_mut to give mutable access to the elements. Note that this also
+    /// fails for elements that are immutably borrowed.
+    pub fn for_each_mut_try_borrow<F: FnMut(&mut T)>(&mut self, policy: BorrowPolicy, mut closure: F) -> Result<Vec<Rc<RefCell<T>>>, Rc<RefCell<T>>> {
+        self.for_each_with_policy(policy, |cell| {
+            match cell.try_borrow_mut() {
+                Ok(mut borrowed) => {
+                    closure(&mut borrowed);
+                    true
+                }, Err(_) => {
+                    false
+                }
+            }
+        })
+    }
+
+    fn for_each_with_policy<F: FnMut(&RefCell<T>) -> bool>(&mut self, policy: BorrowPolicy, mut visit: F) -> Result<Vec<Rc<RefCell<T>>>, Rc<RefCell<T>>> {
+        let mut deferred = Vec::new();
+        self.try_for_each_cell(|cell| {
+            if visit(&cell) {
+                return Ok(());
+            }
+            match policy {
+                BorrowPolicy::Skip => Ok(()),
+                BorrowPolicy::Defer => {
+                    deferred.push(cell);
+                    Ok(())
+                },
+                BorrowPolicy::Report => Err(cell)
+            }
+        })?;
+        Ok(deferred)
+    }
+
+    /// Visits the elements in order until the closure returns true. Returns true if the closure returned true for any
+    /// element (and thus stopped the iteration). This is useful for event dispatching, where the event should stop at
+    /// the first listener that consumes it.
+    pub fn for_each_until<F: FnMut(&T) -> bool>(&mut self, mut closure: F) -> bool {
+        self.try_for_each(|element| if closure(element) { Err(()) } else { Ok(()) }).is_err()
+    }
+
+    /// Like for_each_until, but gives mutable access to the elements.
+    pub fn for_each_mut_until<F: FnMut(&mut T) -> bool>(&mut self, mut closure: F) -> bool {
+        self.try_for_each_mut(|element| if closure(element) { Err(()) } else { Ok(()) }).is_err()
+    }
+
+    /// Returns the first element for which the predicate returns true, or None if there is no such element. The slots
+    /// of dropped elements that are encountered before the match are removed.
+    pub fn find<F: FnMut(&T) -> bool>(&mut self, mut predicate: F) -> Option<Rc<RefCell<T>>> {
+        self.try_for_each_cell(|cell| {
+            let matches = predicate(&cell.borrow());
+            if matches { Err(cell) } else { Ok(()) }
+        }).err()
+    }
+
+    /// Removes all elements for which the given closure returns false (as well as all elements that have been dropped).
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut keep: F) {
+        self.for_each_cell(|cell| {
+            let borrowed = cell.borrow();
+            !keep(&borrowed)
+        });
+    }
+
+    /// Calls the closure for each element of the shared WeakVec that is alive at the start of this call. Unlike for_each,
+    /// this doesn't keep the WeakVec borrowed while the closure runs: it iterates over a snapshot instead. This allows
+    /// the closure (for instance a click handler) to push new elements or remove elements. Elements pushed during the
+    /// iteration will not be visited until the next iteration, whereas elements removed during the iteration may still
+    /// be visited during this one. The slots of dropped elements are removed after the iteration.
+    pub fn for_each_shared<F: FnMut(&T)>(shared: &RefCell<WeakVec<T>>, mut closure: F) {
+        Self::for_each_cell_shared(shared, |cell| closure(&cell.borrow()));
+    }
+
+    /// Like for_each_shared, but gives mutable access to the elements.
+    pub fn for_each_mut_shared<F: FnMut(&mut T)>(shared: &RefCell<WeakVec<T>>, mut closure: F) {
+        Self::for_each_cell_shared(shared, |cell| closure(&mut cell.borrow_mut()));
+    }
+
+    fn for_each_cell_shared<F: FnMut(Rc<RefCell<T>>)>(shared: &RefCell<WeakVec<T>>, closure: F) {
+        let snapshot: Vec<_> = shared.borrow().iter_upgraded().collect();
+        snapshot.into_iter().for_each(closure);
+
+        // The WeakVec may still be borrowed when this is a nested call
+        if let Ok(mut weak_vec) = shared.try_borrow_mut() {
+            weak_vec.prune();
+        }
+    }
+
+    /// Returns an iterator over the elements that are still alive. Unlike for_each, this doesn't remove the slots of
+    /// elements that have been dropped, but it can be combined with the standard iterator adapters.
+    pub fn iter_upgraded(&self) -> impl Iterator<Item = Rc<RefCell<T>>> + '_ {
+        self.vec.iter().filter_map(|weak_cell| weak_cell.upgrade())
+    }
+}
+
+impl<T: ?Sized> Default for WeakVec<T> {
+
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A consuming iterator over the elements of a WeakVec that are still alive.
+pub struct WeakVecIntoIter<T: ?Sized> {
+
+    inner: std::vec::IntoIter<Weak<RefCell<T>>>
+}
+
+impl<T: ?Sized> Iterator for WeakVecIntoIter<T> {
+
+    type Item = Rc<RefCell<T>>;
+
+    fn next(&mut self) -> Option<Rc<RefCell<T>>> {
+        for weak_cell in &mut self.inner {
+            if let Some(cell) = weak_cell.upgrade() {
+                return Some(cell);
+            }
+        }
+        None
+    }
+}
+
+impl<T: ?Sized> IntoIterator for WeakVec<T> {
+
+    type Item = Rc<RefCell<T>>;
+    type IntoIter = WeakVecIntoIter<T>;
+
+    /// Consumes this WeakVec and returns an iterator over the elements that are still alive, skipping the ones that
+    /// have been dropped.
+    fn into_iter(self) -> WeakVecIntoIter<T> {
+        WeakVecIntoIter {
+            inner: self.vec.into_iter()
+        }
+    }
 }
 
 pub struct WeakMetaVec<T: ?Sized, M> {
 
-    pub vec: Vec<WeakMetaHandle<T,M>>
+    vec: Vec<WeakMetaHandle<T,M>>,
+    compaction_policy: CompactionPolicy
 }
 
 pub struct WeakMetaHandle<T: ?Sized, M> {
@@ -68,13 +415,15 @@ impl<T: ?Sized, M> WeakMetaVec<T, M> {
 
     pub fn new() -> Self {
         Self {
-            vec: Vec::new()
+            vec: Vec::new(),
+            compaction_policy: CompactionPolicy::Never
         }
     }
 
     pub fn with_capacity(initial_capacity: usize) -> Self {
         Self {
-            vec: Vec::with_capacity(initial_capacity)
+            vec: Vec::with_capacity(initial_capacity),
+            compaction_policy: CompactionPolicy::Never
         }
     }
 
@@ -85,16 +434,111 @@ impl<T: ?Sized, M> WeakMetaVec<T, M> {
         });
     }
 
+    /// Moves all elements of other (together with their metadata) to the end of this WeakMetaVec, leaving other empty.
+    /// The slots of dropped elements are skipped. Note that the result won't be sorted, even if both were sorted.
+    pub fn append(&mut self, other: &mut WeakMetaVec<T, M>) {
+        other.prune();
+        self.vec.append(&mut other.vec);
+    }
+
+    /// Inserts the element at the position that keeps this WeakMetaVec ordered by ascending metadata, assuming it was
+    /// already ordered. The element is inserted after all elements with equal metadata, so elements with the same
+    /// priority are visited in the order in which they were pushed.
+    pub fn push_sorted(&mut self, weak_cell: Weak<RefCell<T>>, metadata: M) where M: Ord {
+        let index = self.vec.partition_point(|handle| handle.metadata <= metadata);
+        self.vec.insert(index, WeakMetaHandle {
+            weak_cell,
+            metadata
+        });
+    }
+
+    /// Sorts the elements of this WeakMetaVec by their metadata, using the given comparator. The sort is stable, so
+    /// elements with equal metadata keep their relative order.
+    pub fn sort_by_metadata<F: FnMut(&M, &M) -> Ordering>(&mut self, mut compare: F) {
+        self.vec.sort_by(|a, b| compare(&a.metadata, &b.metadata));
+    }
+
+    /// Gets the metadata of the slot at the given index, or None if the index is out of bounds. Note that the indices
+    /// of the slots change when the slots of dropped elements are removed (which happens during each iteration).
+    pub fn get_metadata(&self, index: usize) -> Option<&M> {
+        self.vec.get(index).map(|handle| &handle.metadata)
+    }
+
+    /// Like get_metadata, but gives mutable access to the metadata.
+    pub fn get_metadata_mut(&mut self, index: usize) -> Option<&mut M> {
+        self.vec.get_mut(index).map(|handle| &mut handle.metadata)
+    }
+
+    /// Calls update on the metadata of each element that is still alive and whose metadata satisfies the predicate,
+    /// without borrowing the elements themselves. Returns the number of updated elements.
+    pub fn update_metadata_where<P: FnMut(&M) -> bool, U: FnMut(&mut M)>(&mut self, mut predicate: P, mut update: U) -> usize {
+        let mut counter = 0;
+        for handle in &mut self.vec {
+            if handle.weak_cell.strong_count() > 0 && predicate(&handle.metadata) {
+                update(&mut handle.metadata);
+                counter += 1;
+            }
+        }
+        counter
+    }
+
+    /// Returns true if this WeakMetaVec has a slot that points to the same element as target (using pointer equality).
+    pub fn contains(&self, target: &Weak<RefCell<T>>) -> bool {
+        self.vec.iter().any(|handle| Weak::ptr_eq(&handle.weak_cell, target))
+    }
+
+    /// Returns the number of slots in this WeakMetaVec, including the slots of elements that have been dropped, but not
+    /// yet removed. Use live_count to get the number of elements that are still alive.
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Returns true if this WeakMetaVec has no slots at all. Note that this returns false when all elements have been
+    /// dropped, but not yet removed. Call prune first if that matters.
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// Counts the number of elements that are still alive.
+    pub fn live_count(&self) -> usize {
+        self.vec.iter().filter(|handle| handle.weak_cell.strong_count() > 0).count()
+    }
+
+    /// Removes the slots (and metadata) of all elements that have been dropped. This happens automatically during each
+    /// iteration, but this method can be used to do it without iterating.
+    pub fn prune(&mut self) {
+        self.vec.retain(|handle| handle.weak_cell.strong_count() > 0);
+        compact(&mut self.vec, self.compaction_policy);
+    }
+
+    /// Removes the slots of all elements that have been dropped, and shrinks the capacity as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        self.vec.retain(|handle| handle.weak_cell.strong_count() > 0);
+        self.vec.shrink_to_fit();
+    }
+
+    /// Returns the number of slots this WeakMetaVec can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    /// Sets the policy that determines whether prune and for_each_cell (and the methods built on top of it) shrink
+    /// the capacity after removing the slots of dropped elements.
+    pub fn set_compaction_policy(&mut self, policy: CompactionPolicy) {
+        self.compaction_policy = policy;
+    }
+
     pub fn for_each_cell<F: FnMut(Rc<RefCell<T>>, &mut M) -> bool>(&mut self, mut closure: F) {
-        self.vec.drain_filter(|handle| {
+        self.vec.retain_mut(|handle| {
             match handle.weak_cell.upgrade() {
                 Some(cell) => {
-                    closure(cell, &mut handle.metadata)
+                    !closure(cell, &mut handle.metadata)
                 }, None => {
-                    true
+                    false
                 }
             }
         });
+        compact(&mut self.vec, self.compaction_policy);
     }
 
     pub fn for_each<F: FnMut(&T, &M)>(&mut self, mut closure: F) {
@@ -112,6 +556,150 @@ impl<T: ?Sized, M> WeakMetaVec<T, M> {
             false
         });
     }
+
+    /// Like for_each_cell, but visits the elements in reverse order (the last element comes first).
+    pub fn for_each_cell_rev<F: FnMut(Rc<RefCell<T>>, &mut M) -> bool>(&mut self, mut closure: F) {
+        // Decide first and remove afterwards, since removing during the loop would take quadratic time
+        let mut keep = vec![false; self.vec.len()];
+        for (index, handle) in self.vec.iter_mut().enumerate().rev() {
+            if let Some(cell) = handle.weak_cell.upgrade() {
+                keep[index] = !closure(cell, &mut handle.metadata);
+            }
+        }
+        let mut keep = keep.into_iter();
+        self.vec.retain(|_| keep.next().expect("There is a keep flag for every slot"));
+        compact(&mut self.vec, self.compaction_policy);
+    }
+
+    /// Like for_each, but visits the elements in reverse order.
+    pub fn for_each_rev<F: FnMut(&T, &M)>(&mut self, mut closure: F) {
+        self.for_each_cell_rev(|cell, meta| {
+            let borrowed = cell.borrow();
+            closure(&borrowed, meta);
+            false
+        });
+    }
+
+    /// Like for_each_mut, but visits the elements in reverse order.
+    pub fn for_each_mut_rev<F: FnMut(&mut T, &mut M)>(&mut self, mut closure: F) {
+        self.for_each_cell_rev(|cell, meta| {
+            let mut borrowed = cell.borrow_mut();
+            closure(&mut borrowed, meta);
+            false
+        });
+    }
+
+    /// Like for_each_cell, but stops as soon as the closure returns an error, and returns that error. The elements after
+    /// the failing element will not be visited (and their slots won't be pruned). Slots of elements that have been
+    /// dropped are removed, but the closure can't remove elements.
+    pub fn try_for_each_cell<E, F: FnMut(Rc<RefCell<T>>, &mut M) -> Result<(), E>>(&mut self, mut closure: F) -> Result<(), E> {
+        let mut result = Ok(());
+        self.vec.retain_mut(|handle| {
+            if result.is_err() {
+                return true;
+            }
+            match handle.weak_cell.upgrade() {
+                Some(cell) => {
+                    result = closure(cell, &mut handle.metadata);
+                    true
+                }, None => {
+                    false
+                }
+            }
+        });
+        result
+    }
+
+    /// Like for_each, but stops as soon as the closure returns an error, and returns that error.
+    pub fn try_for_each<E, F: FnMut(&T, &M) -> Result<(), E>>(&mut self, mut closure: F) -> Result<(), E> {
+        self.try_for_each_cell(|cell, meta| {
+            let borrowed = cell.borrow();
+            closure(&borrowed, meta)
+        })
+    }
+
+    /// Like for_each_mut, but stops as soon as the closure returns an error, and returns that error.
+    pub fn try_for_each_mut<E, F: FnMut(&mut T, &mut M) -> Result<(), E>>(&mut self, mut closure: F) -> Result<(), E> {
+        self.try_for_each_cell(|cell, meta| {
+            let mut borrowed = cell.borrow_mut();
+            closure(&mut borrowed, meta)
+        })
+    }
+
+    /// Visits the elements in order until the closure returns true. Returns true if the closure returned true for any
+    /// element (and thus stopped the iteration). This is useful for event dispatching, where the event should stop at
+    /// the first listener that consumes it.
+    pub fn for_each_until<F: FnMut(&T, &M) -> bool>(&mut self, mut closure: F) -> bool {
+        self.try_for_each(|element, meta| if closure(element, meta) { Err(()) } else { Ok(()) }).is_err()
+    }
+
+    /// Like for_each_until, but gives mutable access to the elements and their metadata.
+    pub fn for_each_mut_until<F: FnMut(&mut T, &mut M) -> bool>(&mut self, mut closure: F) -> bool {
+        self.try_for_each_mut(|element, meta| if closure(element, meta) { Err(()) } else { Ok(()) }).is_err()
+    }
+
+    /// Returns the first element whose metadata satisfies the predicate, or None if there is no such element. Only
+    /// elements that are still alive are considered. Unlike find, this doesn't need to borrow the elements themselves.
+    pub fn find_by_meta<F: FnMut(&M) -> bool>(&mut self, mut predicate: F) -> Option<Rc<RefCell<T>>> {
+        self.try_for_each_cell(|cell, meta| if predicate(meta) { Err(cell) } else { Ok(()) }).err()
+    }
+
+    /// Removes all elements for which the given closure returns false (as well as all elements that have been dropped).
+    /// The closure also gets the metadata of each element.
+    pub fn retain<F: FnMut(&T, &M) -> bool>(&mut self, mut keep: F) {
+        self.for_each_cell(|cell, meta| {
+            let borrowed = cell.borrow();
+            !keep(&borrowed, meta)
+        });
+    }
+
+    /// Returns an iterator over the elements that are still alive, together with their metadata. Unlike for_each, this
+    /// doesn't remove the slots of elements that have been dropped, but it can be combined with the standard iterator
+    /// adapters.
+    pub fn iter_upgraded(&self) -> impl Iterator<Item = (Rc<RefCell<T>>, &M)> + '_ {
+        self.vec.iter().filter_map(|handle| handle.weak_cell.upgrade().map(|cell| (cell, &handle.metadata)))
+    }
+}
+
+impl<T: ?Sized, M> Default for WeakMetaVec<T, M> {
+
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A consuming iterator over the elements of a WeakMetaVec that are still alive, together with their metadata.
+pub struct WeakMetaVecIntoIter<T: ?Sized, M> {
+
+    inner: std::vec::IntoIter<WeakMetaHandle<T,M>>
+}
+
+impl<T: ?Sized, M> Iterator for WeakMetaVecIntoIter<T, M> {
+
+    type Item = (Rc<RefCell<T>>, M);
+
+    fn next(&mut self) -> Option<(Rc<RefCell<T>>, M)> {
+        for handle in &mut self.inner {
+            if let Some(cell) = handle.weak_cell.upgrade() {
+                return Some((cell, handle.metadata));
+            }
+        }
+        None
+    }
+}
+
+impl<T: ?Sized, M> IntoIterator for WeakMetaVec<T, M> {
+
+    type Item = (Rc<RefCell<T>>, M);
+    type IntoIter = WeakMetaVecIntoIter<T, M>;
+
+    /// Consumes this WeakMetaVec and returns an iterator over the elements that are still alive (and their metadata),
+    /// skipping the ones that have been dropped.
+    fn into_iter(self) -> WeakMetaVecIntoIter<T, M> {
+        WeakMetaVecIntoIter {
+            inner: self.vec.into_iter()
+        }
+    }
 }
 
 #[cfg(test)]
@@ -258,4 +846,562 @@ mod tests {
         });
         assert_eq!("aeeeceee".to_string(), test_string);
     }
+
+    #[test]
+    fn test_len_and_prune() {
+
+        let mut vec = WeakVec::new();
+        assert!(vec.is_empty());
+        assert_eq!(0, vec.live_count());
+
+        let persistent = Rc::new(RefCell::new(1));
+        let vanish = Rc::new(RefCell::new(2));
+        vec.push(Rc::downgrade(&persistent));
+        vec.push(Rc::downgrade(&vanish));
+        assert_eq!(2, vec.len());
+        assert_eq!(2, vec.live_count());
+
+        drop(vanish);
+        assert_eq!(2, vec.len());
+        assert_eq!(1, vec.live_count());
+        assert!(!vec.is_empty());
+
+        vec.prune();
+        assert_eq!(1, vec.len());
+        assert_eq!(1, vec.live_count());
+
+        drop(persistent);
+        vec.prune();
+        assert!(vec.is_empty());
+    }
+
+    #[test]
+    fn test_meta_len_and_prune() {
+
+        let mut vec = WeakMetaVec::new();
+
+        let persistent = Rc::new(RefCell::new(1));
+        let vanish = Rc::new(RefCell::new(2));
+        vec.push(Rc::downgrade(&persistent), 'a');
+        vec.push(Rc::downgrade(&vanish), 'b');
+        assert_eq!(2, vec.len());
+        assert_eq!(2, vec.live_count());
+
+        drop(vanish);
+        assert_eq!(1, vec.live_count());
+
+        vec.prune();
+        assert_eq!(1, vec.len());
+        assert_eq!(Some(&'a'), vec.get_metadata(0));
+    }
+
+    #[test]
+    fn test_retain() {
+
+        let mut vec = WeakVec::new();
+
+        let numbers: Vec<_> = (1..=6).map(|number| Rc::new(RefCell::new(number))).collect();
+        for number in &numbers {
+            vec.push(Rc::downgrade(number));
+        }
+
+        vec.retain(|number| number % 2 == 0);
+        assert_eq!(3, vec.len());
+
+        let mut sum = 0;
+        vec.for_each(|number| {
+            sum += number;
+        });
+        assert_eq!(12, sum);
+    }
+
+    #[test]
+    fn test_meta_retain() {
+
+        let mut vec = WeakMetaVec::new();
+
+        let numbers: Vec<_> = (1..=6).map(|number| Rc::new(RefCell::new(number))).collect();
+        for number in &numbers {
+            vec.push(Rc::downgrade(number), number.borrow().to_string());
+        }
+
+        vec.retain(|number, name| *number < 3 || name == "5");
+        assert_eq!(3, vec.len());
+
+        let mut names = String::new();
+        vec.for_each(|_, name| {
+            names.push_str(name);
+        });
+        assert_eq!("125", names);
+    }
+
+    #[test]
+    fn test_iter_upgraded() {
+
+        let mut vec = WeakVec::new();
+
+        let persistent1 = Rc::new(RefCell::new(1));
+        let vanish = Rc::new(RefCell::new(2));
+        let persistent2 = Rc::new(RefCell::new(4));
+
+        vec.push(Rc::downgrade(&persistent1));
+        vec.push(Rc::downgrade(&vanish));
+        vec.push(Rc::downgrade(&persistent2));
+        drop(vanish);
+
+        let numbers: Vec<i32> = vec.iter_upgraded().map(|cell| *cell.borrow()).collect();
+        assert_eq!(vec![1, 4], numbers);
+        assert_eq!(3, vec.len());
+
+        let result: Result<i32, String> = vec.iter_upgraded().map(|cell| {
+            let number = *cell.borrow();
+            if number < 3 { Ok(number) } else { Err(format!("{} is too big", number)) }
+        }).sum();
+        assert_eq!(Err("4 is too big".to_string()), result);
+
+        let consumed: Vec<i32> = vec.into_iter().map(|cell| *cell.borrow()).collect();
+        assert_eq!(vec![1, 4], consumed);
+    }
+
+    #[test]
+    fn test_meta_iter_upgraded() {
+
+        let mut vec = WeakMetaVec::new();
+
+        let persistent = Rc::new(RefCell::new(1));
+        let vanish = Rc::new(RefCell::new(2));
+
+        vec.push(Rc::downgrade(&persistent), 'a');
+        vec.push(Rc::downgrade(&vanish), 'b');
+        drop(vanish);
+
+        let pairs: Vec<(i32, char)> = vec.iter_upgraded().map(|(cell, meta)| (*cell.borrow(), *meta)).collect();
+        assert_eq!(vec![(1, 'a')], pairs);
+
+        let consumed: Vec<(i32, char)> = vec.into_iter().map(|(cell, meta)| (*cell.borrow(), meta)).collect();
+        assert_eq!(vec![(1, 'a')], consumed);
+    }
+
+    #[test]
+    fn test_remove() {
+
+        let mut vec = WeakVec::new();
+
+        let first = Rc::new(RefCell::new(1));
+        let second = Rc::new(RefCell::new(1));
+        vec.push(Rc::downgrade(&first));
+        vec.push(Rc::downgrade(&second));
+
+        // Both elements are equal, but only the second one should be removed
+        assert!(vec.remove(&Rc::downgrade(&second)));
+        assert!(!vec.remove(&Rc::downgrade(&second)));
+        assert_eq!(1, vec.len());
+        assert!(Rc::ptr_eq(&first, &vec.iter_upgraded().next().unwrap()));
+    }
+
+    #[test]
+    fn test_remove_dyn() {
+
+        let mut vec: WeakVec<dyn FnMut()> = WeakVec::new();
+
+        let listener1: Rc<RefCell<dyn FnMut()>> = Rc::new(RefCell::new(|| {}));
+        let listener2: Rc<RefCell<dyn FnMut()>> = Rc::new(RefCell::new(|| {}));
+        vec.push(Rc::downgrade(&listener1));
+        vec.push(Rc::downgrade(&listener2));
+
+        assert!(vec.remove(&Rc::downgrade(&listener1)));
+        assert_eq!(1, vec.len());
+    }
+
+    #[test]
+    fn test_sort_by_metadata() {
+
+        let mut vec = WeakMetaVec::new();
+
+        let elements: Vec<_> = "abcde".chars().map(|name| Rc::new(RefCell::new(name))).collect();
+        for (element, z_index) in elements.iter().zip(&[3, 1, 2, 1, 0]) {
+            vec.push(Rc::downgrade(element), *z_index);
+        }
+
+        vec.sort_by_metadata(|a, b| a.cmp(b));
+        let mut names = String::new();
+        vec.for_each(|name, _| names.push(*name));
+        assert_eq!("ebdca", names);
+
+        vec.sort_by_metadata(|a, b| b.cmp(a));
+        let mut names = String::new();
+        vec.for_each(|name, _| names.push(*name));
+        assert_eq!("acbde", names);
+    }
+
+    #[test]
+    fn test_push_sorted() {
+
+        let mut vec = WeakMetaVec::new();
+
+        let elements: Vec<_> = "abcde".chars().map(|name| Rc::new(RefCell::new(name))).collect();
+        for (element, priority) in elements.iter().zip(&[3, 1, 2, 1, 0]) {
+            vec.push_sorted(Rc::downgrade(element), *priority);
+        }
+
+        let mut names = String::new();
+        vec.for_each(|name, _| names.push(*name));
+        assert_eq!("ebdca", names);
+    }
+
+    #[test]
+    fn test_find() {
+
+        let mut vec = WeakVec::new();
+
+        let numbers: Vec<_> = (1..=4).map(|number| Rc::new(RefCell::new(number))).collect();
+        for number in &numbers {
+            vec.push(Rc::downgrade(number));
+        }
+
+        let even = vec.find(|number| number % 2 == 0).unwrap();
+        assert!(Rc::ptr_eq(&numbers[1], &even));
+        assert!(vec.find(|number| *number > 4).is_none());
+    }
+
+    #[test]
+    fn test_find_by_meta() {
+
+        let mut vec = WeakMetaVec::new();
+
+        let elements: Vec<_> = "abc".chars().map(|name| Rc::new(RefCell::new(name))).collect();
+        for (element, id) in elements.iter().zip(&[10, 20, 30]) {
+            vec.push(Rc::downgrade(element), *id);
+        }
+
+        assert_eq!('c', *vec.find_by_meta(|id| *id == 30).unwrap().borrow());
+        assert!(vec.find_by_meta(|id| *id == 40).is_none());
+    }
+
+    #[test]
+    fn test_compaction() {
+
+        let mut vec = WeakVec::new();
+
+        let numbers: Vec<_> = (0..100).map(|number| Rc::new(RefCell::new(number))).collect();
+        for number in &numbers {
+            vec.push(Rc::downgrade(number));
+        }
+        let full_capacity = vec.capacity();
+        let survivors: Vec<_> = numbers.into_iter().take(10).collect();
+
+        // The default policy never shrinks
+        vec.for_each(|_| {});
+        assert_eq!(full_capacity, vec.capacity());
+
+        vec.set_compaction_policy(CompactionPolicy::BelowLiveFraction(0.25));
+        vec.for_each(|_| {});
+        assert_eq!(10, vec.len());
+        assert!(vec.capacity() < full_capacity);
+        assert!(vec.capacity() >= 20);
+
+        drop(survivors);
+        vec.shrink_to_fit();
+        assert_eq!(0, vec.capacity());
+    }
+
+    #[test]
+    fn test_for_each_rev() {
+
+        let mut vec = WeakVec::new();
+
+        let mut numbers: Vec<_> = (1..=4).map(|number| Rc::new(RefCell::new(number))).collect();
+        for number in &numbers {
+            vec.push(Rc::downgrade(number));
+        }
+        numbers.remove(2);
+
+        let mut visited = Vec::new();
+        vec.for_each_rev(|number| visited.push(*number));
+        assert_eq!(vec![4, 2, 1], visited);
+        assert_eq!(3, vec.len());
+
+        vec.for_each_cell_rev(|cell| *cell.borrow() == 2);
+        let mut visited = Vec::new();
+        vec.for_each(|number| visited.push(*number));
+        assert_eq!(vec![1, 4], visited);
+    }
+
+    #[test]
+    fn test_meta_for_each_rev() {
+
+        let mut vec = WeakMetaVec::new();
+
+        let elements: Vec<_> = "abc".chars().map(|name| Rc::new(RefCell::new(name))).collect();
+        for (index, element) in elements.iter().enumerate() {
+            vec.push(Rc::downgrade(element), index);
+        }
+
+        let mut visited = String::new();
+        vec.for_each_mut_rev(|name, index| {
+            visited.push(*name);
+            *index *= 10;
+        });
+        assert_eq!("cba", visited);
+
+        let mut indices = Vec::new();
+        vec.for_each(|_, index| indices.push(*index));
+        assert_eq!(vec![0, 10, 20], indices);
+    }
+
+    #[test]
+    fn test_for_each_try_borrow() {
+
+        let mut vec = WeakVec::new();
+
+        let numbers: Vec<_> = (1..=3).map(|number| Rc::new(RefCell::new(number))).collect();
+        for number in &numbers {
+            vec.push(Rc::downgrade(number));
+        }
+
+        // Simulate a listener that is currently running and thus mutably borrowed
+        let busy = numbers[1].borrow_mut();
+
+        let mut visited = Vec::new();
+        assert!(vec.for_each_try_borrow(BorrowPolicy::Skip, |number| visited.push(*number)).unwrap().is_empty());
+        assert_eq!(vec![1, 3], visited);
+
+        let mut visited = Vec::new();
+        let deferred = vec.for_each_mut_try_borrow(BorrowPolicy::Defer, |number| visited.push(*number)).unwrap();
+        assert_eq!(vec![1, 3], visited);
+        assert_eq!(1, deferred.len());
+        assert!(Rc::ptr_eq(&numbers[1], &deferred[0]));
+
+        let mut visited = Vec::new();
+        let conflict = vec.for_each_try_borrow(BorrowPolicy::Report, |number| visited.push(*number)).unwrap_err();
+        assert_eq!(vec![1], visited);
+        assert!(Rc::ptr_eq(&numbers[1], &conflict));
+
+        drop(busy);
+        assert!(vec.for_each_try_borrow(BorrowPolicy::Report, |_| {}).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_contains() {
+
+        let mut vec = WeakVec::new();
+        let mut meta_vec = WeakMetaVec::new();
+
+        let registered = Rc::new(RefCell::new(1));
+        let other = Rc::new(RefCell::new(1));
+        vec.push(Rc::downgrade(&registered));
+        meta_vec.push(Rc::downgrade(&registered), ());
+
+        assert!(vec.contains(&Rc::downgrade(&registered)));
+        assert!(!vec.contains(&Rc::downgrade(&other)));
+        assert!(meta_vec.contains(&Rc::downgrade(&registered)));
+        assert!(!meta_vec.contains(&Rc::downgrade(&other)));
+    }
+
+    #[test]
+    fn test_metadata_accessors() {
+
+        let mut vec = WeakMetaVec::new();
+
+        let elements: Vec<_> = (1..=3).map(|number| Rc::new(RefCell::new(number))).collect();
+        for (element, region) in elements.iter().zip(&[10, 20, 30]) {
+            vec.push(Rc::downgrade(element), *region);
+        }
+
+        assert_eq!(Some(&20), vec.get_metadata(1));
+        assert_eq!(None, vec.get_metadata(3));
+        *vec.get_metadata_mut(0).unwrap() = 15;
+        assert_eq!(Some(&15), vec.get_metadata(0));
+
+        assert_eq!(2, vec.update_metadata_where(|region| *region >= 20, |region| *region += 1));
+        let mut regions = Vec::new();
+        vec.for_each(|_, region| regions.push(*region));
+        assert_eq!(vec![15, 21, 31], regions);
+    }
+
+    #[test]
+    fn test_positional() {
+
+        let mut vec = WeakVec::new();
+
+        let mut tabs: Vec<_> = "abcd".chars().map(|name| Rc::new(RefCell::new(name))).collect();
+        for tab in &tabs {
+            vec.push(Rc::downgrade(tab));
+        }
+
+        assert_eq!('c', *vec.get(2).unwrap().borrow());
+        assert!(vec.get(4).is_none());
+        tabs.remove(1);
+        assert!(vec.get(1).is_none());
+
+        let removed = vec.swap_remove(0);
+        assert_eq!('a', *removed.upgrade().unwrap().borrow());
+        assert_eq!('d', *vec.get(0).unwrap().borrow());
+
+        vec.truncate(1);
+        assert_eq!(1, vec.len());
+        assert_eq!('d', *vec.get(0).unwrap().borrow());
+    }
+
+    #[test]
+    fn test_push_during_iteration() {
+
+        let shared = Rc::new(RefCell::new(WeakVec::new()));
+
+        let spawned = Rc::new(RefCell::new(Vec::new()));
+        let first = Rc::new(RefCell::new(1));
+        shared.borrow_mut().push(Rc::downgrade(&first));
+
+        let mut visited = Vec::new();
+        WeakVec::for_each_shared(&shared, |number| {
+            visited.push(*number);
+            let new_element = Rc::new(RefCell::new(number + 1));
+            shared.borrow_mut().push(Rc::downgrade(&new_element));
+            spawned.borrow_mut().push(new_element);
+        });
+        assert_eq!(vec![1], visited);
+        assert_eq!(2, shared.borrow().len());
+
+        let mut visited = Vec::new();
+        WeakVec::for_each_mut_shared(&shared, |number| {
+            *number *= 10;
+            visited.push(*number);
+        });
+        assert_eq!(vec![10, 20], visited);
+    }
+
+    #[test]
+    fn test_append() {
+
+        let mut parent = WeakVec::new();
+        let mut child = WeakVec::new();
+
+        let numbers: Vec<_> = (1..=3).map(|number| Rc::new(RefCell::new(number))).collect();
+        let vanish = Rc::new(RefCell::new(0));
+        parent.push(Rc::downgrade(&numbers[0]));
+        child.push(Rc::downgrade(&numbers[1]));
+        child.push(Rc::downgrade(&vanish));
+        child.push(Rc::downgrade(&numbers[2]));
+        drop(vanish);
+
+        parent.append(&mut child);
+        assert!(child.is_empty());
+        assert_eq!(3, parent.len());
+
+        let mut visited = Vec::new();
+        parent.for_each(|number| visited.push(*number));
+        assert_eq!(vec![1, 2, 3], visited);
+    }
+
+    #[test]
+    fn test_meta_append() {
+
+        let mut parent = WeakMetaVec::new();
+        let mut child = WeakMetaVec::new();
+
+        let elements: Vec<_> = "ab".chars().map(|name| Rc::new(RefCell::new(name))).collect();
+        parent.push(Rc::downgrade(&elements[0]), 1);
+        child.push(Rc::downgrade(&elements[1]), 2);
+
+        parent.append(&mut child);
+        assert!(child.is_empty());
+        assert_eq!(Some(&2), parent.get_metadata(1));
+    }
+
+    #[test]
+    fn test_try_for_each() {
+
+        let mut vec = WeakVec::new();
+
+        let numbers: Vec<_> = (1..=5).map(|number| Rc::new(RefCell::new(number))).collect();
+        for number in &numbers {
+            vec.push(Rc::downgrade(number));
+        }
+
+        let mut visited = Vec::new();
+        let result = vec.try_for_each(|number| {
+            visited.push(*number);
+            if *number == 3 { Err("three") } else { Ok(()) }
+        });
+        assert_eq!(Err("three"), result);
+        assert_eq!(vec![1, 2, 3], visited);
+
+        let result: Result<(), ()> = vec.try_for_each_mut(|number| {
+            *number *= 2;
+            Ok(())
+        });
+        assert_eq!(Ok(()), result);
+        assert_eq!(30, numbers.iter().map(|number| *number.borrow()).sum::<i32>());
+    }
+
+    #[test]
+    fn test_meta_try_for_each() {
+
+        let mut vec = WeakMetaVec::new();
+
+        let persistent = Rc::new(RefCell::new(1));
+        let vanish = Rc::new(RefCell::new(2));
+        let failing = Rc::new(RefCell::new(3));
+        vec.push(Rc::downgrade(&persistent), 'a');
+        vec.push(Rc::downgrade(&vanish), 'b');
+        vec.push(Rc::downgrade(&failing), 'c');
+        drop(vanish);
+
+        let result = vec.try_for_each(|_, meta| {
+            if *meta == 'c' { Err(*meta) } else { Ok(()) }
+        });
+        assert_eq!(Err('c'), result);
+        assert_eq!(2, vec.len());
+
+        let result = vec.try_for_each_mut(|number, meta| {
+            *meta = 'd';
+            Err(*number)
+        });
+        assert_eq!(Err(1), result);
+        assert_eq!(Some(&'d'), vec.get_metadata(0));
+        assert_eq!(Some(&'c'), vec.get_metadata(1));
+    }
+
+    #[test]
+    fn test_for_each_until() {
+
+        let mut vec = WeakVec::new();
+
+        let numbers: Vec<_> = (1..=5).map(|number| Rc::new(RefCell::new(number))).collect();
+        for number in &numbers {
+            vec.push(Rc::downgrade(number));
+        }
+
+        let mut visited = 0;
+        assert!(vec.for_each_until(|number| {
+            visited += 1;
+            *number == 2
+        }));
+        assert_eq!(2, visited);
+
+        assert!(!vec.for_each_mut_until(|number| {
+            *number += 1;
+            false
+        }));
+        assert_eq!(20, numbers.iter().map(|number| *number.borrow()).sum::<i32>());
+    }
+
+    #[test]
+    fn test_meta_for_each_until() {
+
+        let mut vec = WeakMetaVec::new();
+
+        let elements: Vec<_> = (1..=3).map(|number| Rc::new(RefCell::new(number))).collect();
+        for element in &elements {
+            vec.push(Rc::downgrade(element), false);
+        }
+
+        assert!(vec.for_each_mut_until(|number, consumed| {
+            *consumed = true;
+            *number == 2
+        }));
+        assert_eq!(Some(&true), vec.get_metadata(1));
+        assert_eq!(Some(&false), vec.get_metadata(2));
+        assert!(!vec.for_each_until(|_, _| false));
+    }
 }
\ No newline at end of file